@@ -4,6 +4,7 @@ import {
   Poseidon,
   getProgramId,
   BoundedStrategyV2,
+  encodeAdditionalData,
 } from "@mithraic-labs/poseidon";
 import config from "./config";
 import NodeWallet from "@project-serum/anchor/dist/cjs/nodewallet";
@@ -56,7 +57,7 @@ const connection = new Connection(config.jsonRpcUrl);
       } else {
         // get all the accounts needed for this trade, in accordance with the max allowed price
         console.log("getting quote for ", boundedStrategy.publicKey);
-        const { remainingAccounts, additionalData } = await getQuote({
+        const { remainingAccounts, legParams } = await getQuote({
           boundedStrategy: strategy,
           connection,
           payer,
@@ -71,7 +72,14 @@ const connection = new Connection(config.jsonRpcUrl);
           });
 
           const ix = await program.methods
-            .boundedTradeV2(Buffer.from(additionalData))
+            .boundedTradeV2(
+              encodeAdditionalData({
+                inputHint: null,
+                searchIterations: 0,
+                legs: legParams,
+                branchLegCounts: [],
+              })
+            )
             .accounts({
               payer: payer.publicKey,
               strategy: boundedStrategy.publicKey,
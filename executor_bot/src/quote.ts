@@ -7,6 +7,7 @@ import {
 } from "@solana/web3.js";
 import {
   BoundedStrategyV2,
+  LegParams,
   raydiumTradeAccts,
   openbookData,
 } from "@mithraic-labs/poseidon";
@@ -42,7 +43,7 @@ export const getQuote = async ({
   payer: Keypair;
 }) => {
   let remainingAccounts = [] as AccountMeta[];
-  let additionalData = [];
+  let legParams: LegParams[] = [];
   const [collateralAccountBuff, depositAccountBuff] =
     await connection.getMultipleAccountsInfo([
       collateralAccount,
@@ -71,7 +72,7 @@ export const getQuote = async ({
     console.log("Empty collateral account (trade has been executed)...");
 
     return {
-      additionalData,
+      legParams,
       remainingAccounts,
     };
   }
@@ -165,13 +166,14 @@ export const getQuote = async ({
                 (marketInfo.amm as any).serumProgramId
               );
               remainingAccounts.push(...raydiumRemainingAccounts);
+              legParams.push({ raydium: {} });
               break;
             default:
             case "Openbook":
               if (
                 marketInfo.minInAmount < JSBI.BigInt(Number(collateral.amount))
               ) {
-                const { additionalData: aData, remainingAccounts: rAccounts } =
+                const { legParams: legParam, remainingAccounts: rAccounts } =
                   await openbookData(
                     connection,
                     ammId,
@@ -181,12 +183,12 @@ export const getQuote = async ({
                     // jupiter doesn't have devnet routes
                     "mainnet-beta"
                   );
-                additionalData.push(aData.values());
+                legParams.push(legParam);
                 remainingAccounts.push(...rAccounts);
                 break;
               } else {
                 remainingAccounts = [];
-                additionalData = [];
+                legParams = [];
               }
           }
 
@@ -194,19 +196,19 @@ export const getQuote = async ({
         }
         if (!remainingAccounts.length) continue;
         return {
-          additionalData,
+          legParams,
           remainingAccounts,
         };
       }
     }
     return {
-      additionalData,
+      legParams,
       remainingAccounts,
     };
   } catch (error) {
     console.error({ error });
     return {
-      additionalData: [],
+      legParams: [],
       remainingAccounts: [],
     };
   }
@@ -1,7 +1,7 @@
-import { BN, web3 } from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
 import { Market } from "@project-serum/serum";
 import { getProgramId } from "../utils";
-import { SolCluster } from "../types";
+import { LegParams, SolCluster } from "../types";
 import { programId as OB_PID } from "./openBookDex";
 import {} from "./raydium";
 import {
@@ -31,11 +31,11 @@ export const getTradeAccounts = async (
   const openBookProgramId = OB_PID(cluster);
 
   const res: web3.AccountMeta[][] = [];
-  const additionalDataArr: Buffer[] = [];
+  const legParams: LegParams[] = [];
   await Promise.all(
     accountInfos.map(async (acct, index) => {
       let legAccounts: web3.AccountMeta[] = [];
-      let legAdditionalData: Buffer = Buffer.from([]);
+      let legParam: LegParams;
       // Determine which DEX each market belongs to.
       switch (acct.owner.toString()) {
         case openBookProgramId.toString(): {
@@ -49,10 +49,10 @@ export const getTradeAccounts = async (
           // TODO: Handle creating trade accounts (note OpenOrders account and owner will have
           //  to be checked or created)
 
-          legAdditionalData = new BN(
+          legParam = {
             // @ts-ignore
-            serumMarket._baseSplTokenDecimals
-          ).toArrayLike(Buffer, "le", 1);
+            openBookV3: { baseDecimals: serumMarket._baseSplTokenDecimals },
+          };
           break;
         }
         case LIQUIDITY_PROGRAM_ID_V4.toString(): {
@@ -76,20 +76,19 @@ export const getTradeAccounts = async (
           );
           // TODO: Handle creating trade accounts
 
-          legAdditionalData = Buffer.from([]);
+          legParam = { raydium: {} };
           break;
         }
         default:
           throw new Error("Unknown market owner");
       }
       res[index] = legAccounts;
-      additionalDataArr[index] = legAdditionalData;
+      legParams[index] = legParam;
     })
   );
 
-  const additionalData = Buffer.concat(additionalDataArr);
   return {
     remainingAccounts: res.flat(),
-    additionalData: additionalData,
+    legParams,
   };
 };
@@ -10,7 +10,7 @@ import {
   sendAndConfirmTransaction,
 } from "@solana/web3.js";
 import { BN } from "@coral-xyz/anchor";
-import { SolCluster } from "../types";
+import { LegParams, SolCluster } from "../types";
 
 export const programId = (cluster: SolCluster) => {
   if (cluster === "devnet") {
@@ -138,12 +138,12 @@ export const openbookData = async (
     payer.publicKey
   );
 
-  const additionalData = new BN(
+  const legParams: LegParams = {
     // @ts-ignore
-    serumMarketOB._baseSplTokenDecimals
-  ).toArrayLike(Buffer, "le", 1);
+    openBookV3: { baseDecimals: serumMarketOB._baseSplTokenDecimals },
+  };
   return {
     remainingAccounts,
-    additionalData,
+    legParams,
   };
 };
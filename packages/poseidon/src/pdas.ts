@@ -1,6 +1,11 @@
 import { BN, Program, web3 } from "@coral-xyz/anchor";
 import { Poseidon } from "./poseidon";
-import { BoundedStrategyParams, BoundedStrategyParamsV2 } from "./types";
+import {
+  BoundedStrategyParams,
+  BoundedStrategyParamsV2,
+  GridStrategyParams,
+  RebalanceStrategyParams,
+} from "./types";
 
 const textEncoder = new TextEncoder();
 
@@ -27,6 +32,7 @@ export const deriveBoundedStrategyV2 = (
   mint: web3.PublicKey,
   boundPriceNumerator: BN,
   boundPriceDenominator: BN,
+  startDate: BN,
   reclaimDate: BN
 ) =>
   web3.PublicKey.findProgramAddressSync(
@@ -34,6 +40,10 @@ export const deriveBoundedStrategyV2 = (
       mint.toBuffer(),
       boundPriceNumerator.toArrayLike(Buffer, "le", 8),
       boundPriceDenominator.toArrayLike(Buffer, "le", 8),
+      // Strategies that can trade immediately leave the start date out
+      startDate.isZero()
+        ? Buffer.alloc(0)
+        : startDate.toTwos(64).toArrayLike(Buffer, "le", 8),
       reclaimDate.toArrayLike(Buffer, "le", 8),
       textEncoder.encode("boundedStrategy"),
     ],
@@ -67,6 +77,43 @@ export const deriveOpenOrders = (
     program.programId
   );
 
+export const deriveBasket = (
+  program: Program<Poseidon>,
+  strategy: web3.PublicKey
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [strategy.toBuffer(), textEncoder.encode("basket")],
+    program.programId
+  );
+
+export const deriveDepositSplit = (
+  program: Program<Poseidon>,
+  strategy: web3.PublicKey
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [strategy.toBuffer(), textEncoder.encode("depositSplit")],
+    program.programId
+  );
+
+export const deriveProceedsAccount = (
+  program: Program<Poseidon>,
+  strategy: web3.PublicKey
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [strategy.toBuffer(), textEncoder.encode("proceeds")],
+    program.programId
+  );
+
+export const deriveOcoPair = (
+  program: Program<Poseidon>,
+  takeProfit: web3.PublicKey,
+  stopLoss: web3.PublicKey
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [takeProfit.toBuffer(), stopLoss.toBuffer(), textEncoder.encode("ocoPair")],
+    program.programId
+  );
+
 export const deriveAllBoundedStrategyKeys = (
   program: Program<Poseidon>,
   serumMarket: web3.PublicKey,
@@ -92,19 +139,110 @@ export const deriveAllBoundedStrategyKeysV2 = (
   mint: web3.PublicKey,
   boundedStrategyParams: BoundedStrategyParamsV2
 ) => {
-  const { boundPriceNumerator, boundPriceDenominator, reclaimDate } =
-    boundedStrategyParams;
+  const {
+    boundPriceNumerator,
+    boundPriceDenominator,
+    startDate,
+    reclaimDate,
+  } = boundedStrategyParams;
   const [boundedStrategy] = deriveBoundedStrategyV2(
     program,
     mint,
     boundPriceNumerator,
     boundPriceDenominator,
+    startDate,
     reclaimDate
   );
   const [collateralAccount] = deriveCollateralAccount(program, boundedStrategy);
   return { collateralAccount, boundedStrategy };
 };
 
+export const deriveGridStrategy = (
+  program: Program<Poseidon>,
+  baseMint: web3.PublicKey,
+  quoteMint: web3.PublicKey,
+  lowerPrice: BN,
+  upperPrice: BN,
+  reclaimDate: BN
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [
+      baseMint.toBuffer(),
+      quoteMint.toBuffer(),
+      lowerPrice.toArrayLike(Buffer, "le", 8),
+      upperPrice.toArrayLike(Buffer, "le", 8),
+      reclaimDate.toArrayLike(Buffer, "le", 8),
+      textEncoder.encode("gridStrategy"),
+    ],
+    program.programId
+  );
+
+export const deriveAllGridStrategyKeys = (
+  program: Program<Poseidon>,
+  baseMint: web3.PublicKey,
+  quoteMint: web3.PublicKey,
+  gridStrategyParams: GridStrategyParams
+) => {
+  const { lowerPrice, upperPrice, reclaimDate } = gridStrategyParams;
+  const [gridStrategy] = deriveGridStrategy(
+    program,
+    baseMint,
+    quoteMint,
+    lowerPrice,
+    upperPrice,
+    reclaimDate
+  );
+  const [baseAccount] = web3.PublicKey.findProgramAddressSync(
+    [gridStrategy.toBuffer(), textEncoder.encode("gridBase")],
+    program.programId
+  );
+  const [quoteAccount] = web3.PublicKey.findProgramAddressSync(
+    [gridStrategy.toBuffer(), textEncoder.encode("gridQuote")],
+    program.programId
+  );
+  return { gridStrategy, baseAccount, quoteAccount };
+};
+
+export const deriveRebalanceStrategy = (
+  program: Program<Poseidon>,
+  baseMint: web3.PublicKey,
+  quoteMint: web3.PublicKey,
+  reclaimDate: BN
+) =>
+  web3.PublicKey.findProgramAddressSync(
+    [
+      baseMint.toBuffer(),
+      quoteMint.toBuffer(),
+      reclaimDate.toArrayLike(Buffer, "le", 8),
+      textEncoder.encode("rebalanceStrategy"),
+    ],
+    program.programId
+  );
+
+export const deriveAllRebalanceStrategyKeys = (
+  program: Program<Poseidon>,
+  baseMint: web3.PublicKey,
+  quoteMint: web3.PublicKey,
+  rebalanceStrategyParams: RebalanceStrategyParams
+) => {
+  const { reclaimDate } = rebalanceStrategyParams;
+  const [rebalanceStrategy] = deriveRebalanceStrategy(
+    program,
+    baseMint,
+    quoteMint,
+    reclaimDate
+  );
+  const [baseAccount] = web3.PublicKey.findProgramAddressSync(
+    [rebalanceStrategy.toBuffer(), textEncoder.encode("rebalanceBase")],
+    program.programId
+  );
+  const [quoteAccount] = web3.PublicKey.findProgramAddressSync(
+    [rebalanceStrategy.toBuffer(), textEncoder.encode("rebalanceQuote")],
+    program.programId
+  );
+  return { rebalanceStrategy, baseAccount, quoteAccount };
+};
+
 export const deriveTokenAccount = (
   program: Program<Poseidon>,
   strategyKey: web3.PublicKey,
@@ -35,6 +35,151 @@ export type BoundedStrategyV2 = {
   boundedPriceNumerator: BN;
   boundedPriceDenominator: BN;
   bump: number;
+  priceTiers: PriceTier[];
+  executorFeeBps: number;
+  surplusShareBps: number;
+  minFillInput: BN;
+  maxFillInput: BN;
+  minSlotsBetweenTrades: BN;
+  lastTradeSlot: BN;
+  fillStats: FillStats;
+  extension: StrategyExtension;
+  restingInput: BN;
+  upstream: web3.PublicKey;
+  awaitingUpstream: boolean;
+  startDate: BN;
+};
+
+export type StrategyExtension =
+  | { none: Record<string, never> }
+  | { restingOrders: { openOrders: web3.PublicKey } }
+  | { basket: { basket: web3.PublicKey } }
+  | { depositSplit: { depositSplit: web3.PublicKey } }
+  | { ocoPair: { ocoPair: web3.PublicKey } }
+  | { downstream: { strategy: web3.PublicKey } };
+
+export type Basket = {
+  strategy: web3.PublicKey;
+  outputs: BasketOutput[];
+  bump: number;
+};
+
+export type BasketOutput = {
+  depositAddress: web3.PublicKey;
+  weightBps: number;
+  boundedPriceNumerator: BN;
+  boundedPriceDenominator: BN;
+  allocatedInput: BN;
+  filledInput: BN;
+  filledOutput: BN;
+};
+
+export type DepositSplit = {
+  strategy: web3.PublicKey;
+  recipients: DepositRecipient[];
+  bump: number;
+};
+
+export type DepositRecipient = {
+  address: web3.PublicKey;
+  weightBps: number;
+};
+
+export type OcoPair = {
+  takeProfit: web3.PublicKey;
+  stopLoss: web3.PublicKey;
+  collateralAccount: web3.PublicKey;
+  filledBy: web3.PublicKey;
+  bump: number;
+  stopLossTriggerNumerator: BN;
+  stopLossTriggerDenominator: BN;
+};
+
+export type BasketOutputParams = {
+  weightBps: number;
+  boundedPriceNumerator: BN;
+  boundedPriceDenominator: BN;
+};
+
+export type GridStrategy = {
+  baseMint: web3.PublicKey;
+  quoteMint: web3.PublicKey;
+  baseAccount: web3.PublicKey;
+  quoteAccount: web3.PublicKey;
+  startDate: BN;
+  reclaimDate: BN;
+  baseReclaimAddress: web3.PublicKey;
+  quoteReclaimAddress: web3.PublicKey;
+  lowerPrice: BN;
+  upperPrice: BN;
+  priceDenominator: BN;
+  gridLevels: number;
+  levelSize: BN;
+  soldRanges: number;
+  bump: number;
+};
+
+export type InitGridStrategyParams = {
+  baseTransferAmount: BN;
+  quoteTransferAmount: BN;
+  lowerPrice: BN;
+  upperPrice: BN;
+  priceDenominator: BN;
+  gridLevels: number;
+  levelSize: BN;
+  startDate: BN;
+  reclaimDate: BN;
+  soldRanges: number;
+};
+
+export type RebalanceStrategy = {
+  baseMint: web3.PublicKey;
+  quoteMint: web3.PublicKey;
+  baseAccount: web3.PublicKey;
+  quoteAccount: web3.PublicKey;
+  startDate: BN;
+  reclaimDate: BN;
+  baseReclaimAddress: web3.PublicKey;
+  quoteReclaimAddress: web3.PublicKey;
+  referencePriceNumerator: BN;
+  referencePriceDenominator: BN;
+  targetBaseWeightBps: number;
+  toleranceBps: number;
+  maxDeviationBps: number;
+  bump: number;
+};
+
+export type FillStats = {
+  totalInput: BN;
+  totalOutput: BN;
+  tradeCount: BN;
+  firstTradeTimestamp: BN;
+  lastTradeTimestamp: BN;
+  bestPrice: ExecutionPrice;
+  worstPrice: ExecutionPrice;
+};
+
+export type ExecutionPrice = {
+  input: BN;
+  output: BN;
+};
+
+export type PriceTier = {
+  boundedPriceNumerator: BN;
+  boundedPriceDenominator: BN;
+  remainingAmount: BN;
+};
+
+export type LegParams =
+  | { openBookV3: { baseDecimals: number } }
+  | { raydium: {} };
+
+export type AdditionalData = {
+  inputHint: BN | null;
+  searchIterations: number;
+  legs: LegParams[];
+  /** Legs in each branch of a split route, empty when sequential */
+  branchLegCounts: number[];
 };
 
 export type BoundedStrategyParams = {
@@ -50,5 +195,16 @@ export type BoundedStrategyParams = {
 export type BoundedStrategyParamsV2 = {
   boundPriceNumerator: BN;
   boundPriceDenominator: BN;
+  startDate: BN;
+  reclaimDate: BN;
+};
+
+export type GridStrategyParams = {
+  lowerPrice: BN;
+  upperPrice: BN;
+  reclaimDate: BN;
+};
+
+export type RebalanceStrategyParams = {
   reclaimDate: BN;
 };
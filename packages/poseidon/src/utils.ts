@@ -1,6 +1,6 @@
-import { parseIdlErrors, ProgramError, web3 } from "@coral-xyz/anchor";
+import { BN, parseIdlErrors, ProgramError, web3 } from "@coral-xyz/anchor";
 import { IDL } from "./poseidon";
-import { Bound, OrderSide, SolCluster } from "./types";
+import { AdditionalData, Bound, OrderSide, SolCluster } from "./types";
 
 const idlErrors = parseIdlErrors(IDL);
 
@@ -65,3 +65,34 @@ export const orderSideOptions = (() => {
   }
   return options;
 })();
+
+export const ADDITIONAL_DATA_VERSION = 2;
+
+/**
+ * Encode the versioned `additional_data` envelope passed to boundedTradeV2 and
+ * quoteRoute. This is the version byte followed by the Borsh encoded
+ * AdditionalData.
+ */
+export const encodeAdditionalData = (additionalData: AdditionalData) => {
+  const inputHint = additionalData.inputHint
+    ? Buffer.concat([
+        Buffer.from([1]),
+        new BN(additionalData.inputHint).toArrayLike(Buffer, "le", 8),
+      ])
+    : Buffer.from([0]);
+  const legs = additionalData.legs.map((leg) => {
+    if ("openBookV3" in leg) {
+      return Buffer.from([0, leg.openBookV3.baseDecimals]);
+    }
+    return Buffer.from([1]);
+  });
+  return Buffer.concat([
+    Buffer.from([ADDITIONAL_DATA_VERSION]),
+    inputHint,
+    Buffer.from([additionalData.searchIterations]),
+    new BN(legs.length).toArrayLike(Buffer, "le", 4),
+    ...legs,
+    new BN(additionalData.branchLegCounts.length).toArrayLike(Buffer, "le", 4),
+    Buffer.from(additionalData.branchLegCounts),
+  ]);
+};
@@ -2,3 +2,25 @@ pub const AUTHORITY_SEED: &str = "authority";
 pub const ORDER_PAYER_SEED: &str = "orderPayer";
 pub const BOUNDED_STRATEGY_SEED: &str = "boundedStrategy";
 pub const OPEN_ORDERS_SEED: &str = "openOrders";
+pub const BASKET_SEED: &str = "basket";
+pub const DEPOSIT_SPLIT_SEED: &str = "depositSplit";
+pub const PROCEEDS_SEED: &str = "proceeds";
+pub const OCO_PAIR_SEED: &str = "ocoPair";
+pub const GRID_STRATEGY_SEED: &str = "gridStrategy";
+pub const GRID_BASE_SEED: &str = "gridBase";
+pub const GRID_QUOTE_SEED: &str = "gridQuote";
+pub const REBALANCE_STRATEGY_SEED: &str = "rebalanceStrategy";
+pub const REBALANCE_BASE_SEED: &str = "rebalanceBase";
+pub const REBALANCE_QUOTE_SEED: &str = "rebalanceQuote";
+
+/// Basis points in a whole, used to express fees
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// The number of search iterations used when the executor does not set a budget
+pub const DEFAULT_SEARCH_ITERATIONS: u8 = 16;
+/// The number of chunks a split route's input is divided into when allocating it across branches
+pub const SPLIT_ALLOCATION_STEPS: u64 = 10;
+/// The number of slices of a fill, each half the size of the last, simulated to read the market
+/// price independently of the fill's own slippage
+pub const MARKET_PRICE_SLICES: u32 = 8;
+/// The client order id of a strategy's resting OpenBook order. A strategy rests one order at a time
+pub const RESTING_ORDER_CLIENT_ID: u64 = 1;
@@ -1,8 +1,8 @@
-use std::collections::VecDeque;
-
 use anchor_lang::prelude::*;
 use enum_dispatch::enum_dispatch;
 
+use super::math::{PiecewiseLinearCurve, SwapCurve};
+
 #[enum_dispatch]
 pub trait Dex {
     /// Given the amount of tokens_in, return the amount of tokens returned
@@ -16,6 +16,21 @@ pub trait Dex {
 
     /// Returns the mint of the token being swapped to
     fn end_mint(&self) -> Result<Pubkey>;
+
+    /// Returns the program ID of the DEX the trade is executed on
+    fn program_id(&self) -> Pubkey;
+
+    /// Returns the closed form of the swap's output when the DEX has one. Routes where every leg
+    /// has a curve solve for the maximum input directly instead of searching for it.
+    fn swap_curve(&self) -> Option<SwapCurve> {
+        None
+    }
+
+    /// Returns the output as a piecewise linear curve when the DEX fills level by level. Routes
+    /// where every leg has one solve for the maximum input by walking the levels.
+    fn order_book_curve(&self) -> Option<PiecewiseLinearCurve> {
+        None
+    }
 }
 
 pub trait DexStatic<'a, 'info> {
@@ -25,10 +40,13 @@ pub trait DexStatic<'a, 'info> {
     /// The number of accounts needed for initializing trading on the DEX
     const INIT_ACCOUNTS_LEN: usize;
 
-    /// Create the DEX instance from a slice of account infos.
+    /// The executor supplied parameters for a leg on the DEX
+    type Params;
+
+    /// Create the DEX instance from a slice of account infos and the leg's parameters.
     fn from_account_slice(
         accounts: &'a [AccountInfo<'info>],
-        additional_data: &mut VecDeque<u8>,
+        params: &Self::Params,
     ) -> Result<Self>
     where
         Self: Sized;
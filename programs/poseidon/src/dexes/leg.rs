@@ -1,11 +1,14 @@
-use std::collections::VecDeque;
-
 use anchor_lang::prelude::*;
 use enum_dispatch::enum_dispatch;
 
-use crate::dexes::Dex;
+use crate::{dexes::Dex, errors::ErrorCode, instructions::LegParams};
 
-use super::{open_book_dex::OpenBookDex, raydium::RaydiumSwap, DexList, DexStatic};
+use super::{
+    math::{PiecewiseLinearCurve, SwapCurve},
+    open_book_dex::OpenBookDex,
+    raydium::RaydiumSwap,
+    DexList, DexStatic,
+};
 
 #[enum_dispatch(Dex)]
 pub(crate) enum Leg<'a, 'info> {
@@ -17,17 +20,16 @@ impl<'a, 'info> Leg<'a, 'info> {
     pub fn from_account_slice(
         dex: DexList,
         account_infos: &'a [AccountInfo<'info>],
-        additional_data: &mut VecDeque<u8>,
+        params: &LegParams,
     ) -> Result<Self> {
-        let res = match dex {
-            DexList::OpenBookV3 => Leg::OpenBookV3(OpenBookDex::from_account_slice(
-                account_infos,
-                additional_data,
-            )?),
-            DexList::Raydium => Leg::Raydium(RaydiumSwap::from_account_slice(
-                account_infos,
-                additional_data,
-            )?),
+        let res = match (dex, params) {
+            (DexList::OpenBookV3, LegParams::OpenBookV3(params)) => {
+                Leg::OpenBookV3(OpenBookDex::from_account_slice(account_infos, params)?)
+            }
+            (DexList::Raydium, LegParams::Raydium(params)) => {
+                Leg::Raydium(RaydiumSwap::from_account_slice(account_infos, params)?)
+            }
+            _ => return Err(error!(ErrorCode::LegParamsMismatch)),
         };
 
         Ok(res)
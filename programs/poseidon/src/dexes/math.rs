@@ -1,9 +1,15 @@
 use uint::construct_uint;
 
+use crate::constants::BPS_DENOMINATOR;
+
 construct_uint! {
     pub struct U128(2);
 }
 
+construct_uint! {
+    pub struct U256(4);
+}
+
 /// Multiplies two u64's then divides by a u64.
 pub fn mul_div_u64(a: u64, b: u64, divisor: u64) -> Option<u64> {
     let result = U128::from(a)
@@ -51,6 +57,207 @@ pub fn constant_product_simulation(
     out_pool_balance - new_out_pool_balance
 }
 
+///
+/// The output of a swap as a function of its input, in the form `out = a * x / (b + c * x)`.
+/// Constant product pools, including their input fee, have this shape and so does any chain of
+/// them, which lets the maximum input for a bounded price be solved for directly.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwapCurve {
+    a: U256,
+    b: U256,
+    c: U256,
+}
+
+impl SwapCurve {
+    /// The curve of a constant product pool that takes `fee_numerator / fee_denominator` of the
+    /// input as a fee
+    pub fn constant_product(
+        in_pool_balance: u64,
+        out_pool_balance: u64,
+        fee_numerator: u64,
+        fee_denominator: u64,
+    ) -> Self {
+        let post_fee_factor = U256::from(fee_denominator - fee_numerator);
+        SwapCurve {
+            a: post_fee_factor * U256::from(out_pool_balance),
+            b: U256::from(fee_denominator) * U256::from(in_pool_balance),
+            c: post_fee_factor,
+        }
+    }
+
+    ///
+    /// The curve of swapping through this curve and then through `next`, or None when the
+    /// chained coefficients do not fit, in which case the caller should search for the maximum
+    /// input instead
+    ///
+    pub fn then(&self, next: &SwapCurve) -> Option<Self> {
+        // Scaling every coefficient of a curve by the same factor leaves its output unchanged, so
+        //  reduce both curves before multiplying them together
+        let (mut first, mut second) = (*self, *next);
+        first.normalize();
+        second.normalize();
+        let mut curve = SwapCurve {
+            a: first.a.checked_mul(second.a)?,
+            b: first.b.checked_mul(second.b)?,
+            c: second
+                .b
+                .checked_mul(first.c)?
+                .checked_add(second.c.checked_mul(first.a)?)?,
+        };
+        curve.normalize();
+        Some(curve)
+    }
+
+    ///
+    /// Return the largest input whose output, after taking `output_fee_bps` of it, keeps the
+    /// price of input per output at or below the bounded price
+    ///
+    pub fn max_input_for_price(
+        &self,
+        bounded_price_numerator: u64,
+        bounded_price_denominator: u64,
+        output_fee_bps: u16,
+    ) -> u64 {
+        // The output per input, a / (b + c * x), falls as x grows. Solve for the x where
+        //  it reaches the bound, i.e. x = (a * num * (1 - fee) - b * den) / (c * den)
+        let bps = U256::from(BPS_DENOMINATOR);
+        let output_numerator = self.a
+            * U256::from(bounded_price_numerator)
+            * U256::from(BPS_DENOMINATOR - u64::from(output_fee_bps));
+        let input_numerator = self.b * U256::from(bounded_price_denominator) * bps;
+        if output_numerator <= input_numerator {
+            return 0;
+        }
+        let divisor = self.c * U256::from(bounded_price_denominator) * bps;
+        if divisor.is_zero() {
+            return u64::MAX;
+        }
+        let max_input = (output_numerator - input_numerator) / divisor;
+        if max_input > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            max_input.low_u64()
+        }
+    }
+
+    /// Scale the coefficients down to 127 bits, so the sum of two products of them fits in 256
+    fn normalize(&mut self) {
+        let bits = self.a.bits().max(self.b.bits()).max(self.c.bits());
+        if bits > 127 {
+            let shift = bits - 127;
+            self.a >>= shift;
+            self.b >>= shift;
+            self.c >>= shift;
+        }
+    }
+}
+
+///
+/// The output of a swap as a function of its input, linear between each of `points` and flat
+/// past the last one. Order book fills have this shape, one point per price level, and so does
+/// any chain of them.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct PiecewiseLinearCurve {
+    /// The (input, output) breakpoints, starting at (0, 0) with both increasing
+    points: Vec<(u64, u64)>,
+    /// The input is only filled in multiples of this
+    input_lot_size: u64,
+}
+
+impl PiecewiseLinearCurve {
+    /// Create a curve from cumulative (input, output) breakpoints, excluding the origin
+    pub fn new(breakpoints: impl IntoIterator<Item = (u64, u64)>) -> Self {
+        let mut points = vec![(0, 0)];
+        for point in breakpoints {
+            let last = points[points.len() - 1];
+            if point.0 > last.0 && point.1 >= last.1 {
+                points.push(point);
+            }
+        }
+        PiecewiseLinearCurve {
+            points,
+            input_lot_size: 1,
+        }
+    }
+
+    /// Only fill the input in multiples of `input_lot_size`
+    pub fn with_input_lot_size(mut self, input_lot_size: u64) -> Self {
+        self.input_lot_size = input_lot_size;
+        self
+    }
+
+    /// Return the output for the given input
+    pub fn evaluate(&self, input: u64) -> u64 {
+        for segment in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+            if input <= x1 {
+                return y0 + mul_div_u64(input - x0, y1 - y0, x1 - x0).unwrap();
+            }
+        }
+        self.points[self.points.len() - 1].1
+    }
+
+    /// The curve of swapping through this curve and then through `next`
+    pub fn then(&self, next: &PiecewiseLinearCurve) -> Self {
+        let mut points = Vec::with_capacity(self.points.len() + next.points.len());
+        let mut next_index = 1;
+        for segment in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+            // Add the inputs where this segment's output crosses a breakpoint of `next`
+            while next_index < next.points.len() && next.points[next_index].0 < y1 {
+                let (u, v) = next.points[next_index];
+                if u > y0 {
+                    points.push((x0 + mul_div_u64(u - y0, x1 - x0, y1 - y0).unwrap(), v));
+                }
+                next_index += 1;
+            }
+            points.push((x1, next.evaluate(y1)));
+        }
+        PiecewiseLinearCurve::new(points).with_input_lot_size(self.input_lot_size)
+    }
+
+    ///
+    /// Return the largest input whose output, after taking `output_fee_bps` of it, keeps the
+    /// price of input per output at or below the bounded price. Each segment is linear, so the
+    /// bound is checked at every breakpoint and solved exactly within the segment it is crossed,
+    /// then rounded down to a whole input lot.
+    ///
+    pub fn max_input_for_price(
+        &self,
+        bounded_price_numerator: u64,
+        bounded_price_denominator: u64,
+        output_fee_bps: u16,
+    ) -> u64 {
+        // The trade is in bounds when input * den * BPS <= output * num * (BPS - fee)
+        let input_value = |input: u64| {
+            U256::from(input) * U256::from(bounded_price_denominator) * U256::from(BPS_DENOMINATOR)
+        };
+        let output_value = |output: u64| {
+            U256::from(output)
+                * U256::from(bounded_price_numerator)
+                * U256::from(BPS_DENOMINATOR - u64::from(output_fee_bps))
+        };
+        let mut max_input = 0;
+        for segment in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+            let (start_input, start_output) = (input_value(x0), output_value(y0));
+            let (end_input, end_output) = (input_value(x1), output_value(y1));
+            if end_input <= end_output {
+                max_input = x1;
+            } else if start_input <= start_output {
+                // The bound is crossed within the segment
+                let start_slack = start_output - start_input;
+                let end_excess = end_input - end_output;
+                let crossing = start_slack * U256::from(x1 - x0) / (start_slack + end_excess);
+                max_input = std::cmp::max(max_input, x0 + crossing.low_u64());
+            }
+        }
+        max_input - max_input % self.input_lot_size
+    }
+}
+
 //
 // u64 implementation of Golden Section Search algo
 // https://en.wikipedia.org/wiki/Golden-section_search#Iterative_algorithm
@@ -96,3 +303,195 @@ fn find_max_via_golden_section_search<F: Fn(u64) -> u64>(
         return (c + b) / 2;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{constant_product_simulation, mul_div_u64, SwapCurve, U256};
+    use crate::dexes::is_in_bounds;
+
+    struct Pool {
+        in_pool_balance: u64,
+        out_pool_balance: u64,
+        fee_numerator: u64,
+        fee_denominator: u64,
+    }
+
+    impl Pool {
+        fn simulate(&self, tokens_in: u64) -> u64 {
+            let post_fee_in = tokens_in
+                - mul_div_u64(tokens_in, self.fee_numerator, self.fee_denominator).unwrap();
+            constant_product_simulation(post_fee_in, self.in_pool_balance, self.out_pool_balance)
+        }
+
+        fn curve(&self) -> SwapCurve {
+            SwapCurve::constant_product(
+                self.in_pool_balance,
+                self.out_pool_balance,
+                self.fee_numerator,
+                self.fee_denominator,
+            )
+        }
+    }
+
+    /// Binary search for the largest input that keeps the pools within the bound
+    fn brute_force_max_input(pools: &[Pool], numerator: u64, denominator: u64, max: u64) -> u64 {
+        let in_bounds = |input: u64| {
+            let output = pools
+                .iter()
+                .fold(input, |amount, pool| pool.simulate(amount));
+            is_in_bounds(input, output, &numerator, &denominator)
+        };
+        let (mut low, mut high) = (0, max);
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if in_bounds(mid) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+
+    fn assert_close(actual: u64, expected: u64) {
+        let tolerance = expected / 1_000_000 + 10;
+        assert!(
+            actual.abs_diff(expected) <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_constant_product_max_input_matches_brute_force() {
+        // 1,000 SOL and 20,000 USDC with a 25 bps fee
+        let pool = Pool {
+            in_pool_balance: 1_000_000_000_000,
+            out_pool_balance: 20_000_000_000,
+            fee_numerator: 25,
+            fee_denominator: 10_000,
+        };
+        // Sell 1 SOL for at least 19.5 USDC
+        let (numerator, denominator) = (1_000_000_000, 19_500_000);
+        let max_input = pool.curve().max_input_for_price(numerator, denominator, 0);
+        let expected = brute_force_max_input(&[pool], numerator, denominator, u64::MAX / 2);
+        assert!(expected > 0);
+        assert_close(max_input, expected);
+    }
+
+    #[test]
+    fn test_chained_constant_product_max_input_matches_brute_force() {
+        let pools = [
+            // 1,000 SOL and 20,000 USDC with a 25 bps fee
+            Pool {
+                in_pool_balance: 1_000_000_000_000,
+                out_pool_balance: 20_000_000_000,
+                fee_numerator: 25,
+                fee_denominator: 10_000,
+            },
+            // 50,000 USDC and 40,000 mSOL-like tokens with a 30 bps fee
+            Pool {
+                in_pool_balance: 50_000_000_000,
+                out_pool_balance: 40_000_000_000_000,
+                fee_numerator: 30,
+                fee_denominator: 10_000,
+            },
+        ];
+        // Trade 1 SOL for at least 0.78 of the final token
+        let (numerator, denominator) = (1_000_000_000, 780_000_000);
+        let max_input = pools[0]
+            .curve()
+            .then(&pools[1].curve())
+            .unwrap()
+            .max_input_for_price(numerator, denominator, 0);
+        let expected = brute_force_max_input(&pools, numerator, denominator, u64::MAX / 2);
+        assert!(expected > 0);
+        assert_close(max_input, expected);
+    }
+
+    #[test]
+    fn test_chained_max_input_with_large_fee_denominators() {
+        // The same 25 and 30 bps fees as fractions of 10^18
+        let pools = [
+            Pool {
+                in_pool_balance: 1_000_000_000_000,
+                out_pool_balance: 20_000_000_000,
+                fee_numerator: 2_500_000_000_000_000,
+                fee_denominator: 1_000_000_000_000_000_000,
+            },
+            Pool {
+                in_pool_balance: 50_000_000_000,
+                out_pool_balance: 40_000_000_000_000,
+                fee_numerator: 3_000_000_000_000_000,
+                fee_denominator: 1_000_000_000_000_000_000,
+            },
+            Pool {
+                in_pool_balance: 16_000_000_000_000,
+                out_pool_balance: 1_000_000_000_000,
+                fee_numerator: 2_500_000_000_000_000,
+                fee_denominator: 1_000_000_000_000_000_000,
+            },
+        ];
+        // Round trip 1 SOL, losing at most 5%
+        let (numerator, denominator) = (1_000_000_000, 950_000_000);
+        let max_input = pools[0]
+            .curve()
+            .then(&pools[1].curve())
+            .and_then(|curve| curve.then(&pools[2].curve()))
+            .unwrap()
+            .max_input_for_price(numerator, denominator, 0);
+        let expected = brute_force_max_input(&pools, numerator, denominator, u64::MAX / 2);
+        assert!(expected > 0);
+        assert_close(max_input, expected);
+    }
+
+    #[test]
+    fn test_then_with_full_width_coefficients() {
+        // Chained curves can carry every coefficient at full width, whose products would
+        //  overflow 256 bits without reducing them first
+        let full_width = U256::MAX >> 128;
+        let curve = SwapCurve {
+            a: full_width,
+            b: full_width,
+            c: full_width,
+        };
+        let chained = curve.then(&curve).unwrap();
+        assert!(chained.a.bits() <= 127);
+        assert_eq!(chained.a, chained.b);
+        assert_eq!(chained.c, chained.a * 2);
+    }
+
+    #[test]
+    fn test_constant_product_max_input_out_of_bounds() {
+        let pool = Pool {
+            in_pool_balance: 1_000_000_000_000,
+            out_pool_balance: 20_000_000_000,
+            fee_numerator: 25,
+            fee_denominator: 10_000,
+        };
+        // The pool's spot price is below 21 USDC per SOL, so nothing can be sold
+        assert_eq!(
+            pool.curve()
+                .max_input_for_price(1_000_000_000, 21_000_000, 0),
+            0
+        );
+    }
+
+    #[test]
+    fn test_constant_product_max_input_with_output_fee() {
+        let pool = Pool {
+            in_pool_balance: 1_000_000_000_000,
+            out_pool_balance: 20_000_000_000,
+            fee_numerator: 25,
+            fee_denominator: 10_000,
+        };
+        let curve = pool.curve();
+        // Taking a share of the output for the executor lowers the max input
+        assert!(
+            curve.max_input_for_price(1_000_000_000, 19_500_000, 50)
+                < curve.max_input_for_price(1_000_000_000, 19_500_000, 0)
+        );
+    }
+}
@@ -6,6 +6,7 @@ pub mod open_book_dex;
 pub mod raydium;
 pub mod route;
 pub mod serum_v3;
+pub mod split_route;
 
 pub use dex::*;
 pub use dex_list::*;
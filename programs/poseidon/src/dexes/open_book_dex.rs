@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, num::NonZeroU64};
+use std::num::NonZeroU64;
 
 use anchor_lang::prelude::*;
 use anchor_spl::dex::serum_dex::{
@@ -9,10 +9,13 @@ use anchor_spl::dex::serum_dex::{
 };
 use arrayref::array_refs;
 
-use crate::{errors, utils::spl_token_utils};
+use crate::{errors, instructions::OpenBookLegParams, utils::spl_token_utils};
 
 use super::{
-    serum_v3::{self, buy_coin_amount_out, sell_coin_amount_out, OrderBookItem, Slab},
+    math::PiecewiseLinearCurve,
+    serum_v3::{
+        self, buy_coin_amount_out, order_book_curve, sell_coin_amount_out, OrderBookItem, Slab,
+    },
     Dex, DexStatic,
 };
 
@@ -156,6 +159,22 @@ impl Dex for OpenBookDex<'_, '_> {
             &self.payer_destination_wallet().try_borrow_data()?,
         ))
     }
+
+    fn program_id(&self) -> Pubkey {
+        *self.dex_program().key
+    }
+
+    fn order_book_curve(&self) -> Option<PiecewiseLinearCurve> {
+        Some(order_book_curve(
+            self.trade_is_bid,
+            &self.order_book,
+            self.fee_numerator,
+            self.fee_denominator,
+            self.base_decimals_factor,
+            self.coin_lot_size,
+            self.pc_lot_size,
+        ))
+    }
 }
 
 impl<'a, 'info> DexStatic<'a, 'info> for OpenBookDex<'a, 'info> {
@@ -163,12 +182,13 @@ impl<'a, 'info> DexStatic<'a, 'info> for OpenBookDex<'a, 'info> {
 
     const INIT_ACCOUNTS_LEN: usize = 3;
 
+    type Params = OpenBookLegParams;
+
     fn from_account_slice(
         accounts: &'a [AccountInfo<'info>],
-        additional_data: &mut VecDeque<u8>,
+        params: &OpenBookLegParams,
     ) -> anchor_lang::Result<OpenBookDex<'a, 'info>> {
-        let base_decimals = additional_data.pop_front().unwrap();
-        let base_decimals_factor = 10_u64.pow(base_decimals.into());
+        let base_decimals_factor = 10_u64.pow(params.base_decimals.into());
 
         let base_mint = spl_token_utils::mint(&accounts[7].try_borrow_data()?);
         let destination_mint = spl_token_utils::mint(&accounts[15].try_borrow_data()?);
@@ -1,10 +1,10 @@
 use anchor_lang::prelude::*;
 
-use crate::utils::spl_token_utils;
+use crate::{instructions::RaydiumLegParams, utils::spl_token_utils};
 
 use super::{
     super::{
-        math::{constant_product_simulation, mul_div_u64},
+        math::{constant_product_simulation, mul_div_u64, SwapCurve},
         CurveType, Dex, DexStatic,
     },
     base_total_accessor, bnt_accessor, qnt_accessor, quote_total_accessor, swap_base_in,
@@ -88,15 +88,40 @@ impl Dex for RaydiumSwap<'_, '_> {
             &self.user_destination_token_account().try_borrow_data()?,
         ))
     }
+
+    fn program_id(&self) -> Pubkey {
+        *self.accounts[0].key
+    }
+
+    fn swap_curve(&self) -> Option<SwapCurve> {
+        match self.curve_type {
+            CurveType::ConstantProduct => {
+                let (in_pool_balance, out_pool_balance) = if self.base_is_input {
+                    (self.base_effective_balance, self.quote_effective_balance)
+                } else {
+                    (self.quote_effective_balance, self.base_effective_balance)
+                };
+                Some(SwapCurve::constant_product(
+                    in_pool_balance,
+                    out_pool_balance,
+                    self.fee_numerator,
+                    self.fee_denominator,
+                ))
+            }
+            CurveType::Stable => None,
+        }
+    }
 }
 
 impl<'a, 'info> DexStatic<'a, 'info> for RaydiumSwap<'a, 'info> {
     const ACCOUNTS_LEN: usize = 19;
     const INIT_ACCOUNTS_LEN: usize = 20;
 
+    type Params = RaydiumLegParams;
+
     fn from_account_slice(
         accounts: &'a [AccountInfo<'info>],
-        _additional_data: &mut std::collections::VecDeque<u8>,
+        _params: &RaydiumLegParams,
     ) -> Result<Self>
     where
         Self: Sized,
@@ -1,38 +1,58 @@
-use std::collections::VecDeque;
-
-use super::{leg::Leg, math::find_maximum_input, Dex, DexList};
+use std::convert::TryFrom;
+
+use super::{
+    leg::Leg,
+    math::{find_maximum_input, mul_div_u64, PiecewiseLinearCurve, SwapCurve},
+    Dex, DexList,
+};
+use crate::{
+    constants::BPS_DENOMINATOR, errors::ErrorCode, instructions::LegParams, state::MAX_ACCOUNTS,
+};
 use anchor_lang::prelude::*;
-
-const MAX_LEGS: usize = 3;
+use anchor_spl::token::TokenAccount;
 
 #[derive(Default)]
 pub(crate) struct Route<'a, 'info> {
-    pub legs: [Option<Leg<'a, 'info>>; MAX_LEGS],
+    pub legs: Vec<Leg<'a, 'info>>,
+    /// The share of the output, in basis points, that is paid to the executor and does not count
+    /// towards the bound
+    pub executor_fee_bps: u16,
 }
 
 impl<'a, 'info> Route<'a, 'info> {
     pub fn create(
         remaining_accounts: &'a [AccountInfo<'info>],
-        additional_data: VecDeque<u8>,
+        leg_params: &[LegParams],
     ) -> Result<Self> {
+        if remaining_accounts.len() > MAX_ACCOUNTS {
+            return Err(error!(ErrorCode::TooManyAccounts));
+        }
         // Unpack & initalize the routes from remaining accounts
         let mut route = Route::default();
         let (mut account_cursor, mut leg_cursor): (usize, usize) = (0, 0);
-        let mut added_data = additional_data;
         while let Some(dex_program) = remaining_accounts.get(account_cursor) {
             let dex = DexList::from_id(dex_program.key())?;
             let end_index = dex.get_end_account_idx(account_cursor);
 
-            let account_infos = &remaining_accounts[account_cursor..end_index];
+            let account_infos = remaining_accounts
+                .get(account_cursor..end_index)
+                .ok_or(error!(ErrorCode::LegParamsMismatch))?;
+            let params = leg_params
+                .get(leg_cursor)
+                .ok_or(ErrorCode::LegParamsMismatch)?;
             // Create the Leg
-            let leg = Leg::from_account_slice(dex, account_infos, &mut added_data)?;
+            let leg = Leg::from_account_slice(dex, account_infos, params)?;
 
             // Add the leg to the Route
-            route.legs[leg_cursor] = Some(leg);
+            route.legs.push(leg);
 
             account_cursor = end_index;
             leg_cursor += 1;
         }
+        // Every leg's parameters must be used
+        if leg_cursor != leg_params.len() {
+            return Err(error!(ErrorCode::LegParamsMismatch));
+        }
         Ok(route)
     }
 
@@ -44,7 +64,7 @@ impl<'a, 'info> Route<'a, 'info> {
         bounded_price_numerator: &u64,
         bounded_price_denominator: &u64,
     ) -> bool {
-        let output = self.simulate_execution(input_amount);
+        let output = self.simulate_net_execution(input_amount);
 
         is_in_bounds(
             input_amount,
@@ -58,24 +78,67 @@ impl<'a, 'info> Route<'a, 'info> {
     /// Return the mint that is the input to the trade route
     ///
     pub fn start_mint(&self) -> Result<Pubkey> {
-        match &self.legs[0] {
-            Some(leg) => leg.start_mint(),
-            None => panic!("First leg cannot be blank"),
-        }
+        self.legs
+            .first()
+            .ok_or(error!(ErrorCode::RouteIsEmpty))?
+            .start_mint()
     }
 
     ///
     /// Return the end mint of the final leg in the route.
     ///
     pub fn end_mint(&self) -> Result<Pubkey> {
-        // Iterate in reverse returning the end_mint of the first leg
-        for leg in self.legs.iter().rev() {
-            match leg {
-                Some(leg) => return leg.end_mint(),
-                None => {}
-            }
+        self.legs
+            .last()
+            .ok_or(error!(ErrorCode::RouteIsEmpty))?
+            .end_mint()
+    }
+
+    ///
+    /// Return the number of legs in the route
+    ///
+    pub fn leg_count(&self) -> usize {
+        self.legs.len()
+    }
+
+    ///
+    /// Return the program IDs of the DEXes for each leg in the route
+    ///
+    pub fn dex_program_ids(&self) -> Vec<Pubkey> {
+        let mut ids = Vec::new();
+        self.for_each_leg(|leg| {
+            ids.push(leg.program_id());
+            Ok(())
+        })
+        .unwrap();
+        ids
+    }
+
+    ///
+    /// Return `input_tokens` when trading all of it stays within the bounded price, otherwise
+    /// search below it for the maximum input that does.
+    ///
+    pub fn max_input_in_bounds(
+        &self,
+        input_tokens: u64,
+        bounded_price_numerator: &u64,
+        bounded_price_denominator: &u64,
+        iterations: u8,
+    ) -> u64 {
+        if self.simple_price_check(
+            input_tokens,
+            bounded_price_numerator,
+            bounded_price_denominator,
+        ) {
+            input_tokens
+        } else {
+            self.calculate_max_input(
+                input_tokens,
+                bounded_price_numerator,
+                bounded_price_denominator,
+                iterations,
+            )
         }
-        panic!("There must be at least one leg")
     }
 
     ///
@@ -89,6 +152,21 @@ impl<'a, 'info> Route<'a, 'info> {
         bounded_price_denominator: &u64,
         iterations: u8,
     ) -> u64 {
+        let mut upper_bound = input_tokens_available;
+        if let Some(max_input) =
+            self.solve_max_input(bounded_price_numerator, bounded_price_denominator)
+        {
+            upper_bound = std::cmp::min(max_input, input_tokens_available);
+            if self.simple_price_check(
+                upper_bound,
+                bounded_price_numerator,
+                bounded_price_denominator,
+            ) {
+                return upper_bound;
+            }
+            // Rounding in the DEX math can push the exact solution just out of bounds, in
+            //  which case search below it
+        }
         find_maximum_input(
             |x| {
                 self.simulate_bounded_execution(
@@ -98,26 +176,76 @@ impl<'a, 'info> Route<'a, 'info> {
                 )
             },
             0,
-            input_tokens_available,
+            upper_bound,
             iterations.into(),
         )
     }
 
+    ///
+    /// Solve for the maximum input directly when every leg is a constant product pool or every
+    /// leg is an order book
+    ///
+    fn solve_max_input(
+        &self,
+        bounded_price_numerator: &u64,
+        bounded_price_denominator: &u64,
+    ) -> Option<u64> {
+        if let Some(curve) = self.swap_curve() {
+            return Some(curve.max_input_for_price(
+                *bounded_price_numerator,
+                *bounded_price_denominator,
+                self.executor_fee_bps,
+            ));
+        }
+        self.order_book_curve().map(|curve| {
+            curve.max_input_for_price(
+                *bounded_price_numerator,
+                *bounded_price_denominator,
+                self.executor_fee_bps,
+            )
+        })
+    }
+
+    ///
+    /// Return the route's output as a piecewise linear curve when every leg has one
+    ///
+    fn order_book_curve(&self) -> Option<PiecewiseLinearCurve> {
+        let mut route_curve: Option<PiecewiseLinearCurve> = None;
+        for leg in self.legs.iter() {
+            let leg_curve = leg.order_book_curve()?;
+            route_curve = Some(match route_curve {
+                Some(curve) => curve.then(&leg_curve),
+                None => leg_curve,
+            });
+        }
+        route_curve
+    }
+
+    ///
+    /// Return the closed form of the route's output when every leg has one
+    ///
+    fn swap_curve(&self) -> Option<SwapCurve> {
+        let mut route_curve: Option<SwapCurve> = None;
+        for leg in self.legs.iter() {
+            let leg_curve = leg.swap_curve()?;
+            route_curve = Some(match route_curve {
+                Some(curve) => curve.then(&leg_curve)?,
+                None => leg_curve,
+            });
+        }
+        route_curve
+    }
+
     ///
     /// Execute all legs of the route
     ///
     pub fn execute(&self, input_tokens: u64, signers_seeds: &[&[&[u8]]]) -> Result<()> {
         for (index, leg) in self.legs.iter().enumerate() {
-            match leg {
-                Some(leg) => {
-                    if index == 0 {
-                        leg.swap(input_tokens, signers_seeds)?;
-                    } else {
-                        let amount = leg.input_balance()?;
-                        leg.swap(amount, signers_seeds)?;
-                    }
-                }
-                None => {}
+            if index == 0 {
+                leg.swap(input_tokens, signers_seeds)?;
+            } else {
+                let amount = leg.input_balance()?;
+                leg.swap(amount, signers_seeds)?;
             }
         }
         Ok(())
@@ -134,7 +262,7 @@ impl<'a, 'info> Route<'a, 'info> {
         bounded_price_numerator: &u64,
         bounded_price_denominator: &u64,
     ) -> u64 {
-        let output = self.simulate_execution(input_amount);
+        let output = self.simulate_net_execution(input_amount);
         // Price check to ensure the input to output ratio is in bounds.
         if is_in_bounds(
             input_amount,
@@ -148,30 +276,49 @@ impl<'a, 'info> Route<'a, 'info> {
         }
     }
 
+    ///
+    /// Simulate the amount of output tokens the strategy keeps after paying the executor fee
+    ///
+    pub fn simulate_net_execution(&self, input_amount: u64) -> u64 {
+        let output = self.simulate_execution(input_amount);
+        output - mul_div_u64(output, self.executor_fee_bps.into(), BPS_DENOMINATOR).unwrap()
+    }
+
     ///
     /// Simulate the amount of output tokens you will receive if executing the Route
     ///
-    fn simulate_execution(&self, input_amount: u64) -> u64 {
-        let mut output: u64 = 0;
+    pub fn simulate_execution(&self, input_amount: u64) -> u64 {
+        let mut output: u64 = input_amount;
         self.for_each_leg(|leg| {
-            output = leg.simulate_trade(input_amount);
+            output = leg.simulate_trade(output);
             Ok(())
         })
         .unwrap();
         return output;
     }
 
+    ///
+    /// Simulate the amount of output tokens each leg of the Route produces, feeding the output
+    /// of each leg into the next
+    ///
+    pub fn simulate_legs(&self, input_amount: u64) -> Vec<u64> {
+        let mut outputs = Vec::new();
+        let mut amount = input_amount;
+        self.for_each_leg(|leg| {
+            amount = leg.simulate_trade(amount);
+            outputs.push(amount);
+            Ok(())
+        })
+        .unwrap();
+        outputs
+    }
+
     fn for_each_leg<F>(&self, mut f: F) -> Result<()>
     where
         F: FnMut(&Leg<'a, 'info>) -> Result<()>,
     {
         for leg in self.legs.iter() {
-            match leg {
-                Some(leg) => {
-                    f(&leg)?;
-                }
-                None => {}
-            }
+            f(leg)?;
         }
         Ok(())
     }
@@ -188,8 +335,8 @@ pub fn is_in_bounds(
 ) -> bool {
     // Normalize input to output to determine whether the price per asset matches the
     //  bound. This must handle the case where output is less than input (i.e. the purchase price is < 1)
-    let bounded_numerator = bounded_price_numerator * output;
-    let executed_numerator = input * bounded_price_denominator;
+    let bounded_numerator = u128::from(*bounded_price_numerator) * u128::from(output);
+    let executed_numerator = u128::from(input) * u128::from(*bounded_price_denominator);
     if bounded_numerator == 0 && executed_numerator == 0 {
         false
     } else if executed_numerator > bounded_numerator {
@@ -199,14 +346,68 @@ pub fn is_in_bounds(
     }
 }
 
+///
+/// Return the smallest output that keeps a trade of `input` tokens within the bound
+///
+pub fn bounded_output(
+    input: u64,
+    bounded_price_numerator: &u64,
+    bounded_price_denominator: &u64,
+) -> u64 {
+    if *bounded_price_numerator == 0 {
+        return 0;
+    }
+    // Round up so the output always satisfies `is_in_bounds`
+    let output = (u128::from(input) * u128::from(*bounded_price_denominator))
+        .div_ceil(u128::from(*bounded_price_numerator));
+    u64::try_from(output).unwrap_or(u64::MAX)
+}
+
+///
+/// Reload the source and destination accounts after a trade and check the tokens it used and
+/// gained against the bound. `starting_balances` holds the source's and destination's balances
+/// before the trade. Returns the input used and the output gained.
+///
+pub fn settle_trade<'info>(
+    source: &mut Account<'info, TokenAccount>,
+    destination: &mut Account<'info, TokenAccount>,
+    starting_balances: (u64, u64),
+    bounded_price_numerator: &u64,
+    bounded_price_denominator: &u64,
+) -> Result<(u64, u64)> {
+    source.reload()?;
+    destination.reload()?;
+    let input_tokens_used = starting_balances.0.checked_sub(source.amount).unwrap();
+    let output_tokens_gained = destination.amount.checked_sub(starting_balances.1).unwrap();
+    if !is_in_bounds(
+        input_tokens_used,
+        output_tokens_gained,
+        bounded_price_numerator,
+        bounded_price_denominator,
+    ) {
+        // If actual changes are out of bounds, rollback
+        return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
+    }
+    Ok((input_tokens_used, output_tokens_gained))
+}
+
 // Write unit tests for simple_price_check
 #[cfg(test)]
 mod test {
-    use anchor_lang::prelude::AccountInfo;
+    use anchor_lang::prelude::{AccountInfo, Pubkey};
 
     use crate::dexes::open_book_dex::OpenBookDex;
     use crate::dexes::serum_v3::OrderBookItem;
-    use crate::dexes::{math::U128, Leg, Route};
+    use crate::dexes::{bounded_output, is_in_bounds, math::U128, raydium, Dex, Leg, Route};
+    use crate::{errors::ErrorCode, state::MAX_ACCOUNTS};
+
+    /// Create an empty account for each of `lamports`, all with the same key
+    fn mock_account_infos<'a>(key: &'a Pubkey, lamports: &'a mut [u64]) -> Vec<AccountInfo<'a>> {
+        lamports
+            .iter_mut()
+            .map(|lamports| AccountInfo::new(key, false, false, lamports, &mut [], key, false, 0))
+            .collect()
+    }
 
     fn mock_open_book_route<'a, 'info>(
         route: &mut Route<'a, 'info>,
@@ -219,19 +420,19 @@ mod test {
                     price: 92750000,
                     quantity: 191300000000,
                     quantity_sum: 191300000000,
-                    price_quantity_sum: U128::from(17_743_075_000_000_000_000 as i128),
+                    price_quantity_sum: U128::from(17_743_075_000_000_000_000_i128),
                 },
                 OrderBookItem {
                     price: 92761000,
                     quantity: 977900000000,
                     quantity_sum: 1169200000000,
-                    price_quantity_sum: U128::from(108_454_056_900_000_000_000 as i128),
+                    price_quantity_sum: U128::from(108_454_056_900_000_000_000_i128),
                 },
                 OrderBookItem {
                     price: 92805000,
                     quantity: 383100000000,
                     quantity_sum: 1552300000000,
-                    price_quantity_sum: U128::from(144_007_652_400_000_000_000 as i128),
+                    price_quantity_sum: U128::from(144_007_652_400_000_000_000_i128),
                 },
             ]
         } else {
@@ -240,14 +441,14 @@ mod test {
                     price: 92805000,
                     quantity: 383100000000,
                     quantity_sum: 383100000000,
-                    price_quantity_sum: U128::from(92805000 * 383100000000 as i128),
+                    price_quantity_sum: U128::from(92805000 * 383100000000_i128),
                 },
                 OrderBookItem {
                     price: 92761000,
                     quantity: 977900000000,
                     quantity_sum: 1361000000000,
                     price_quantity_sum: U128::from(
-                        92805000 * 383100000000 + 977900000000 * 92761000 as i128,
+                        92805000 * 383100000000 + 977900000000 * 92761000_i128,
                     ),
                 },
                 OrderBookItem {
@@ -257,7 +458,7 @@ mod test {
                     price_quantity_sum: U128::from(
                         92805000 * 383100000000
                             + 977900000000 * 92761000
-                            + 92750000 * 191300000000 as i128,
+                            + 92750000 * 191300000000_i128,
                     ),
                 },
             ]
@@ -278,7 +479,100 @@ mod test {
         // Create leg from OpenBookDex
         let leg = Leg::OpenBookV3(obd);
         // Create single legged route with OpenBookDex
-        route.legs[0] = Some(leg);
+        route.legs.push(leg);
+    }
+
+    #[test]
+    /// Test that the max input of an order book route is solved by walking the levels
+    fn test_calculate_max_input_for_order_book() {
+        let mock_accounts: Vec<AccountInfo> = vec![];
+        let mut route = Route::default();
+        mock_open_book_route(&mut route, &mock_accounts, false);
+
+        // Sell SOL for at least 92.78 USDC each, which fills part of the second level
+        let bounded_price_numerator = 1_000_000_000;
+        let bounded_price_denominator = 92_780_000;
+        let max_input = route.calculate_max_input(
+            10_000_000_000_000,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+            16,
+        );
+        assert!(max_input > 383_100_000_000);
+        assert!(max_input < 1_361_000_000_000);
+        assert!(route.simple_price_check(
+            max_input,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
+    }
+
+    #[test]
+    /// Test that each leg is simulated with the output of the previous leg
+    fn test_simulate_legs_chains_outputs() {
+        let mock_accounts: Vec<AccountInfo> = vec![];
+        let mut route = Route::default();
+        mock_open_book_route(&mut route, &mock_accounts, false);
+        let mut second_route = Route::default();
+        mock_open_book_route(&mut second_route, &mock_accounts, true);
+        route.legs.append(&mut second_route.legs);
+
+        // Sell 1 SOL for USDC, then buy SOL back with the USDC
+        let outputs = route.simulate_legs(1_000_000_000);
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(route.leg_count(), 2);
+        assert_eq!(outputs[1], route.legs[1].simulate_trade(outputs[0]));
+        assert_eq!(route.simulate_net_execution(1_000_000_000), outputs[1]);
+    }
+
+    #[test]
+    /// Test that a route holds more than three legs
+    fn test_simulate_legs_with_four_legs() {
+        let mock_accounts: Vec<AccountInfo> = vec![];
+        let mut route = Route::default();
+        for trade_is_bid in [false, true, false, true] {
+            mock_open_book_route(&mut route, &mock_accounts, trade_is_bid);
+        }
+
+        // Sell 1 SOL for USDC and buy it back, twice
+        let outputs = route.simulate_legs(1_000_000_000);
+        assert_eq!(outputs.len(), 4);
+        assert_eq!(route.leg_count(), 4);
+        for index in 1..4 {
+            assert_eq!(
+                outputs[index],
+                route.legs[index].simulate_trade(outputs[index - 1])
+            );
+        }
+        assert_eq!(route.simulate_net_execution(1_000_000_000), outputs[3]);
+    }
+
+    #[test]
+    /// Test that a leg without all of its accounts is rejected rather than read past the end
+    fn test_create_rejects_truncated_leg() {
+        let mut lamports = [0; 5];
+        let accounts = mock_account_infos(&raydium::ID, &mut lamports);
+        let error = Route::create(&accounts, &[]).err().unwrap();
+        assert_eq!(error, ErrorCode::LegParamsMismatch.into());
+    }
+
+    #[test]
+    /// Test that a route spanning more than the account limit is rejected
+    fn test_create_rejects_too_many_accounts() {
+        let mut lamports = [0; MAX_ACCOUNTS + 1];
+        let accounts = mock_account_infos(&raydium::ID, &mut lamports);
+        let error = Route::create(&accounts, &[]).err().unwrap();
+        assert_eq!(error, ErrorCode::TooManyAccounts.into());
+    }
+
+    #[test]
+    /// Test that an empty route has no mints instead of panicking
+    fn test_empty_route_has_no_mints() {
+        let route = Route::default();
+        let error = route.start_mint().err().unwrap();
+        assert_eq!(error, ErrorCode::RouteIsEmpty.into());
+        let error = route.end_mint().err().unwrap();
+        assert_eq!(error, ErrorCode::RouteIsEmpty.into());
     }
 
     #[test]
@@ -296,7 +590,7 @@ mod test {
             &bounded_price_numerator,
             &bounded_price_denominator,
         );
-        assert!(res == true);
+        assert!(res);
     }
 
     #[test]
@@ -315,7 +609,7 @@ mod test {
             &bounded_price_numerator,
             &bounded_price_denominator,
         );
-        assert!(res == false);
+        assert!(!res);
     }
 
     #[test]
@@ -334,7 +628,7 @@ mod test {
             &bounded_price_numerator,
             &bounded_price_denominator,
         );
-        assert!(res == true);
+        assert!(res);
     }
 
     #[test]
@@ -353,6 +647,79 @@ mod test {
             &bounded_price_numerator,
             &bounded_price_denominator,
         );
-        assert!(res == false);
+        assert!(!res);
+    }
+
+    #[test]
+    // Test the executor fee is taken out of the output before checking the bound
+    fn test_simple_price_with_executor_fee() {
+        let mock_accounts: Vec<AccountInfo> = vec![];
+        let mut route = Route::default();
+        mock_open_book_route(&mut route, &mock_accounts, false);
+
+        // Sell 1 SOL for at least 92 USDC
+        let bounded_price_numerator = 1_000_000_000;
+        let bounded_price_denominator = 92_000_000;
+        assert!(route.simple_price_check(
+            1_000_000_000,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
+
+        // A 1% executor fee leaves the strategy with less than 92 USDC
+        route.executor_fee_bps = 100;
+        assert!(!route.simple_price_check(
+            1_000_000_000,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
+    }
+
+    #[test]
+    // Test the bounded output is the exact amount that meets the bound
+    fn test_bounded_output() {
+        // Sell 1 SOL for at least 92 USDC
+        let bounded_price_numerator = 1_000_000_000;
+        let bounded_price_denominator = 92_000_000;
+        let output = bounded_output(
+            1_500_000_000,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        );
+        assert_eq!(output, 138_000_000);
+        assert!(is_in_bounds(
+            1_500_000_000,
+            output,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
+        assert!(!is_in_bounds(
+            1_500_000_000,
+            output - 1,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
+
+        // Buy 1 SOL for at most 93 USDC, where the output must round up
+        let bounded_price_numerator = 93_000_000;
+        let bounded_price_denominator = 1_000_000_000;
+        let output = bounded_output(
+            10_000_001,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        );
+        assert_eq!(output, 107_526_893);
+        assert!(is_in_bounds(
+            10_000_001,
+            output,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
+        assert!(!is_in_bounds(
+            10_000_001,
+            output - 1,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
     }
 }
@@ -1,4 +1,4 @@
-use super::super::math::{mul_div_u64, U128};
+use super::super::math::{mul_div_u64, PiecewiseLinearCurve, U128};
 
 #[derive(Clone, Debug)]
 pub struct OrderBookItem {
@@ -30,60 +30,23 @@ pub fn buy_coin_amount_out(
 ) -> u64 {
     // Subtract fees from amount in because we're buying with price currency
     let mut amount_avail =
-        U128::from(amount_in - mul_div_u64(amount_in, fee_numerator, fee_denominator).unwrap());
+        amount_in - mul_div_u64(amount_in, fee_numerator, fee_denominator).unwrap();
     // Account for pc lot size after fees. https://github.com/project-serum/serum-dex/blob/master/dex/src/matching.rs#L637
     amount_avail = (amount_avail / pc_lot_size) * pc_lot_size;
-    // Binary search where in the OrderBook item list we can sell all (or the most) of amount_in
-    let mut low: usize = 0;
-    let mut high = asks.len() - 1;
-    let mut mid: usize;
-    let mut base_index: usize = 0;
-    while low < high {
-        mid = (low + high) / 2;
-        let price_quantity_sum = asks[mid].price_quantity_sum;
-        if price_quantity_sum == amount_avail {
-            base_index = mid;
-            break;
-        } else if amount_avail < price_quantity_sum {
-            // Not enough buying power, decrease high
-            if let Some(new_high) = mid.checked_sub(1) {
-                high = new_high;
-            } else {
-                // Edge case when OB len is 2, low = 0, mid = 0, high = 1
-                if low == mid {
-                    high = mid;
-                } else {
-                    panic!("Subtraction Overflow! low {} mid {} high {} asks len {}\namount_avail {} pqs {}", low, mid, high, asks.len(), amount_avail, price_quantity_sum);
-                }
-            }
-        } else {
-            // more buying power, increase the low
-            base_index = mid;
-            low = mid + 1;
-        }
+    // Binary search for the number of levels amount_in buys in full
+    let filled_levels = asks.partition_point(|item| {
+        item.price_quantity_sum / base_decimal_factor <= U128::from(amount_avail)
+    });
+    let mut amount_out = 0;
+    if filled_levels > 0 {
+        let last_filled = &asks[filled_levels - 1];
+        amount_out = last_filled.quantity_sum;
+        amount_avail -= (last_filled.price_quantity_sum / base_decimal_factor).as_u64();
+    }
+    // Buy what is left from the next level, if the order book has one
+    if let Some(next_level) = asks.get(filled_levels) {
+        amount_out += mul_div_u64(amount_avail, base_decimal_factor, next_level.price).unwrap();
     }
-    if base_index == 0 {
-        return mul_div_u64(
-            amount_avail.as_u64(),
-            base_decimal_factor,
-            asks[base_index].price,
-        )
-        .unwrap();
-    }
-    // If it's not the 0 index, then we need to get all from the base index, and then calculate the change from the next level
-    let mut amount_out: u64 = asks[base_index].quantity_sum;
-    amount_avail = amount_avail - asks[base_index].price_quantity_sum / base_decimal_factor;
-    let next_index = base_index + 1;
-    amount_out += if asks.len() == next_index {
-        0
-    } else {
-        mul_div_u64(
-            amount_avail.as_u64(),
-            base_decimal_factor,
-            asks[next_index].price,
-        )
-        .unwrap()
-    };
     amount_out
 }
 
@@ -96,45 +59,261 @@ pub fn sell_coin_amount_out(
     coin_lot_size: u64,
 ) -> u64 {
     let mut amount_avail = (amount_in / coin_lot_size) * coin_lot_size;
-    // Binary search where in the OrderBook item list we can sell all (or the most) of amount_in
-    let mut low: usize = 0;
-    let mut high = bids.len() - 1;
-    let mut mid: usize;
-    let mut base_index: usize = 0;
-    while low < high {
-        mid = (low + high) / 2;
-        let quantity_sum = bids[mid].quantity_sum;
-        if quantity_sum == amount_avail {
-            base_index = mid;
-            break;
-        } else if amount_avail < quantity_sum {
-            if let Some(new_high) = mid.checked_sub(1) {
-                high = new_high;
-            } else {
-                // Edge case when OB len is 2, low = 0, mid = 0, high = 1
-                if low == mid {
-                    high = mid;
-                } else {
-                    panic!("Subtraction Overflow! low {} mid {} high {} bids len {}\namount_avail {} pqs {}", low, mid, high, bids.len(), amount_avail, quantity_sum);
+    // Binary search for the number of levels amount_in sells in full
+    let filled_levels = bids.partition_point(|item| item.quantity_sum <= amount_avail);
+    let mut amount_out = 0;
+    if filled_levels > 0 {
+        let last_filled = &bids[filled_levels - 1];
+        amount_out = (last_filled.price_quantity_sum / base_decimal_factor).as_u64();
+        amount_avail -= last_filled.quantity_sum;
+    }
+    // Sell what is left into the next level, if the order book has one
+    if let Some(next_level) = bids.get(filled_levels) {
+        amount_out += mul_div_u64(amount_avail, next_level.price, base_decimal_factor).unwrap();
+    }
+    // subtract the fee from the price currency out https://github.com/project-serum/serum-dex/blob/master/dex/src/matching.rs#L501
+    amount_out - mul_div_u64(amount_out, fee_numerator, fee_denominator).unwrap()
+}
+
+///
+/// Build the curve of filling against the order book level by level. Bids pay the fee on the
+/// price currency going in, asks pay it on the price currency coming out. The order book only
+/// fills whole lots: the coin sold is solved in whole coin lots, and a bid's input is padded by
+/// the pc lot its fill may be floored by, keeping the curve at or below the order book.
+///
+pub fn order_book_curve(
+    trade_is_bid: bool,
+    order_book: &[OrderBookItem],
+    fee_numerator: u64,
+    fee_denominator: u64,
+    base_decimal_factor: u64,
+    coin_lot_size: u64,
+    pc_lot_size: u64,
+) -> PiecewiseLinearCurve {
+    if !trade_is_bid {
+        let post_fee_factor = fee_denominator - fee_numerator;
+        return PiecewiseLinearCurve::new(order_book.iter().map(|item| {
+            let pc_amount = (item.price_quantity_sum / base_decimal_factor).as_u64();
+            (
+                item.quantity_sum.div_ceil(coin_lot_size) * coin_lot_size,
+                mul_div_u64(pc_amount, post_fee_factor, fee_denominator).unwrap(),
+            )
+        }))
+        .with_input_lot_size(coin_lot_size);
+    }
+    // Round the input up so the fee and the pc lot the rest is floored to are covered
+    let post_fee_factor = U128::from(fee_denominator - fee_numerator);
+    let gross_up = |pc_amount: u64| {
+        let input = U128::from(pc_amount + pc_lot_size - 1) * U128::from(fee_denominator);
+        ((input + post_fee_factor - 1) / post_fee_factor).as_u64()
+    };
+    let levels = order_book.iter().map(|item| {
+        let pc_amount = (item.price_quantity_sum / base_decimal_factor).as_u64();
+        (gross_up(pc_amount), item.quantity_sum)
+    });
+    PiecewiseLinearCurve::new(std::iter::once((gross_up(0), 0)).chain(levels))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{buy_coin_amount_out, order_book_curve, sell_coin_amount_out, OrderBookItem};
+    use crate::dexes::{is_in_bounds, math::PiecewiseLinearCurve, math::U128};
+
+    const BASE_DECIMALS_FACTOR: u64 = 1_000_000_000;
+    const FEE_NUMERATOR: u64 = 20;
+    const FEE_DENOMINATOR: u64 = 100_000;
+    const COIN_LOT_SIZE: u64 = 1_000_000;
+    const PC_LOT_SIZE: u64 = 100;
+
+    /// Build order book items from (price, quantity) levels
+    fn order_book(levels: &[(u64, u64)]) -> Vec<OrderBookItem> {
+        let mut quantity_sum = 0;
+        let mut price_quantity_sum = U128::zero();
+        levels
+            .iter()
+            .map(|&(price, quantity)| {
+                quantity_sum += quantity;
+                price_quantity_sum += U128::from(price) * U128::from(quantity);
+                OrderBookItem {
+                    price,
+                    quantity,
+                    quantity_sum,
+                    price_quantity_sum,
                 }
-            }
+            })
+            .collect()
+    }
+
+    fn bids() -> Vec<OrderBookItem> {
+        order_book(&[
+            (92805000, 383100000000),
+            (92761000, 977900000000),
+            (92750000, 191300000000),
+        ])
+    }
+
+    fn asks() -> Vec<OrderBookItem> {
+        order_book(&[
+            (92750000, 191300000000),
+            (92761000, 977900000000),
+            (92805000, 383100000000),
+        ])
+    }
+
+    /// Fill the input against the order book the way the DEX leg simulates it
+    fn fill(trade_is_bid: bool, order_book: &Vec<OrderBookItem>, amount_in: u64) -> u64 {
+        if trade_is_bid {
+            buy_coin_amount_out(
+                amount_in,
+                order_book,
+                FEE_NUMERATOR,
+                FEE_DENOMINATOR,
+                BASE_DECIMALS_FACTOR,
+                PC_LOT_SIZE,
+            )
         } else {
-            base_index = mid;
-            low = mid + 1;
+            sell_coin_amount_out(
+                amount_in,
+                order_book,
+                FEE_NUMERATOR,
+                FEE_DENOMINATOR,
+                BASE_DECIMALS_FACTOR,
+                COIN_LOT_SIZE,
+            )
         }
     }
-    if base_index == 0 {
-        return mul_div_u64(amount_avail, bids[base_index].price, base_decimal_factor).unwrap();
-    }
-    // If it's not the 0 index, then we need to get all from the base index, and then calculate the change from the next level
-    let mut amount_out: u64 = (bids[base_index].price_quantity_sum / base_decimal_factor).as_u64();
-    amount_avail = amount_avail - bids[base_index].quantity_sum;
-    let next_index = base_index + 1;
-    amount_out += if bids.len() == next_index {
-        0
-    } else {
-        mul_div_u64(amount_avail, bids[next_index].price, base_decimal_factor).unwrap()
-    };
-    // subtract the fee from the price currency out https://github.com/project-serum/serum-dex/blob/master/dex/src/matching.rs#L501
-    amount_out - mul_div_u64(amount_out, fee_numerator, fee_denominator).unwrap()
+
+    fn book_curve(trade_is_bid: bool, order_book: &[OrderBookItem]) -> PiecewiseLinearCurve {
+        order_book_curve(
+            trade_is_bid,
+            order_book,
+            FEE_NUMERATOR,
+            FEE_DENOMINATOR,
+            BASE_DECIMALS_FACTOR,
+            COIN_LOT_SIZE,
+            PC_LOT_SIZE,
+        )
+    }
+
+    /// Try whole lots across the range in small steps, then binary search past the last step in
+    ///  bounds
+    fn brute_force_max_input<F: Fn(u64) -> u64>(
+        simulate: F,
+        numerator: u64,
+        denominator: u64,
+        max: u64,
+        lot_size: u64,
+    ) -> u64 {
+        let in_bounds = |lots: u64| {
+            let input = lots * lot_size;
+            is_in_bounds(input, simulate(input), &numerator, &denominator)
+        };
+        let max = max / lot_size;
+        let step = max / 100_000;
+        let mut best = 0;
+        let mut input = step;
+        while input <= max {
+            if in_bounds(input) {
+                best = input;
+            }
+            input += step;
+        }
+        let (mut low, mut high) = (best, std::cmp::min(best + step, max));
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if in_bounds(mid) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low * lot_size
+    }
+
+    fn assert_close(actual: u64, expected: u64, tolerance: u64) {
+        assert!(
+            actual.abs_diff(expected) <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_sell_max_input_matches_brute_force() {
+        let bids = bids();
+        let curve = book_curve(false, &bids);
+        // Sell SOL for at least 92.755 USDC each, which crosses into the second level
+        let (numerator, denominator) = (1_000_000_000, 92_755_000);
+        let expected = brute_force_max_input(
+            |input| fill(false, &bids, input),
+            numerator,
+            denominator,
+            bids[bids.len() - 1].quantity_sum,
+            COIN_LOT_SIZE,
+        );
+        assert!(expected > bids[0].quantity_sum && expected < bids[1].quantity_sum);
+        let max_input = curve.max_input_for_price(numerator, denominator, 0);
+        assert!(is_in_bounds(
+            max_input,
+            fill(false, &bids, max_input),
+            &numerator,
+            &denominator
+        ));
+        assert_close(max_input, expected, COIN_LOT_SIZE);
+    }
+
+    #[test]
+    fn test_buy_max_input_matches_brute_force() {
+        let asks = asks();
+        let curve = book_curve(true, &asks);
+        // Buy SOL for at most 92.78 USDC each, which crosses into the third level
+        let (numerator, denominator) = (92_780_000, 1_000_000_000);
+        let max = (asks[asks.len() - 1].price_quantity_sum / BASE_DECIMALS_FACTOR).as_u64();
+        let expected = brute_force_max_input(
+            |input| fill(true, &asks, input),
+            numerator,
+            denominator,
+            max,
+            1,
+        );
+        assert!(expected > 0);
+        let max_input = curve.max_input_for_price(numerator, denominator, 0);
+        assert!(is_in_bounds(
+            max_input,
+            fill(true, &asks, max_input),
+            &numerator,
+            &denominator
+        ));
+        // The third level is priced just 0.05% above the bound, so the pc lot the curve pads
+        //  its input by moves the crossing about 2,000 times as far
+        assert_close(max_input, expected, 2_000 * PC_LOT_SIZE);
+    }
+
+    #[test]
+    fn test_chained_max_input_matches_brute_force() {
+        let (bids, asks) = (bids(), asks());
+        // Sell SOL and buy it back, losing at most 0.1% on the round trip
+        let curve = book_curve(false, &bids).then(&book_curve(true, &asks));
+        let (numerator, denominator) = (1_000_000_000, 999_000_000);
+        let expected = brute_force_max_input(
+            |input| fill(true, &asks, fill(false, &bids, input)),
+            numerator,
+            denominator,
+            bids[bids.len() - 1].quantity_sum,
+            COIN_LOT_SIZE,
+        );
+        assert!(expected > 0);
+        let max_input = curve.max_input_for_price(numerator, denominator, 0);
+        assert_close(max_input, expected, COIN_LOT_SIZE);
+    }
+
+    #[test]
+    fn test_max_input_is_zero_when_out_of_bounds() {
+        let bids = bids();
+        let curve = book_curve(false, &bids);
+        // The best bid is below 93 USDC
+        assert_eq!(curve.max_input_for_price(1_000_000_000, 93_000_000, 0), 0);
+    }
 }
@@ -0,0 +1,444 @@
+use super::{is_in_bounds, route::Route, DexList};
+use crate::{
+    constants::{MARKET_PRICE_SLICES, SPLIT_ALLOCATION_STEPS},
+    errors::ErrorCode,
+    instructions::LegParams,
+    state::MAX_ACCOUNTS,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+/// A route made of parallel branches that share the same input and output mints. The input is
+/// split across the branches and each branch executes its share sequentially.
+#[derive(Default)]
+pub(crate) struct SplitRoute<'a, 'info> {
+    pub branches: Vec<Route<'a, 'info>>,
+}
+
+impl<'a, 'info> SplitRoute<'a, 'info> {
+    ///
+    /// Build the branches from the remaining accounts. `branch_leg_counts` holds the number of
+    /// legs in each branch, an empty slice builds a single branch from every leg.
+    ///
+    pub fn create(
+        remaining_accounts: &'a [AccountInfo<'info>],
+        leg_params: &[LegParams],
+        branch_leg_counts: &[u8],
+    ) -> Result<Self> {
+        if remaining_accounts.len() > MAX_ACCOUNTS {
+            return Err(error!(ErrorCode::TooManyAccounts));
+        }
+        if branch_leg_counts.is_empty() {
+            return Ok(SplitRoute {
+                branches: vec![Route::create(remaining_accounts, leg_params)?],
+            });
+        }
+
+        let mut split_route = SplitRoute::default();
+        let (mut account_cursor, mut leg_cursor): (usize, usize) = (0, 0);
+        for leg_count in branch_leg_counts.iter().map(|count| usize::from(*count)) {
+            if leg_count == 0 || leg_cursor + leg_count > leg_params.len() {
+                return Err(error!(ErrorCode::LegParamsMismatch));
+            }
+            // Walk the branch's legs to find where its accounts end
+            let start_index = account_cursor;
+            for _ in 0..leg_count {
+                let dex_program = remaining_accounts
+                    .get(account_cursor)
+                    .ok_or(ErrorCode::LegParamsMismatch)?;
+                account_cursor =
+                    DexList::from_id(dex_program.key())?.get_end_account_idx(account_cursor);
+            }
+            let branch_accounts = remaining_accounts
+                .get(start_index..account_cursor)
+                .ok_or(error!(ErrorCode::LegParamsMismatch))?;
+            let branch = Route::create(
+                branch_accounts,
+                &leg_params[leg_cursor..leg_cursor + leg_count],
+            )?;
+            split_route.branches.push(branch);
+            leg_cursor += leg_count;
+        }
+        // Every leg must belong to a branch
+        if account_cursor != remaining_accounts.len() || leg_cursor != leg_params.len() {
+            return Err(error!(ErrorCode::LegParamsMismatch));
+        }
+
+        // The branches must all trade the same pair
+        let start_mint = split_route.start_mint()?;
+        let end_mint = split_route.end_mint()?;
+        for branch in split_route.branches.iter() {
+            if branch.start_mint()? != start_mint || branch.end_mint()? != end_mint {
+                return Err(error!(ErrorCode::SplitRouteMintMismatch));
+            }
+        }
+        Ok(split_route)
+    }
+
+    ///
+    /// Set the executor fee on every branch
+    ///
+    pub fn set_executor_fee_bps(&mut self, executor_fee_bps: u16) {
+        for branch in self.branches.iter_mut() {
+            branch.executor_fee_bps = executor_fee_bps;
+        }
+    }
+
+    ///
+    /// Return the mint that is the input to every branch
+    ///
+    pub fn start_mint(&self) -> Result<Pubkey> {
+        self.branches
+            .first()
+            .ok_or(error!(ErrorCode::RouteIsEmpty))?
+            .start_mint()
+    }
+
+    ///
+    /// Return the mint that every branch ends in
+    ///
+    pub fn end_mint(&self) -> Result<Pubkey> {
+        self.branches
+            .first()
+            .ok_or(error!(ErrorCode::RouteIsEmpty))?
+            .end_mint()
+    }
+
+    ///
+    /// Return the number of legs across all branches
+    ///
+    pub fn leg_count(&self) -> usize {
+        self.branches.iter().map(|branch| branch.leg_count()).sum()
+    }
+
+    ///
+    /// Return the program IDs of the DEXes for each leg, branch by branch
+    ///
+    pub fn dex_program_ids(&self) -> Vec<Pubkey> {
+        self.branches
+            .iter()
+            .flat_map(|branch| branch.dex_program_ids())
+            .collect()
+    }
+
+    ///
+    /// Return the allocation of the largest input, up to `input_tokens`, whose combined output
+    /// stays within the bounded price
+    ///
+    pub fn max_input_in_bounds(
+        &self,
+        input_tokens: u64,
+        bounded_price_numerator: &u64,
+        bounded_price_denominator: &u64,
+        iterations: u8,
+    ) -> Vec<u64> {
+        if self.branches.len() == 1 {
+            return vec![self.branches[0].max_input_in_bounds(
+                input_tokens,
+                bounded_price_numerator,
+                bounded_price_denominator,
+                iterations,
+            )];
+        }
+        let allocation = self.allocate(input_tokens);
+        if self.simple_price_check(
+            &allocation,
+            bounded_price_numerator,
+            bounded_price_denominator,
+        ) {
+            return allocation;
+        }
+        // The average price of the greedy allocation worsens as the input grows, so bisect for
+        //  the largest input that is still in bounds
+        let (mut in_bounds, mut out_of_bounds) = (0, input_tokens);
+        for _ in 0..iterations {
+            let input = in_bounds + (out_of_bounds - in_bounds) / 2;
+            if input == in_bounds {
+                break;
+            }
+            if self.simple_price_check(
+                &self.allocate(input),
+                bounded_price_numerator,
+                bounded_price_denominator,
+            ) {
+                in_bounds = input;
+            } else {
+                out_of_bounds = input;
+            }
+        }
+        self.allocate(in_bounds)
+    }
+
+    ///
+    /// Split `input_amount` across the branches by handing out equal chunks, each to the branch
+    /// whose output grows the most from it
+    ///
+    pub fn allocate(&self, input_amount: u64) -> Vec<u64> {
+        let mut allocation = vec![0_u64; self.branches.len()];
+        let mut outputs = vec![0_u64; self.branches.len()];
+        for step in 0..SPLIT_ALLOCATION_STEPS {
+            let mut chunk = input_amount / SPLIT_ALLOCATION_STEPS;
+            if step < input_amount % SPLIT_ALLOCATION_STEPS {
+                chunk += 1;
+            }
+            if chunk == 0 {
+                continue;
+            }
+            let mut best: Option<(usize, u64)> = None;
+            for (index, branch) in self.branches.iter().enumerate() {
+                let output = branch.simulate_net_execution(allocation[index] + chunk);
+                let gain = output.saturating_sub(outputs[index]);
+                match best {
+                    Some((best_index, best_output))
+                        if gain <= best_output.saturating_sub(outputs[best_index]) => {}
+                    _ => best = Some((index, output)),
+                }
+            }
+            if let Some((index, output)) = best {
+                allocation[index] += chunk;
+                outputs[index] = output;
+            }
+        }
+        allocation
+    }
+
+    ///
+    /// Check whether the combined output of the allocation fits the bounds
+    ///
+    pub fn simple_price_check(
+        &self,
+        allocation: &[u64],
+        bounded_price_numerator: &u64,
+        bounded_price_denominator: &u64,
+    ) -> bool {
+        is_in_bounds(
+            allocation.iter().sum(),
+            self.simulate_net_execution(allocation),
+            bounded_price_numerator,
+            bounded_price_denominator,
+        )
+    }
+
+    ///
+    /// Simulate the combined output the strategy keeps after paying the executor fee
+    ///
+    pub fn simulate_net_execution(&self, allocation: &[u64]) -> u64 {
+        self.branches
+            .iter()
+            .zip(allocation)
+            .map(|(branch, input)| branch.simulate_net_execution(*input))
+            .sum()
+    }
+
+    ///
+    /// Simulate the combined output, before the executor fee, of `input_amount` and of each
+    /// half of the previous slice. The smaller slices price the top of the market, free of the
+    /// slippage of the full amount. Returns each slice's input and output.
+    ///
+    pub fn market_quotes(&self, input_amount: u64) -> Vec<(u64, u64)> {
+        (0..MARKET_PRICE_SLICES)
+            .map(|slice| input_amount >> slice)
+            .filter(|input| *input > 0)
+            .map(|input| {
+                let output = self
+                    .branches
+                    .iter()
+                    .zip(self.allocate(input))
+                    .map(|(branch, branch_input)| branch.simulate_execution(branch_input))
+                    .sum();
+                (input, output)
+            })
+            .collect()
+    }
+
+    ///
+    /// Execute each branch with its share of the input
+    ///
+    pub fn execute(&self, allocation: &[u64], signers_seeds: &[&[&[u8]]]) -> Result<()> {
+        for (branch, input) in self.branches.iter().zip(allocation) {
+            if *input > 0 {
+                branch.execute(*input, signers_seeds)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Delegate the allocation's input from `source` to the executor, execute each branch and
+    /// revoke the delegation. `authority` owns `source` and signs with `signers_seeds`.
+    ///
+    pub fn execute_delegated(
+        &self,
+        allocation: &[u64],
+        token_program: &AccountInfo<'info>,
+        source: &AccountInfo<'info>,
+        executor: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        signers_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let cpi_ctx = CpiContext {
+            program: token_program.clone(),
+            accounts: token::Approve {
+                to: source.clone(),
+                delegate: executor.clone(),
+                authority: authority.clone(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: signers_seeds,
+        };
+        token::approve(cpi_ctx, allocation.iter().sum())?;
+
+        self.execute(allocation, signers_seeds)?;
+
+        let cpi_ctx = CpiContext {
+            program: token_program.clone(),
+            accounts: token::Revoke {
+                source: source.clone(),
+                authority: authority.clone(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: signers_seeds,
+        };
+        token::revoke(cpi_ctx)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use anchor_lang::prelude::AccountInfo;
+
+    use crate::dexes::open_book_dex::OpenBookDex;
+    use crate::dexes::serum_v3::OrderBookItem;
+    use crate::dexes::{math::U128, raydium, split_route::SplitRoute, Leg, Route};
+    use crate::errors::ErrorCode;
+    use crate::instructions::{LegParams, RaydiumLegParams};
+
+    /// Build a branch that sells SOL into `quantity` of bids at `price`, with the rest of the
+    /// book bidding half the price
+    pub(crate) fn mock_bid_branch<'a, 'info>(
+        accounts: &'a [AccountInfo<'info>],
+        price: u64,
+        quantity: u64,
+    ) -> Route<'a, 'info> {
+        let lot = 1_000_000;
+        let depth = 100_000_000_000;
+        let price_quantity_sum = U128::from(price) * U128::from(quantity);
+        let order_book = vec![
+            OrderBookItem {
+                price,
+                quantity,
+                quantity_sum: quantity,
+                price_quantity_sum,
+            },
+            OrderBookItem {
+                price: price / 2,
+                quantity: lot,
+                quantity_sum: quantity + lot,
+                price_quantity_sum: price_quantity_sum + U128::from(price / 2) * U128::from(lot),
+            },
+            OrderBookItem {
+                price: price / 2,
+                quantity: depth,
+                quantity_sum: quantity + lot + depth,
+                price_quantity_sum: price_quantity_sum
+                    + U128::from(price / 2) * U128::from(lot + depth),
+            },
+        ];
+        let mut route = Route::default();
+        route.legs.push(Leg::OpenBookV3(OpenBookDex {
+            trade_is_bid: false,
+            order_book,
+            fee_numerator: 20,
+            fee_denominator: 100000,
+            coin_lot_size: lot,
+            pc_lot_size: 100,
+            has_fee_discount_account: false,
+            base_decimals_factor: 1_000_000_000,
+            accounts,
+        }));
+        route
+    }
+
+    #[test]
+    /// Test that input spills over to the worse venue once the better one is exhausted
+    fn test_allocate_splits_across_branches() {
+        let mock_accounts: Vec<AccountInfo> = vec![];
+        let split_route = SplitRoute {
+            branches: vec![
+                mock_bid_branch(&mock_accounts, 92_000_000, 4_000_000_000),
+                mock_bid_branch(&mock_accounts, 93_000_000, 6_000_000_000),
+            ],
+        };
+
+        let allocation = split_route.allocate(10_000_000_000);
+        assert_eq!(allocation, vec![4_000_000_000, 6_000_000_000]);
+        let allocation = split_route.allocate(5_000_000_000);
+        assert_eq!(allocation, vec![0, 5_000_000_000]);
+    }
+
+    #[test]
+    /// Test that the combined execution of the solved allocation stays within the bound
+    fn test_max_input_in_bounds_for_split_route() {
+        let mock_accounts: Vec<AccountInfo> = vec![];
+        let split_route = SplitRoute {
+            branches: vec![
+                mock_bid_branch(&mock_accounts, 92_000_000, 4_000_000_000),
+                mock_bid_branch(&mock_accounts, 93_000_000, 6_000_000_000),
+            ],
+        };
+
+        // Sell SOL for at least 92.8 USDC each on average, which needs most of the input on the
+        //  second venue
+        let bounded_price_numerator = 1_000_000_000;
+        let bounded_price_denominator = 92_800_000;
+        let allocation = split_route.max_input_in_bounds(
+            10_000_000_000,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+            16,
+        );
+        assert!(split_route.simple_price_check(
+            &allocation,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+        ));
+        assert!(allocation.iter().sum::<u64>() > 6_000_000_000);
+        assert!(allocation[0] < allocation[1]);
+
+        // Sell SOL for at least 91 USDC each, which both venues fill
+        let bounded_price_denominator = 91_000_000;
+        let allocation = split_route.max_input_in_bounds(
+            10_000_000_000,
+            &bounded_price_numerator,
+            &bounded_price_denominator,
+            16,
+        );
+        assert_eq!(allocation, vec![4_000_000_000, 6_000_000_000]);
+    }
+
+    #[test]
+    /// Test that a branch without all of its leg's accounts is rejected rather than cut short
+    fn test_create_rejects_truncated_branch() {
+        let mut lamports = [0; 5];
+        let accounts: Vec<AccountInfo> = lamports
+            .iter_mut()
+            .map(|lamports| {
+                AccountInfo::new(
+                    &raydium::ID,
+                    false,
+                    false,
+                    lamports,
+                    &mut [],
+                    &raydium::ID,
+                    false,
+                    0,
+                )
+            })
+            .collect();
+        let leg_params = [LegParams::Raydium(RaydiumLegParams::default())];
+        let error = SplitRoute::create(&accounts, &leg_params, &[1])
+            .err()
+            .unwrap();
+        assert_eq!(error, ErrorCode::LegParamsMismatch.into());
+    }
+}
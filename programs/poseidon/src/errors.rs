@@ -56,6 +56,140 @@ pub enum ErrorCode {
     BadTokenAccountKeyForLeg, // 6025
     #[msg("Bad LUT program address")]
     BadLutProgramAddress, // 6026
-    #[msg("Cannot handle more than 30 accounts")]
+    #[msg("Cannot handle more than 64 accounts")]
     TooManyAccounts, // 6027
+    #[msg("Cannot have more than 4 price tiers")]
+    TooManyPriceTiers, // 6028
+    #[msg("Price tier amount cannot be 0")]
+    PriceTierAmountIsZero, // 6029
+    #[msg("Price tier amounts cannot exceed the transfer amount")]
+    PriceTiersExceedTransferAmount, // 6030
+    #[msg("All price tiers have been filled")]
+    PriceTiersFilled, // 6031
+    #[msg("Executor fee cannot exceed 10000 bps")]
+    ExecutorFeeTooHigh, // 6032
+    #[msg("Executor token account mint must match the deposit account")]
+    ExecutorTokenAccountMintMismatch, // 6033
+    #[msg("Surplus share cannot exceed 10000 bps")]
+    SurplusShareTooHigh, // 6034
+    #[msg("Minimum fill input cannot exceed the maximum fill input")]
+    MinFillExceedsMaxFill, // 6035
+    #[msg("Trade input is below the minimum fill")]
+    FillBelowMinimum, // 6036
+    #[msg("Trade input is above the maximum fill")]
+    FillAboveMaximum, // 6037
+    #[msg("Start date must be before the reclaim date")]
+    StartDateAfterReclaimDate, // 6038
+    #[msg("Strategy cannot trade before the start date")]
+    StartDateHasNotPassed, // 6039
+    #[msg("Not enough slots have passed since the last trade")]
+    TradeCooldownHasNotPassed, // 6040
+    #[msg("Unsupported additional data version")]
+    UnsupportedAdditionalDataVersion, // 6041
+    #[msg("Additional data could not be decoded")]
+    InvalidAdditionalData, // 6042
+    #[msg("Leg parameters do not match the legs of the route")]
+    LegParamsMismatch, // 6043
+    #[msg("Every branch of a split route must share the same input and output mints")]
+    SplitRouteMintMismatch, // 6044
+    #[msg("Each strategy must sell the mint the other strategy deposits")]
+    StrategyMintsNotMirrored, // 6045
+    #[msg("Strategy bounds do not overlap")]
+    StrategyBoundsDoNotOverlap, // 6046
+    #[msg("Matched amount cannot be 0")]
+    MatchAmountIsZero, // 6047
+    #[msg("Fill amounts cannot be 0")]
+    FillAmountIsZero, // 6048
+    #[msg("Fill exceeds the collateral available to the strategy")]
+    FillExceedsAvailableInput, // 6049
+    #[msg("Strategy already has an OpenOrders account")]
+    OpenOrdersAlreadyInitialized, // 6050
+    #[msg("Strategy does not have an OpenOrders account")]
+    OpenOrdersNotInitialized, // 6051
+    #[msg("Laddered strategies cannot rest orders")]
+    LadderedStrategyCannotRestOrders, // 6052
+    #[msg("Strategy already has a resting order")]
+    RestingOrderAlreadyPlaced, // 6053
+    #[msg("Strategy does not have a resting order")]
+    NoRestingOrder, // 6054
+    #[msg("Resting order is smaller than the market's lot size")]
+    RestingOrderBelowLotSize, // 6055
+    #[msg("Resting order must be cancelled and its events consumed before reclaiming")]
+    RestingOrderNotCancelled, // 6056
+    #[msg("Market must trade the strategy's collateral mint against its deposit mint")]
+    MarketMintsDoNotMatchStrategy, // 6057
+    #[msg("Grid lower price must be above 0 and below its upper price")]
+    InvalidGridPriceBand, // 6058
+    #[msg("Grid must have between 2 and 32 levels")]
+    InvalidGridLevels, // 6059
+    #[msg("Grid level size cannot be 0")]
+    GridLevelSizeIsZero, // 6060
+    #[msg("Grid level is out of range")]
+    InvalidGridLevel, // 6061
+    #[msg("Grid does not hold enough to fill the level")]
+    GridLevelUnfunded, // 6062
+    #[msg("Target weight must be between 0 and 10000 basis points")]
+    InvalidTargetWeight, // 6063
+    #[msg("Rebalance tolerance must be below 10000 basis points")]
+    InvalidRebalanceTolerance, // 6064
+    #[msg("Max deviation must be below 10000 basis points")]
+    InvalidMaxDeviation, // 6065
+    #[msg("Portfolio is within its tolerance of the target weight")]
+    PortfolioWithinTolerance, // 6066
+    #[msg("Strategy already has a basket")]
+    BasketAlreadyInitialized, // 6067
+    #[msg("Basket must have between 1 and 4 outputs, each with a deposit account")]
+    InvalidBasketOutputs, // 6068
+    #[msg("Basket weights must add up to 10000 basis points")]
+    BasketWeightsDoNotSumToWhole, // 6069
+    #[msg("Strategy cannot have traded, be laddered or be chained behind an upstream strategy")]
+    StrategyCannotHaveBasket, // 6070
+    #[msg("Deposit account is not one of the basket's outputs")]
+    BasketOutputNotFound, // 6071
+    #[msg("Basket key does not match the strategy")]
+    WrongBasketKey, // 6072
+    #[msg("Basket, split deposit, OCO and upstream strategies can only trade through bounded_trade_v2")]
+    StrategyRequiresRoute, // 6073
+    #[msg("Strategy already splits its deposits")]
+    DepositSplitAlreadyInitialized, // 6074
+    #[msg("Deposit split must have between 1 and 4 recipients, each with a deposit account")]
+    InvalidDepositRecipients, // 6075
+    #[msg("Deposit split weights must add up to 10000 basis points")]
+    DepositWeightsDoNotSumToWhole, // 6076
+    #[msg("Strategy already has a resting order, basket, deposit split, pair or downstream")]
+    StrategyHasExtension, // 6077
+    #[msg("Deposit split key does not match the strategy")]
+    WrongDepositSplitKey, // 6078
+    #[msg("Deposit recipient does not match the deposit split")]
+    DepositRecipientMismatch, // 6079
+    #[msg("Strategy cannot have traded or be chained behind an upstream strategy")]
+    StrategyCannotJoinOcoPair, // 6080
+    #[msg("OCO strategies must share a collateral mint, reclaim address and reclaim date")]
+    OcoStrategiesMismatch, // 6081
+    #[msg("OcoPair key does not match the strategy")]
+    WrongOcoPairKey, // 6082
+    #[msg("The strategy's OCO sibling has already filled")]
+    OcoSiblingFilled, // 6083
+    #[msg("The strategy's upstream strategy has not filled yet")]
+    UpstreamHasNotFilled, // 6084
+    #[msg("Downstream strategy cannot have traded, have a basket or pair, or have an upstream")]
+    StrategiesCannotBeChained, // 6085
+    #[msg("Downstream strategy does not match the strategy")]
+    WrongDownstreamStrategy, // 6086
+    #[msg("Upstream strategy cannot itself be chained behind an upstream strategy")]
+    UpstreamIsChained, // 6087
+    #[msg("Deposit amount cannot be 0")]
+    DepositAmountIsZero, // 6088
+    #[msg(
+        "Stop loss trigger must be at or above the stop loss's price and below the take profit's"
+    )]
+    InvalidStopLossTrigger, // 6089
+    #[msg("The market has not fallen to the stop loss trigger")]
+    StopLossNotTriggered, // 6090
+    #[msg("Proceeds account must be owned by the strategy and share the deposit account's mint")]
+    WrongProceedsAccount, // 6091
+    #[msg("Route must have at least one leg")]
+    RouteIsEmpty, // 6092
+    #[msg("Grid range must trade its full size")]
+    GridRangePartiallyFilled, // 6093
 }
@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{BasketOutput, DepositRecipient, PriceTier};
+
+#[event]
+pub struct InitBoundedStrategyEvent {
+    pub strategy: Pubkey,
+    pub serum_market: Pubkey,
+    pub mint: Pubkey,
+    pub order_payer: Pubkey,
+    pub deposit_address: Pubkey,
+    pub reclaim_address: Pubkey,
+    pub transfer_amount: u64,
+    pub bound_price: u64,
+    pub reclaim_date: i64,
+    pub order_side: u8,
+    pub bound: u8,
+}
+
+#[event]
+pub struct BoundedTradeEvent {
+    pub strategy: Pubkey,
+    /// The signer that executed the trade
+    pub executor: Pubkey,
+    pub serum_market: Pubkey,
+    pub input_used: u64,
+    pub output_gained: u64,
+}
+
+#[event]
+pub struct ReclaimEvent {
+    pub strategy: Pubkey,
+    pub reclaim_address: Pubkey,
+    /// The amount of collateral returned to the reclaim address
+    pub amount: u64,
+}
+
+#[event]
+pub struct InitBoundedStrategyV2Event {
+    pub strategy: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub collateral_account: Pubkey,
+    pub deposit_address: Pubkey,
+    pub reclaim_address: Pubkey,
+    pub transfer_amount: u64,
+    pub bounded_price_numerator: u64,
+    pub bounded_price_denominator: u64,
+    pub start_date: i64,
+    pub reclaim_date: i64,
+    pub price_tiers: Vec<PriceTier>,
+    pub executor_fee_bps: u16,
+    pub surplus_share_bps: u16,
+    pub min_fill_input: u64,
+    pub max_fill_input: u64,
+    pub min_slots_between_trades: u64,
+}
+
+#[event]
+pub struct BoundedTradeV2Event {
+    pub strategy: Pubkey,
+    /// The signer that executed the trade
+    pub executor: Pubkey,
+    /// The program ID of the DEX each leg of the route traded on
+    pub leg_dex_ids: Vec<Pubkey>,
+    /// The index of the price tier the trade filled, if the strategy is laddered
+    pub price_tier: Option<u8>,
+    /// The index of the basket output the trade filled, if the strategy has a basket
+    pub basket_output: Option<u8>,
+    /// The amount of collateral sold
+    pub input_used: u64,
+    /// The total output of the route
+    pub output_gained: u64,
+    /// The share of the output transferred to the deposit address
+    pub deposit_amount: u64,
+    /// The share of the output kept by the executor as fees and surplus
+    pub executor_amount: u64,
+    /// The amount paid to each of the deposit split's recipients. Empty when the strategy's
+    /// proceeds are not split.
+    pub payouts: Vec<u64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InitBasketV2Event {
+    pub strategy: Pubkey,
+    pub basket: Pubkey,
+    /// The basket's outputs with their collateral allocations
+    pub outputs: Vec<BasketOutput>,
+}
+
+#[event]
+pub struct InitDepositSplitV2Event {
+    pub strategy: Pubkey,
+    pub deposit_split: Pubkey,
+    /// The strategy owned account trades now deposit into
+    pub proceeds_account: Pubkey,
+    pub recipients: Vec<DepositRecipient>,
+}
+
+#[event]
+pub struct ChainStrategiesV2Event {
+    pub upstream: Pubkey,
+    pub downstream: Pubkey,
+    /// The downstream strategy's collateral account the upstream strategy now deposits into
+    pub collateral_account: Pubkey,
+}
+
+#[event]
+pub struct StrategyFundedV2Event {
+    /// The strategy that was funded
+    pub strategy: Pubkey,
+    /// The upstream strategy whose trade funded the strategy, or the depositor
+    pub funder: Pubkey,
+    /// The amount deposited into the strategy's collateral account
+    pub amount: u64,
+}
+
+#[event]
+pub struct InitOcoPairV2Event {
+    pub oco_pair: Pubkey,
+    pub take_profit: Pubkey,
+    pub stop_loss: Pubkey,
+    /// The collateral account both strategies now sell from
+    pub collateral_account: Pubkey,
+    /// The collateral both strategies now share
+    pub amount: u64,
+    pub stop_loss_trigger_numerator: u64,
+    pub stop_loss_trigger_denominator: u64,
+}
+
+#[event]
+pub struct ReclaimV2Event {
+    pub strategy: Pubkey,
+    pub reclaim_address: Pubkey,
+    /// The amount of collateral returned to the reclaim address
+    pub amount: u64,
+}
+
+#[event]
+pub struct MatchStrategiesV2Event {
+    pub strategy_a: Pubkey,
+    pub strategy_b: Pubkey,
+    /// The signer that matched the strategies
+    pub executor: Pubkey,
+    /// The index of the price tier each strategy filled, if it is laddered
+    pub price_tier_a: Option<u8>,
+    pub price_tier_b: Option<u8>,
+    /// The amount of collateral the first strategy sold to the second
+    pub amount_a: u64,
+    /// The amount of collateral the second strategy sold to the first
+    pub amount_b: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FillStrategyV2Event {
+    pub strategy: Pubkey,
+    /// The market maker that filled the strategy
+    pub maker: Pubkey,
+    /// The index of the price tier the fill traded, if the strategy is laddered
+    pub price_tier: Option<u8>,
+    /// The amount of collateral sold to the maker
+    pub input_used: u64,
+    /// The amount the maker paid to the deposit address
+    pub deposit_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlaceRestingOrderV2Event {
+    pub strategy: Pubkey,
+    /// True when the strategy bids for the market's base currency
+    pub is_bid: bool,
+    /// The order's price in quote lots per base lot
+    pub limit_price: u64,
+    /// The amount of collateral locked in the order
+    pub input_committed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettleRestingOrderV2Event {
+    pub strategy: Pubkey,
+    /// The amount of collateral the resting order sold since the last settle
+    pub input_used: u64,
+    /// The amount settled to the deposit address
+    pub deposit_amount: u64,
+    /// The amount of collateral still locked in the resting order
+    pub resting_input: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InitGridStrategyEvent {
+    pub strategy: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_transfer_amount: u64,
+    pub quote_transfer_amount: u64,
+    pub lower_price: u64,
+    pub upper_price: u64,
+    pub price_denominator: u64,
+    pub grid_levels: u8,
+    pub level_size: u64,
+    pub start_date: i64,
+    pub reclaim_date: i64,
+    pub sold_ranges: u32,
+}
+
+#[event]
+pub struct GridTradeEvent {
+    pub strategy: Pubkey,
+    pub executor: Pubkey,
+    /// The index of the range that was filled
+    pub range: u8,
+    /// True when the range sold base, false when it bought the base back
+    pub sold_base: bool,
+    pub input_used: u64,
+    pub output_gained: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReclaimGridStrategyEvent {
+    pub strategy: Pubkey,
+    /// The amounts of base and quote returned to the reclaim addresses
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+#[event]
+pub struct InitRebalanceStrategyEvent {
+    pub strategy: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_transfer_amount: u64,
+    pub quote_transfer_amount: u64,
+    pub reference_price_numerator: u64,
+    pub reference_price_denominator: u64,
+    pub target_base_weight_bps: u16,
+    pub tolerance_bps: u16,
+    pub max_deviation_bps: u16,
+    pub start_date: i64,
+    pub reclaim_date: i64,
+}
+
+#[event]
+pub struct RebalanceTradeEvent {
+    pub strategy: Pubkey,
+    pub executor: Pubkey,
+    /// True when the overweight base was sold, false when quote was spent on base
+    pub sold_base: bool,
+    pub input_used: u64,
+    pub output_gained: u64,
+    /// The share of the portfolio's value held in base after the trade
+    pub base_weight_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReclaimRebalanceStrategyEvent {
+    pub strategy: Pubkey,
+    /// The amounts of base and quote returned to the reclaim addresses
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::DEFAULT_SEARCH_ITERATIONS, errors::ErrorCode};
+
+/// The current version of the `additional_data` envelope
+pub const ADDITIONAL_DATA_VERSION: u8 = 2;
+/// The first version of the envelope, which has no split route branches
+pub const ADDITIONAL_DATA_VERSION_1: u8 = 1;
+
+/// Executor supplied parameters for building and sizing a trade. It is passed to instructions as
+/// a version byte followed by the Borsh encoded struct.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdditionalData {
+    /// An input amount computed off chain. It is used as is when it stays within the bound,
+    /// otherwise the search for the maximum input is limited to below it.
+    pub input_hint: Option<u64>,
+    /// The number of iterations to search for the maximum input, 0 uses the default
+    pub search_iterations: u8,
+    /// The parameters for each leg of the route, in order
+    pub legs: Vec<LegParams>,
+    /// The number of legs in each parallel branch of a split route, in order. Empty for a single
+    /// sequential route.
+    pub branch_leg_counts: Vec<u8>,
+}
+
+/// Version 1 of `AdditionalData`, before split routes
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct AdditionalDataV1 {
+    input_hint: Option<u64>,
+    search_iterations: u8,
+    legs: Vec<LegParams>,
+}
+
+impl From<AdditionalDataV1> for AdditionalData {
+    fn from(data: AdditionalDataV1) -> Self {
+        AdditionalData {
+            input_hint: data.input_hint,
+            search_iterations: data.search_iterations,
+            legs: data.legs,
+            branch_leg_counts: Vec::new(),
+        }
+    }
+}
+
+/// The parameters for a leg of the route, keyed by the DEX the leg trades on. Variants are in
+/// the same order as `DexList`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum LegParams {
+    OpenBookV3(OpenBookLegParams),
+    Raydium(RaydiumLegParams),
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpenBookLegParams {
+    /// The decimals of the market's base mint
+    pub base_decimals: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RaydiumLegParams {}
+
+impl AdditionalData {
+    /// Decode the versioned envelope, rejecting versions this program does not know
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        let (version, data) = data
+            .split_first()
+            .ok_or(ErrorCode::UnsupportedAdditionalDataVersion)?;
+        match *version {
+            ADDITIONAL_DATA_VERSION => AdditionalData::try_from_slice(data)
+                .map_err(|_| error!(ErrorCode::InvalidAdditionalData)),
+            ADDITIONAL_DATA_VERSION_1 => AdditionalDataV1::try_from_slice(data)
+                .map(AdditionalData::from)
+                .map_err(|_| error!(ErrorCode::InvalidAdditionalData)),
+            _ => Err(error!(ErrorCode::UnsupportedAdditionalDataVersion)),
+        }
+    }
+
+    /// Encode the struct in the current version of the envelope
+    pub fn pack(&self) -> Result<Vec<u8>> {
+        let mut data = vec![ADDITIONAL_DATA_VERSION];
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+
+    /// Returns the search iteration budget
+    pub fn search_iterations(&self) -> u8 {
+        if self.search_iterations == 0 {
+            DEFAULT_SEARCH_ITERATIONS
+        } else {
+            self.search_iterations
+        }
+    }
+
+    /// Returns the largest input to try, limited to the executor's hint
+    pub fn input_upper_bound(&self, input_tokens: u64) -> u64 {
+        match self.input_hint {
+            Some(hint) => std::cmp::min(hint, input_tokens),
+            None => input_tokens,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_additional_data_round_trip() {
+        let additional_data = AdditionalData {
+            input_hint: Some(1_000_000_000),
+            search_iterations: 8,
+            legs: vec![
+                LegParams::OpenBookV3(OpenBookLegParams { base_decimals: 9 }),
+                LegParams::Raydium(RaydiumLegParams {}),
+            ],
+            branch_leg_counts: vec![1, 1],
+        };
+        let data = additional_data.pack().unwrap();
+        assert_eq!(data[0], ADDITIONAL_DATA_VERSION);
+        assert_eq!(AdditionalData::unpack(&data).unwrap(), additional_data);
+    }
+
+    #[test]
+    fn test_additional_data_unpacks_version_1() {
+        let legs = vec![LegParams::OpenBookV3(OpenBookLegParams {
+            base_decimals: 6,
+        })];
+        let mut data = vec![ADDITIONAL_DATA_VERSION_1];
+        AdditionalDataV1 {
+            input_hint: None,
+            search_iterations: 4,
+            legs: legs.clone(),
+        }
+        .serialize(&mut data)
+        .unwrap();
+        assert_eq!(
+            AdditionalData::unpack(&data).unwrap(),
+            AdditionalData {
+                input_hint: None,
+                search_iterations: 4,
+                legs,
+                branch_leg_counts: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_additional_data_rejects_unknown_version() {
+        let mut data = AdditionalData::default().pack().unwrap();
+        data[0] = ADDITIONAL_DATA_VERSION + 1;
+        assert_eq!(
+            AdditionalData::unpack(&data).unwrap_err(),
+            error!(ErrorCode::UnsupportedAdditionalDataVersion)
+        );
+        assert_eq!(
+            AdditionalData::unpack(&[]).unwrap_err(),
+            error!(ErrorCode::UnsupportedAdditionalDataVersion)
+        );
+    }
+
+    #[test]
+    fn test_additional_data_rejects_malformed_data() {
+        assert_eq!(
+            AdditionalData::unpack(&[ADDITIONAL_DATA_VERSION, 1]).unwrap_err(),
+            error!(ErrorCode::InvalidAdditionalData)
+        );
+    }
+}
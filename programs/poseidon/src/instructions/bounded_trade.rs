@@ -16,6 +16,7 @@ use crate::{authority_signer_seeds, settle_funds};
 use crate::{
     constants::AUTHORITY_SEED,
     errors::ErrorCode,
+    events::BoundedTradeEvent,
     place_order,
     serum_utils::{get_best_bid_ask, FeeTier},
     state::BoundedStrategy,
@@ -106,6 +107,8 @@ pub fn handler<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, BoundedTrade<'info>>,
 ) -> Result<()> {
     let bounded_strategy = &ctx.accounts.strategy;
+    let starting_input_balance = ctx.accounts.order_payer.amount;
+    let starting_destination_balance = ctx.accounts.deposit_account.amount;
 
     let (best_bid, best_ask, coin_lot_size, pc_lot_size) = {
         // load the Serum market
@@ -222,6 +225,20 @@ pub fn handler<'a, 'b, 'c, 'info>(
             return Err(error!(ErrorCode::NoUpperBoundedAsks));
         }
     }
+
+    ctx.accounts.order_payer.reload()?;
+    ctx.accounts.deposit_account.reload()?;
+    emit!(BoundedTradeEvent {
+        strategy: ctx.accounts.strategy.key(),
+        executor: ctx.accounts.payer.key(),
+        serum_market: ctx.accounts.serum_market.key(),
+        input_used: starting_input_balance.saturating_sub(ctx.accounts.order_payer.amount),
+        output_gained: ctx
+            .accounts
+            .deposit_account
+            .amount
+            .saturating_sub(starting_destination_balance),
+    });
     Ok(())
 }
 
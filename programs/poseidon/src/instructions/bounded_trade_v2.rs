@@ -1,13 +1,16 @@
-use std::collections::VecDeque;
-
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::token::{
+    self, spl_token::instruction::AuthorityType, SetAuthority, Token, TokenAccount, Transfer,
+};
 
 use crate::{
-    constants::BOUNDED_STRATEGY_SEED,
-    dexes::{is_in_bounds, Route},
+    constants::{BOUNDED_STRATEGY_SEED, BPS_DENOMINATOR, OCO_PAIR_SEED},
+    dexes::{bounded_output, is_in_bounds, math::mul_div_u64, split_route::SplitRoute},
     errors::ErrorCode,
-    state::BoundedStrategyV2,
+    events::{BoundedTradeV2Event, StrategyFundedV2Event},
+    instructions::{validate_stop_loss_trigger, AdditionalData, PreTrade, TradeBound},
+    oco_pair_signer_seeds,
+    state::{BoundedStrategyV2, OcoPair},
     strategy_signer_seeds,
 };
 
@@ -16,6 +19,7 @@ pub struct BoundedTradeV2<'info> {
     /// Anyone can fire this transaction
     payer: Signer<'info>,
     /// The BoundedStrategy account
+    #[account(mut)]
     strategy: Box<Account<'info, BoundedStrategyV2>>,
     #[account(
         mut,
@@ -24,36 +28,84 @@ pub struct BoundedTradeV2<'info> {
     )]
     pub order_payer: Box<Account<'info, TokenAccount>>,
 
-    // Validate the key matches BoundedStrategy
-    #[account(
-        mut,
-        constraint = deposit_account.key() == strategy.deposit_address
-            @ ErrorCode::DepositAddressMisMatch
-    )]
+    /// The strategy's deposit address, or one of its basket's outputs. Validated in the handler.
+    #[account(mut)]
     pub deposit_account: Box<Account<'info, TokenAccount>>,
     pub token_program: Program<'info, Token>,
 }
 
+/// The result of a trade, returned to callers via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TradeReceipt {
+    /// The amount of collateral sold
+    pub input_used: u64,
+    /// The total output of the route
+    pub output_gained: u64,
+    /// The share of the output transferred to the deposit address
+    pub deposit_amount: u64,
+    /// The number of legs in the executed route
+    pub legs_executed: u8,
+    /// The executed price in the same terms as the strategy's bounded price, i.e. the input
+    /// used over the amount deposited
+    pub effective_price_numerator: u64,
+    pub effective_price_denominator: u64,
+}
+
 pub fn handler<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, BoundedTradeV2<'info>>,
     additional_data: Vec<u8>,
-) -> Result<()> {
+) -> Result<TradeReceipt> {
     let bounded_strategy = &ctx.accounts.strategy;
 
-    // Validate that the reclaim date has not passed.
-    if bounded_strategy.reclaim_date < Clock::get()?.unix_timestamp {
-        return Err(ErrorCode::ReclaimDateHasPassed.into());
-    }
+    // Validate that the strategy can trade, load its companion accounts and resolve the price
+    //  and size it trades at
+    let clock = Clock::get()?;
+    let PreTrade {
+        oco_pair,
+        mut downstream,
+        mut basket,
+        deposit_split,
+        mut executor_payment,
+        route_accounts,
+        bound:
+            TradeBound {
+                tier: active_tier,
+                bounded_price_numerator,
+                bounded_price_denominator,
+                available_input,
+            },
+    } = PreTrade::load(
+        bounded_strategy,
+        &ctx.accounts.order_payer,
+        &ctx.accounts.deposit_account,
+        ctx.remaining_accounts,
+        &clock,
+    )?;
+    // OCO strategies claim the shared collateral from the pair on their first fill, which
+    //  leaves the sibling unable to trade
+    let oco_pair = match oco_pair {
+        Some(mut oco_pair) => {
+            claim_oco_collateral(&ctx, &mut oco_pair)?;
+            Some(oco_pair)
+        }
+        None => None,
+    };
 
     // store balance data in memory for end of instruction checks
     let starting_input_balance = ctx.accounts.order_payer.amount;
-    let starting_destination_balance = ctx.accounts.deposit_account.amount;
+    let starting_destination_balance = match &executor_payment {
+        Some((proceeds_account, _)) => proceeds_account.amount,
+        None => ctx.accounts.deposit_account.amount,
+    };
 
     // Build the route
-    let route = Route::create(
-        ctx.remaining_accounts,
-        VecDeque::from(additional_data.to_vec()),
+    let additional_data = AdditionalData::unpack(&additional_data)?;
+    let mut route = SplitRoute::create(
+        route_accounts,
+        &additional_data.legs,
+        &additional_data.branch_leg_counts,
     )?;
+    route.set_executor_fee_bps(bounded_strategy.executor_fee_bps);
     // Validate that the route starts and ends with the right tokens
     if ctx.accounts.order_payer.mint != route.start_mint()? {
         return Err(error!(ErrorCode::InputMintMismatch));
@@ -62,87 +114,243 @@ pub fn handler<'a, 'b, 'c, 'info>(
         return Err(error!(ErrorCode::OutputMintMismatch));
     }
 
-    // Get the input token account balance
-    let input_tokens = ctx.accounts.order_payer.amount;
-    // Test the maxiumum amount of tokens the payer has in order to off load all at once.
-    let input_amount = if route.simple_price_check(
+    let input_tokens =
+        additional_data.input_upper_bound(bounded_strategy.clamp_fill_input(available_input));
+    // Test the maxiumum amount of tokens the payer has in order to off load all at once,
+    //  otherwise search for the largest trade within the bound. Split routes allocate the
+    //  input across their branches.
+    let allocation = route.max_input_in_bounds(
         input_tokens,
-        &bounded_strategy.bounded_price_numerator,
-        &bounded_strategy.bounded_price_denominator,
+        &bounded_price_numerator,
+        &bounded_price_denominator,
+        additional_data.search_iterations(),
+    );
+    let input_amount: u64 = allocation.iter().sum();
+    if !route.simple_price_check(
+        &allocation,
+        &bounded_price_numerator,
+        &bounded_price_denominator,
     ) {
-        input_tokens
-    } else {
-        // Trade input calculation
-        let input_amount = route.calculate_max_input(
-            input_tokens,
-            &bounded_strategy.bounded_price_numerator,
-            &bounded_strategy.bounded_price_denominator,
-            16,
-        );
-        if !route.simple_price_check(
-            input_amount,
-            &bounded_strategy.bounded_price_numerator,
-            &bounded_strategy.bounded_price_denominator,
-        ) {
-            return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
-        }
-        input_amount
-    };
-    // Delegate the input amount to the trader
-    let token_approve_accts = token::Approve {
-        to: ctx.accounts.order_payer.to_account_info(),
-        delegate: ctx.accounts.payer.to_account_info(),
-        authority: ctx.accounts.strategy.to_account_info(),
-    };
-    let cpi_ctx = CpiContext {
-        program: ctx.accounts.token_program.to_account_info(),
-        accounts: token_approve_accts,
-        remaining_accounts: Vec::new(),
-        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
-    };
-    token::approve(cpi_ctx, input_amount)?;
-
-    // Execute the trade route
-    route.execute(
+        return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
+    }
+    bounded_strategy.validate_fill_input(input_amount, available_input)?;
+    validate_stop_loss_trigger(
+        oco_pair.as_deref(),
+        &ctx.accounts.strategy.key(),
+        &route,
         input_amount,
-        &[strategy_signer_seeds!(&ctx.accounts.strategy)],
+    )?;
+    // Delegate the input amount to the trader, execute the trade route and revoke the delegation
+    let order_payer_info = ctx.accounts.order_payer.to_account_info();
+    route.execute_delegated(
+        &allocation,
+        &ctx.accounts.token_program.to_account_info(),
+        &order_payer_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.strategy.to_account_info(),
+        &[strategy_signer_seeds!(ctx.accounts.strategy)],
     )?;
 
-    // Sanity check the deltas for input and output accounts
+    // Sanity check the deltas for input and output accounts, which combine every branch of a
+    //  split route
     ctx.accounts.order_payer.reload()?;
-    ctx.accounts.deposit_account.reload()?;
+    let ending_destination_balance = match &mut executor_payment {
+        Some((proceeds_account, _)) => {
+            proceeds_account.reload()?;
+            proceeds_account.amount
+        }
+        None => {
+            ctx.accounts.deposit_account.reload()?;
+            ctx.accounts.deposit_account.amount
+        }
+    };
 
     let ending_input_balance = ctx.accounts.order_payer.amount;
-    let ending_destination_balance = ctx.accounts.deposit_account.amount;
     let input_tokens_used = starting_input_balance
         .checked_sub(ending_input_balance)
         .unwrap();
     let destination_tokens_gained = ending_destination_balance
         .checked_sub(starting_destination_balance)
         .unwrap();
+    // Enforce the fill limits on what was actually traded
+    bounded_strategy.validate_fill_input(input_tokens_used, available_input)?;
+
+    // The executor is paid their fee out of the proceeds
+    let executor_fee = mul_div_u64(
+        destination_tokens_gained,
+        bounded_strategy.executor_fee_bps.into(),
+        BPS_DENOMINATOR,
+    )
+    .unwrap();
+    let net_tokens_gained = destination_tokens_gained - executor_fee;
+    // Split any output above the bound between the executor and the strategy
+    let surplus = net_tokens_gained.saturating_sub(bounded_output(
+        input_tokens_used,
+        &bounded_price_numerator,
+        &bounded_price_denominator,
+    ));
+    let executor_surplus = mul_div_u64(
+        surplus,
+        bounded_strategy.surplus_share_bps.into(),
+        BPS_DENOMINATOR,
+    )
+    .unwrap();
+    let deposit_tokens_gained = net_tokens_gained - executor_surplus;
+    if let Some((proceeds_account, executor_token_account)) = &executor_payment {
+        settle_executor_payment(
+            &ctx,
+            proceeds_account,
+            executor_token_account,
+            destination_tokens_gained - deposit_tokens_gained,
+            deposit_tokens_gained,
+        )?;
+    }
 
     if !is_in_bounds(
         input_tokens_used,
-        destination_tokens_gained,
-        &bounded_strategy.bounded_price_numerator,
-        &bounded_strategy.bounded_price_denominator,
+        deposit_tokens_gained,
+        &bounded_price_numerator,
+        &bounded_price_denominator,
     ) {
         // If actual changes are out of bounds, rollback
         return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
     }
-
-    // Revoke the delegated amount from the trader
-    let token_revoke_accts = token::Revoke {
-        source: ctx.accounts.order_payer.to_account_info(),
-        authority: ctx.accounts.strategy.to_account_info(),
+    // Record the size traded against the active tier
+    ctx.accounts.strategy.record_fill(
+        active_tier,
+        input_tokens_used,
+        deposit_tokens_gained,
+        &clock,
+    );
+    // Record the basket output's fill progress
+    if let Some((basket, index)) = &mut basket {
+        basket.record_fill(*index, input_tokens_used, deposit_tokens_gained);
+        basket.exit(&crate::ID)?;
+    }
+    // The deposit funds the downstream strategy, which can trade from now on
+    if let Some(downstream) = &mut downstream {
+        downstream.awaiting_upstream = false;
+        downstream.exit(&crate::ID)?;
+        emit!(StrategyFundedV2Event {
+            strategy: downstream.key(),
+            funder: ctx.accounts.strategy.key(),
+            amount: deposit_tokens_gained,
+        });
+    }
+    // Pay the deposited proceeds out to the split's recipients
+    let payouts = match &deposit_split {
+        Some((deposit_split, recipients)) => {
+            let payouts = deposit_split.payouts(deposit_tokens_gained);
+            for (amount, recipient) in payouts.iter().zip(recipients.iter()) {
+                if *amount == 0 {
+                    continue;
+                }
+                let cpi_ctx = CpiContext {
+                    program: ctx.accounts.token_program.to_account_info(),
+                    accounts: Transfer {
+                        from: ctx.accounts.deposit_account.to_account_info(),
+                        to: recipient.clone(),
+                        authority: ctx.accounts.strategy.to_account_info(),
+                    },
+                    remaining_accounts: Vec::new(),
+                    signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+                };
+                token::transfer(cpi_ctx, *amount)?;
+            }
+            payouts
+        }
+        None => Vec::new(),
     };
+
+    emit!(BoundedTradeV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        executor: ctx.accounts.payer.key(),
+        leg_dex_ids: route.dex_program_ids(),
+        price_tier: active_tier.map(|index| index as u8),
+        basket_output: basket.as_ref().map(|(_, index)| *index as u8),
+        input_used: input_tokens_used,
+        output_gained: destination_tokens_gained,
+        deposit_amount: deposit_tokens_gained,
+        executor_amount: destination_tokens_gained - deposit_tokens_gained,
+        payouts,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(TradeReceipt {
+        input_used: input_tokens_used,
+        output_gained: destination_tokens_gained,
+        deposit_amount: deposit_tokens_gained,
+        legs_executed: route.leg_count() as u8,
+        effective_price_numerator: input_tokens_used,
+        effective_price_denominator: deposit_tokens_gained,
+    })
+}
+
+///
+/// Load the proceeds account and the executor's token account from the head of the remaining
+/// accounts. The proceeds account must be owned by the strategy, and both accounts must share
+/// the deposit account's mint.
+///
+/// Pay the executor's share of the proceeds to the executor and forward the strategy's share to
+/// the deposit account, unless the route already ended in it
+///
+fn settle_executor_payment<'info>(
+    ctx: &Context<'_, '_, '_, 'info, BoundedTradeV2<'info>>,
+    proceeds_account: &Account<'info, TokenAccount>,
+    executor_token_account: &Account<'info, TokenAccount>,
+    executor_amount: u64,
+    deposit_amount: u64,
+) -> Result<()> {
+    let mut payments = vec![(executor_token_account.to_account_info(), executor_amount)];
+    if proceeds_account.key() != ctx.accounts.deposit_account.key() {
+        payments.push((
+            ctx.accounts.deposit_account.to_account_info(),
+            deposit_amount,
+        ));
+    }
+    for (destination, amount) in payments {
+        if amount == 0 {
+            continue;
+        }
+        let cpi_ctx = CpiContext {
+            program: ctx.accounts.token_program.to_account_info(),
+            accounts: Transfer {
+                from: proceeds_account.to_account_info(),
+                to: destination,
+                authority: ctx.accounts.strategy.to_account_info(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+        };
+        token::transfer(cpi_ctx, amount)?;
+    }
+    Ok(())
+}
+
+///
+/// Load the strategy's DepositSplit and its recipients' token accounts from the head of the
+/// remaining accounts. The recipients must be in the same order as in the split.
+///
+/// Hand the OCO pair's collateral account to the strategy on its first fill
+///
+fn claim_oco_collateral<'info>(
+    ctx: &Context<'_, '_, '_, 'info, BoundedTradeV2<'info>>,
+    oco_pair: &mut Account<'info, OcoPair>,
+) -> Result<()> {
+    if oco_pair.is_filled() {
+        return Ok(());
+    }
+    let strategy_key = ctx.accounts.strategy.key();
     let cpi_ctx = CpiContext {
         program: ctx.accounts.token_program.to_account_info(),
-        accounts: token_revoke_accts,
+        accounts: SetAuthority {
+            current_authority: oco_pair.to_account_info(),
+            account_or_mint: ctx.accounts.order_payer.to_account_info(),
+        },
         remaining_accounts: Vec::new(),
-        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+        signer_seeds: &[oco_pair_signer_seeds!(oco_pair)],
     };
-    token::revoke(cpi_ctx)?;
-
-    Ok(())
+    token::set_authority(cpi_ctx, AuthorityType::AccountOwner, Some(strategy_key))?;
+    oco_pair.filled_by = strategy_key;
+    oco_pair.exit(&crate::ID)
 }
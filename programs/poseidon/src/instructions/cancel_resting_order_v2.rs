@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::dex::CancelOrderV2;
+
+use crate::{
+    constants::BOUNDED_STRATEGY_SEED,
+    dexes::open_book_dex,
+    errors::ErrorCode,
+    serum_utils::{self, OpenOrdersBalances},
+    state::BoundedStrategyV2,
+    strategy_signer_seeds,
+};
+
+#[derive(Accounts)]
+pub struct CancelRestingOrderV2<'info> {
+    /// Anyone can fire this transaction
+    pub payer: Signer<'info>,
+    /// The BoundedStrategy account
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    /// CHECK: Constraints are handled
+    #[account(
+        mut,
+        constraint = strategy.has_open_orders() @ ErrorCode::OpenOrdersNotInitialized,
+        constraint = strategy.open_orders_key() == Some(open_orders.key())
+            @ ErrorCode::WrongOpenOrdersKey,
+    )]
+    pub open_orders: UncheckedAccount<'info>,
+    /// CHECK: Constraints are handled
+    #[account(
+        mut,
+        owner = open_book_dex::ID
+    )]
+    pub serum_market: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub bids: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub asks: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub event_queue: UncheckedAccount<'info>,
+    /// The Serum program
+    pub dex_program: Program<'info, open_book_dex::OpenBookDexV3>,
+}
+
+///
+/// Cancel the strategy's resting order once the reclaim date has passed. The cancelled funds can
+/// be settled straight away, but OpenBook only frees the order's slot once the cancel event is
+/// consumed, so reclaiming has to wait for the event queue to be cranked.
+///
+pub fn handler(ctx: Context<CancelRestingOrderV2>) -> Result<()> {
+    if ctx.accounts.strategy.reclaim_date > Clock::get()?.unix_timestamp {
+        return Err(ErrorCode::ReclaimDateHasNotPassed.into());
+    }
+    let balances = OpenOrdersBalances::load(&ctx.accounts.open_orders.try_borrow_data()?)?;
+    if !balances.has_orders() {
+        return Err(error!(ErrorCode::NoRestingOrder));
+    }
+
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.dex_program.to_account_info(),
+        accounts: CancelOrderV2 {
+            market: ctx.accounts.serum_market.to_account_info(),
+            market_bids: ctx.accounts.bids.to_account_info(),
+            market_asks: ctx.accounts.asks.to_account_info(),
+            open_orders: ctx.accounts.open_orders.to_account_info(),
+            open_orders_authority: ctx.accounts.strategy.to_account_info(),
+            event_queue: ctx.accounts.event_queue.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    serum_utils::cancel_resting_order(cpi_ctx)
+}
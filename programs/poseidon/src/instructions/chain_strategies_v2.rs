@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    errors::ErrorCode,
+    events::ChainStrategiesV2Event,
+    state::{BoundedStrategyV2, StrategyExtension},
+};
+
+#[derive(Accounts)]
+pub struct ChainStrategiesV2<'info> {
+    /// The owner of both strategies' reclaim accounts
+    pub payer: Signer<'info>,
+    /// The strategy whose proceeds fund the downstream strategy
+    #[account(
+        mut,
+        constraint = upstream.key() != downstream.key() @ ErrorCode::StrategiesCannotBeChained,
+    )]
+    pub upstream: Box<Account<'info, BoundedStrategyV2>>,
+    /// The strategy that sells the upstream strategy's proceeds
+    #[account(mut)]
+    pub downstream: Box<Account<'info, BoundedStrategyV2>>,
+    /// The upstream strategy's current deposit account, whose mint the downstream must sell
+    #[account(
+        constraint = deposit_account.key() == upstream.deposit_address
+            @ ErrorCode::DepositAddressMisMatch,
+    )]
+    pub deposit_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = downstream_collateral.key() == downstream.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+        constraint = downstream_collateral.mint == deposit_account.mint
+            @ ErrorCode::OutputMintMismatch,
+    )]
+    pub downstream_collateral: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = reclaim_account.key() == upstream.reclaim_address
+            @ ErrorCode::WrongReclaimAddress,
+        constraint = reclaim_account.owner == payer.key()
+            @ ErrorCode::AuthorityMisMatch,
+    )]
+    pub reclaim_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = downstream_reclaim_account.key() == downstream.reclaim_address
+            @ ErrorCode::WrongReclaimAddress,
+        constraint = downstream_reclaim_account.owner == payer.key()
+            @ ErrorCode::AuthorityMisMatch,
+    )]
+    pub downstream_reclaim_account: Box<Account<'info, TokenAccount>>,
+}
+
+///
+/// Chain two strategies so the upstream strategy deposits its proceeds into the downstream
+/// strategy's collateral account. The downstream strategy cannot trade until the upstream
+/// strategy's first fill, or a deposit, funds it, and must not have traded yet.
+///
+pub fn handler(ctx: Context<ChainStrategiesV2>) -> Result<()> {
+    if ctx.accounts.upstream.has_extension() {
+        return Err(error!(ErrorCode::StrategyHasExtension));
+    }
+    // Only strategies at the head of a chain can gain a downstream, so chains cannot form a
+    //  cycle. Longer chains are built from their last strategy backwards.
+    if ctx.accounts.upstream.has_upstream() {
+        return Err(error!(ErrorCode::UpstreamIsChained));
+    }
+    // The downstream's collateral must stay where the upstream deposits, and be sold in full
+    let downstream = &ctx.accounts.downstream;
+    if downstream.fill_stats.trade_count > 0
+        || downstream.basket_key().is_some()
+        || downstream.oco_pair_key().is_some()
+        || downstream.has_upstream()
+    {
+        return Err(error!(ErrorCode::StrategiesCannotBeChained));
+    }
+
+    let upstream = &mut ctx.accounts.upstream;
+    upstream.deposit_address = ctx.accounts.downstream_collateral.key();
+    upstream.extension = StrategyExtension::Downstream {
+        strategy: ctx.accounts.downstream.key(),
+    };
+    let downstream = &mut ctx.accounts.downstream;
+    downstream.upstream = ctx.accounts.upstream.key();
+    downstream.awaiting_upstream = true;
+
+    emit!(ChainStrategiesV2Event {
+        upstream: ctx.accounts.upstream.key(),
+        downstream: ctx.accounts.downstream.key(),
+        collateral_account: ctx.accounts.downstream_collateral.key(),
+    });
+
+    Ok(())
+}
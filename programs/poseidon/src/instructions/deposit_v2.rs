@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{errors::ErrorCode, events::StrategyFundedV2Event, state::BoundedStrategyV2};
+
+#[derive(Accounts)]
+pub struct DepositV2<'info> {
+    /// The owner of the strategy's reclaim account
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        mut,
+        constraint = collateral_account.key() == strategy.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub collateral_account: Box<Account<'info, TokenAccount>>,
+    /// The payer's token account the collateral is transferred from
+    #[account(
+        mut,
+        constraint = source_account.mint == collateral_account.mint
+            @ ErrorCode::InputMintMismatch,
+    )]
+    pub source_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = reclaim_account.key() == strategy.reclaim_address
+            @ ErrorCode::WrongReclaimAddress,
+        constraint = reclaim_account.owner == payer.key()
+            @ ErrorCode::AuthorityMisMatch,
+    )]
+    pub reclaim_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+///
+/// Deposit collateral into the strategy's collateral account. A strategy chained behind an
+/// upstream strategy can trade from then on, so it is not left waiting on an upstream strategy
+/// that may never fill.
+///
+pub fn handler(ctx: Context<DepositV2>, amount: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.source_account.to_account_info(),
+        to: ctx.accounts.collateral_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    ctx.accounts.strategy.awaiting_upstream = false;
+
+    emit!(StrategyFundedV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        funder: ctx.accounts.payer.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+impl<'info> DepositV2<'info> {
+    pub fn valid_arguments(amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(error!(ErrorCode::DepositAmountIsZero));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    constants::BOUNDED_STRATEGY_SEED, dexes::is_in_bounds, errors::ErrorCode,
+    events::FillStrategyV2Event, instructions::TradeReceipt, state::BoundedStrategyV2,
+    strategy_signer_seeds,
+};
+
+#[derive(Accounts)]
+pub struct FillStrategyV2<'info> {
+    /// The market maker filling the strategy from their own inventory
+    pub maker: Signer<'info>,
+    /// The BoundedStrategy account
+    #[account(
+        mut,
+        constraint = !strategy.requires_route() @ ErrorCode::StrategyRequiresRoute,
+    )]
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        mut,
+        constraint = order_payer.key() == strategy.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub order_payer: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = deposit_account.key() == strategy.deposit_address
+            @ ErrorCode::DepositAddressMisMatch
+    )]
+    pub deposit_account: Box<Account<'info, TokenAccount>>,
+    /// The maker's token account the output is paid from
+    #[account(
+        mut,
+        constraint = maker_source_account.mint == deposit_account.mint
+            @ ErrorCode::OutputMintMismatch
+    )]
+    pub maker_source_account: Box<Account<'info, TokenAccount>>,
+    /// The maker's token account that receives the collateral
+    #[account(
+        mut,
+        constraint = maker_destination_account.mint == order_payer.mint
+            @ ErrorCode::InputMintMismatch
+    )]
+    pub maker_destination_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(
+    ctx: Context<FillStrategyV2>,
+    input_amount: u64,
+    output_amount: u64,
+) -> Result<TradeReceipt> {
+    let bounded_strategy = &ctx.accounts.strategy;
+    let clock = Clock::get()?;
+    bounded_strategy.validate_can_trade(&clock)?;
+
+    // Laddered strategies are filled at the active tier's price, up to the tier's size
+    let active_tier = bounded_strategy.trading_tier()?;
+    let (bounded_price_numerator, bounded_price_denominator) =
+        bounded_strategy.bounded_price(active_tier);
+    let available_input =
+        bounded_strategy.available_input(ctx.accounts.order_payer.amount, active_tier);
+    if input_amount > available_input {
+        return Err(error!(ErrorCode::FillExceedsAvailableInput));
+    }
+    bounded_strategy.validate_fill_input(input_amount, available_input)?;
+    if !is_in_bounds(
+        input_amount,
+        output_amount,
+        &bounded_price_numerator,
+        &bounded_price_denominator,
+    ) {
+        return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
+    }
+
+    // The maker pays the output to the deposit address and receives the collateral
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.maker_source_account.to_account_info(),
+        to: ctx.accounts.deposit_account.to_account_info(),
+        authority: ctx.accounts.maker.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, output_amount)?;
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.token_program.to_account_info(),
+        accounts: Transfer {
+            from: ctx.accounts.order_payer.to_account_info(),
+            to: ctx.accounts.maker_destination_account.to_account_info(),
+            authority: ctx.accounts.strategy.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    token::transfer(cpi_ctx, input_amount)?;
+
+    ctx.accounts
+        .strategy
+        .record_fill(active_tier, input_amount, output_amount, &clock);
+
+    emit!(FillStrategyV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        maker: ctx.accounts.maker.key(),
+        price_tier: active_tier.map(|index| index as u8),
+        input_used: input_amount,
+        deposit_amount: output_amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(TradeReceipt {
+        input_used: input_amount,
+        output_gained: output_amount,
+        deposit_amount: output_amount,
+        legs_executed: 0,
+        effective_price_numerator: input_amount,
+        effective_price_denominator: output_amount,
+    })
+}
+
+impl<'info> FillStrategyV2<'info> {
+    pub fn valid_arguments(input_amount: u64, output_amount: u64) -> Result<()> {
+        if input_amount == 0 || output_amount == 0 {
+            return Err(error!(ErrorCode::FillAmountIsZero));
+        }
+        Ok(())
+    }
+}
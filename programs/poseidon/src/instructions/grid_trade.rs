@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    constants::GRID_STRATEGY_SEED,
+    dexes::{settle_trade, split_route::SplitRoute},
+    errors::ErrorCode,
+    events::GridTradeEvent,
+    grid_signer_seeds,
+    instructions::{AdditionalData, TradeReceipt},
+    state::GridStrategy,
+};
+
+#[derive(Accounts)]
+pub struct GridTrade<'info> {
+    /// Anyone can fire this transaction
+    pub payer: Signer<'info>,
+    /// The GridStrategy account
+    #[account(mut)]
+    pub strategy: Box<Account<'info, GridStrategy>>,
+    #[account(
+        mut,
+        constraint = base_account.key() == strategy.base_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub base_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = quote_account.key() == strategy.quote_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub quote_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+///
+/// Fill one range of the grid through a route. An unsold range sells its base for quote at or
+/// above its upper level, a sold range buys the base back at or below its lower level.
+///
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, GridTrade<'info>>,
+    range: u8,
+    additional_data: Vec<u8>,
+) -> Result<TradeReceipt> {
+    let grid = &ctx.accounts.strategy;
+    let clock = Clock::get()?;
+    grid.validate_can_trade(&clock)?;
+    let trade = grid.range_trade(range)?;
+
+    let (source_account, destination_account) = if trade.sells_base {
+        (&ctx.accounts.base_account, &ctx.accounts.quote_account)
+    } else {
+        (&ctx.accounts.quote_account, &ctx.accounts.base_account)
+    };
+    if source_account.amount < trade.input_amount {
+        return Err(error!(ErrorCode::GridLevelUnfunded));
+    }
+
+    // Build the route, which must trade from the source side of the grid to the other
+    let additional_data = AdditionalData::unpack(&additional_data)?;
+    let route = SplitRoute::create(
+        ctx.remaining_accounts,
+        &additional_data.legs,
+        &additional_data.branch_leg_counts,
+    )?;
+    if source_account.mint != route.start_mint()? {
+        return Err(error!(ErrorCode::InputMintMismatch));
+    }
+    if destination_account.mint != route.end_mint()? {
+        return Err(error!(ErrorCode::OutputMintMismatch));
+    }
+
+    // The range trades its full size or not at all
+    let allocation = route.allocate(trade.input_amount);
+    if !route.simple_price_check(
+        &allocation,
+        &trade.bounded_price_numerator,
+        &trade.bounded_price_denominator,
+    ) {
+        return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
+    }
+
+    let starting_balances = (source_account.amount, destination_account.amount);
+    let source_account_info = source_account.to_account_info();
+    route.execute_delegated(
+        &allocation,
+        &ctx.accounts.token_program.to_account_info(),
+        &source_account_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.strategy.to_account_info(),
+        &[grid_signer_seeds!(ctx.accounts.strategy)],
+    )?;
+
+    // Check the actual deltas against the range's bound
+    let (source_account, destination_account) = if trade.sells_base {
+        (
+            &mut ctx.accounts.base_account,
+            &mut ctx.accounts.quote_account,
+        )
+    } else {
+        (
+            &mut ctx.accounts.quote_account,
+            &mut ctx.accounts.base_account,
+        )
+    };
+    let (input_tokens_used, output_tokens_gained) = settle_trade(
+        source_account,
+        destination_account,
+        starting_balances,
+        &trade.bounded_price_numerator,
+        &trade.bounded_price_denominator,
+    )?;
+
+    ctx.accounts
+        .strategy
+        .record_range_fill(range, input_tokens_used)?;
+
+    emit!(GridTradeEvent {
+        strategy: ctx.accounts.strategy.key(),
+        executor: ctx.accounts.payer.key(),
+        range,
+        sold_base: trade.sells_base,
+        input_used: input_tokens_used,
+        output_gained: output_tokens_gained,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(TradeReceipt {
+        input_used: input_tokens_used,
+        output_gained: output_tokens_gained,
+        deposit_amount: output_tokens_gained,
+        legs_executed: route.leg_count() as u8,
+        effective_price_numerator: input_tokens_used,
+        effective_price_denominator: output_tokens_gained,
+    })
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    constants::{BASKET_SEED, BPS_DENOMINATOR},
+    errors::ErrorCode,
+    events::InitBasketV2Event,
+    state::{
+        Basket, BasketOutput, BasketOutputParams, BoundedStrategyV2, StrategyExtension,
+        MAX_BASKET_OUTPUTS,
+    },
+};
+
+#[derive(Accounts)]
+pub struct InitBasketV2<'info> {
+    /// The owner of the strategy's reclaim account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = strategy.basket_key().is_none() @ ErrorCode::BasketAlreadyInitialized,
+        constraint = !strategy.has_extension() @ ErrorCode::StrategyHasExtension,
+    )]
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        init,
+        seeds = [strategy.key().as_ref(), BASKET_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        space = Basket::LEN,
+    )]
+    pub basket: Box<Account<'info, Basket>>,
+    #[account(
+        constraint = collateral_account.key() == strategy.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub collateral_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = reclaim_account.key() == strategy.reclaim_address
+            @ ErrorCode::WrongReclaimAddress,
+        constraint = reclaim_account.owner == payer.key()
+            @ ErrorCode::AuthorityMisMatch,
+    )]
+    pub reclaim_account: Box<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+}
+
+///
+/// Split a strategy's collateral into a basket of outputs by weight. The ctx.remaining_accounts
+/// hold each output's deposit account, in the same order as the outputs. The strategy must not
+/// have traded yet.
+///
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitBasketV2<'info>>,
+    outputs: Vec<BasketOutputParams>,
+) -> Result<()> {
+    let strategy = &ctx.accounts.strategy;
+    // Collateral that arrives after the basket is allocated would never be sold
+    if strategy.fill_stats.trade_count > 0 || strategy.is_laddered() || strategy.has_upstream() {
+        return Err(error!(ErrorCode::StrategyCannotHaveBasket));
+    }
+    if ctx.remaining_accounts.len() != outputs.len() {
+        return Err(error!(ErrorCode::InvalidBasketOutputs));
+    }
+
+    let basket = &mut ctx.accounts.basket;
+    basket.strategy = strategy.key();
+    basket.bump = *ctx.bumps.get("basket").unwrap();
+    for (index, (params, deposit_info)) in outputs.iter().zip(ctx.remaining_accounts).enumerate() {
+        // Proceeds can only be deposited to accounts the DAO owns
+        let deposit_account = Account::<TokenAccount>::try_from(deposit_info)?;
+        if deposit_account.owner != ctx.accounts.reclaim_account.owner {
+            return Err(error!(ErrorCode::BadDepositAddress));
+        }
+        if deposit_account.mint == strategy.collateral_mint {
+            return Err(error!(ErrorCode::InvalidBasketOutputs));
+        }
+        if basket.output_index(&deposit_account.key()).is_ok() {
+            return Err(error!(ErrorCode::InvalidBasketOutputs));
+        }
+        basket.outputs[index] = BasketOutput {
+            deposit_address: deposit_account.key(),
+            weight_bps: params.weight_bps,
+            bounded_price_numerator: params.bounded_price_numerator,
+            bounded_price_denominator: params.bounded_price_denominator,
+            ..BasketOutput::default()
+        };
+    }
+    basket.allocate(ctx.accounts.collateral_account.amount);
+
+    ctx.accounts.strategy.extension = StrategyExtension::Basket {
+        basket: ctx.accounts.basket.key(),
+    };
+
+    emit!(InitBasketV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        basket: ctx.accounts.basket.key(),
+        outputs: ctx.accounts.basket.outputs[..outputs.len()].to_vec(),
+    });
+
+    Ok(())
+}
+
+impl<'info> InitBasketV2<'info> {
+    pub fn valid_arguments(outputs: &[BasketOutputParams]) -> Result<()> {
+        if outputs.is_empty() || outputs.len() > MAX_BASKET_OUTPUTS {
+            return Err(error!(ErrorCode::InvalidBasketOutputs));
+        }
+        let mut total_weight: u64 = 0;
+        for output in outputs {
+            if output.bounded_price_numerator == 0 || output.bounded_price_denominator == 0 {
+                return Err(error!(ErrorCode::BoundPriceIsZero));
+            }
+            total_weight += u64::from(output.weight_bps);
+        }
+        if total_weight != BPS_DENOMINATOR {
+            return Err(error!(ErrorCode::BasketWeightsDoNotSumToWhole));
+        }
+        Ok(())
+    }
+}
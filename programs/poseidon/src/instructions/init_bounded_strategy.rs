@@ -14,6 +14,7 @@ use crate::{
     constants::{AUTHORITY_SEED, BOUNDED_STRATEGY_SEED, OPEN_ORDERS_SEED, ORDER_PAYER_SEED},
     dexes::open_book_dex,
     errors::ErrorCode,
+    events::InitBoundedStrategyEvent,
     open_orders_signer_seeds,
     state::BoundedStrategy,
 };
@@ -197,6 +198,20 @@ pub fn handler(
     bounded_strategy.authority_bump = authority_bump;
     bounded_strategy.serum_dex_id = ctx.accounts.dex_program.key();
 
+    emit!(InitBoundedStrategyEvent {
+        strategy: ctx.accounts.strategy.key(),
+        serum_market: ctx.accounts.serum_market.key(),
+        mint: ctx.accounts.mint.key(),
+        order_payer: ctx.accounts.order_payer.key(),
+        deposit_address: ctx.accounts.deposit_account.key(),
+        reclaim_address: ctx.accounts.reclaim_account.key(),
+        transfer_amount,
+        bound_price,
+        reclaim_date,
+        order_side,
+        bound,
+    });
+
     Ok(())
 }
 
@@ -2,13 +2,14 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
 use crate::{
-    constants::{BOUNDED_STRATEGY_SEED, ORDER_PAYER_SEED},
+    constants::{BOUNDED_STRATEGY_SEED, BPS_DENOMINATOR, ORDER_PAYER_SEED},
     errors::ErrorCode,
-    state::BoundedStrategyV2,
+    events::InitBoundedStrategyV2Event,
+    state::{start_date_seed, BoundedStrategyV2, PriceTier, MAX_PRICE_TIERS},
 };
 
 #[derive(Accounts)]
-#[instruction(transfer_amount: u64, bounded_price_numerator: u64, bounded_price_denominator: u64, reclaim_date: i64)]
+#[instruction(transfer_amount: u64, bounded_price_numerator: u64, bounded_price_denominator: u64, start_date: i64, reclaim_date: i64)]
 pub struct InitBoundedStrategyV2<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -27,7 +28,7 @@ pub struct InitBoundedStrategyV2<'info> {
     /// users will be uniquely constrained by these values.
     #[account(
     init,
-    seeds = [mint.key().as_ref(), &bounded_price_numerator.to_le_bytes(), &bounded_price_denominator.to_le_bytes(), &reclaim_date.to_le_bytes(), BOUNDED_STRATEGY_SEED.as_bytes()],
+    seeds = [mint.key().as_ref(), &bounded_price_numerator.to_le_bytes(), &bounded_price_denominator.to_le_bytes(), &start_date_seed(start_date)[..], &reclaim_date.to_le_bytes(), BOUNDED_STRATEGY_SEED.as_bytes()],
     payer = payer,
     bump,
     space = BoundedStrategyV2::LEN,
@@ -55,12 +56,20 @@ pub struct InitBoundedStrategyV2<'info> {
 
 /// The ctx.remaining_accounts should contain a list of account infos in the
 /// exact order that the Leg's require.
+#[allow(clippy::too_many_arguments)]
 pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, InitBoundedStrategyV2<'info>>,
     transfer_amount: u64,
     bounded_price_numerator: u64,
     bounded_price_denominator: u64,
+    start_date: i64,
     reclaim_date: i64,
+    price_tiers: Vec<PriceTier>,
+    executor_fee_bps: u16,
+    surplus_share_bps: u16,
+    min_fill_input: u64,
+    max_fill_input: u64,
+    min_slots_between_trades: u64,
 ) -> Result<()> {
     // Set BoundedStrategy information
     let strategy_bump = match ctx.bumps.get("strategy") {
@@ -75,10 +84,19 @@ pub fn handler<'info>(
     bounded_strategy.collateral_mint = ctx.accounts.mint.key();
     bounded_strategy.bounded_price_numerator = bounded_price_numerator;
     bounded_strategy.bounded_price_denominator = bounded_price_denominator;
+    bounded_strategy.start_date = start_date;
     bounded_strategy.reclaim_date = reclaim_date;
     bounded_strategy.reclaim_address = ctx.accounts.reclaim_account.key();
     bounded_strategy.deposit_address = ctx.accounts.deposit_account.key();
     bounded_strategy.bump = strategy_bump;
+    bounded_strategy.executor_fee_bps = executor_fee_bps;
+    bounded_strategy.surplus_share_bps = surplus_share_bps;
+    bounded_strategy.min_fill_input = min_fill_input;
+    bounded_strategy.max_fill_input = max_fill_input;
+    bounded_strategy.min_slots_between_trades = min_slots_between_trades;
+    for (index, tier) in price_tiers.iter().enumerate() {
+        bounded_strategy.price_tiers[index] = *tier;
+    }
 
     // Transfer the assets to the remote execution program
     let cpi_accounts = Transfer {
@@ -89,5 +107,71 @@ pub fn handler<'info>(
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     token::transfer(cpi_ctx, transfer_amount)?;
 
+    emit!(InitBoundedStrategyV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        collateral_mint: ctx.accounts.mint.key(),
+        collateral_account: ctx.accounts.collateral_account.key(),
+        deposit_address: ctx.accounts.deposit_account.key(),
+        reclaim_address: ctx.accounts.reclaim_account.key(),
+        transfer_amount,
+        bounded_price_numerator,
+        bounded_price_denominator,
+        start_date,
+        reclaim_date,
+        price_tiers,
+        executor_fee_bps,
+        surplus_share_bps,
+        min_fill_input,
+        max_fill_input,
+        min_slots_between_trades,
+    });
+
     Ok(())
 }
+
+impl<'info> InitBoundedStrategyV2<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn valid_arguments(
+        transfer_amount: u64,
+        start_date: i64,
+        reclaim_date: i64,
+        price_tiers: &[PriceTier],
+        executor_fee_bps: u16,
+        surplus_share_bps: u16,
+        min_fill_input: u64,
+        max_fill_input: u64,
+    ) -> Result<()> {
+        if start_date >= reclaim_date {
+            return Err(error!(ErrorCode::StartDateAfterReclaimDate));
+        }
+        if price_tiers.len() > MAX_PRICE_TIERS {
+            return Err(error!(ErrorCode::TooManyPriceTiers));
+        }
+        let mut tiers_amount: u64 = 0;
+        for tier in price_tiers {
+            if tier.bounded_price_numerator == 0 || tier.bounded_price_denominator == 0 {
+                return Err(error!(ErrorCode::BoundPriceIsZero));
+            }
+            if tier.remaining_amount == 0 {
+                return Err(error!(ErrorCode::PriceTierAmountIsZero));
+            }
+            tiers_amount = tiers_amount
+                .checked_add(tier.remaining_amount)
+                .ok_or(ErrorCode::PriceTiersExceedTransferAmount)?;
+        }
+        // Validate the ladder can be fully funded by the transfer
+        if tiers_amount > transfer_amount {
+            return Err(error!(ErrorCode::PriceTiersExceedTransferAmount));
+        }
+        if u64::from(executor_fee_bps) > BPS_DENOMINATOR {
+            return Err(error!(ErrorCode::ExecutorFeeTooHigh));
+        }
+        if u64::from(surplus_share_bps) > BPS_DENOMINATOR {
+            return Err(error!(ErrorCode::SurplusShareTooHigh));
+        }
+        if max_fill_input > 0 && min_fill_input > max_fill_input {
+            return Err(error!(ErrorCode::MinFillExceedsMaxFill));
+        }
+        Ok(())
+    }
+}
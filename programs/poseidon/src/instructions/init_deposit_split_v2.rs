@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    constants::{BPS_DENOMINATOR, DEPOSIT_SPLIT_SEED, PROCEEDS_SEED},
+    errors::ErrorCode,
+    events::InitDepositSplitV2Event,
+    state::{
+        BoundedStrategyV2, DepositRecipient, DepositSplit, StrategyExtension,
+        MAX_DEPOSIT_RECIPIENTS,
+    },
+};
+
+#[derive(Accounts)]
+pub struct InitDepositSplitV2<'info> {
+    /// The owner of the strategy's reclaim account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = strategy.deposit_split_key().is_none()
+            @ ErrorCode::DepositSplitAlreadyInitialized,
+        constraint = !strategy.has_extension() @ ErrorCode::StrategyHasExtension,
+    )]
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        init,
+        seeds = [strategy.key().as_ref(), DEPOSIT_SPLIT_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        space = DepositSplit::LEN,
+    )]
+    pub deposit_split: Box<Account<'info, DepositSplit>>,
+    /// The strategy's current deposit account, whose mint every recipient must share
+    #[account(
+        constraint = deposit_account.key() == strategy.deposit_address
+            @ ErrorCode::DepositAddressMisMatch,
+    )]
+    pub deposit_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = deposit_mint.key() == deposit_account.mint
+            @ ErrorCode::OutputMintMismatch,
+    )]
+    pub deposit_mint: Box<Account<'info, Mint>>,
+    /// The strategy owned account trades deposit into before the proceeds are split
+    #[account(
+        init,
+        seeds = [strategy.key().as_ref(), PROCEEDS_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        token::mint = deposit_mint,
+        token::authority = strategy
+    )]
+    pub proceeds_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = reclaim_account.key() == strategy.reclaim_address
+            @ ErrorCode::WrongReclaimAddress,
+        constraint = reclaim_account.owner == payer.key()
+            @ ErrorCode::AuthorityMisMatch,
+    )]
+    pub reclaim_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+///
+/// Split a strategy's proceeds between several deposit accounts by weight. The
+/// ctx.remaining_accounts hold each recipient's token account, in the same order as the
+/// weights. The first recipient receives any rounding dust.
+///
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitDepositSplitV2<'info>>,
+    weights_bps: Vec<u16>,
+) -> Result<()> {
+    let strategy = &ctx.accounts.strategy;
+    if ctx.remaining_accounts.len() != weights_bps.len() {
+        return Err(error!(ErrorCode::InvalidDepositRecipients));
+    }
+
+    let deposit_split = &mut ctx.accounts.deposit_split;
+    deposit_split.strategy = strategy.key();
+    deposit_split.bump = *ctx.bumps.get("deposit_split").unwrap();
+    for (index, (weight_bps, recipient_info)) in
+        weights_bps.iter().zip(ctx.remaining_accounts).enumerate()
+    {
+        // Proceeds can only be deposited to accounts the DAO owns
+        let recipient_account = Account::<TokenAccount>::try_from(recipient_info)?;
+        if recipient_account.owner != ctx.accounts.reclaim_account.owner {
+            return Err(error!(ErrorCode::BadDepositAddress));
+        }
+        if recipient_account.mint != ctx.accounts.deposit_account.mint {
+            return Err(error!(ErrorCode::InvalidDepositRecipients));
+        }
+        deposit_split.recipients[index] = DepositRecipient {
+            address: recipient_account.key(),
+            weight_bps: *weight_bps,
+        };
+    }
+
+    // Trades now deposit into the proceeds account, which pays out the recipients
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.extension = StrategyExtension::DepositSplit {
+        deposit_split: ctx.accounts.deposit_split.key(),
+    };
+    strategy.deposit_address = ctx.accounts.proceeds_account.key();
+
+    emit!(InitDepositSplitV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        deposit_split: ctx.accounts.deposit_split.key(),
+        proceeds_account: ctx.accounts.proceeds_account.key(),
+        recipients: ctx.accounts.deposit_split.recipients[..weights_bps.len()].to_vec(),
+    });
+
+    Ok(())
+}
+
+impl<'info> InitDepositSplitV2<'info> {
+    pub fn valid_arguments(weights_bps: &[u16]) -> Result<()> {
+        if weights_bps.is_empty() || weights_bps.len() > MAX_DEPOSIT_RECIPIENTS {
+            return Err(error!(ErrorCode::InvalidDepositRecipients));
+        }
+        let total_weight: u64 = weights_bps.iter().map(|weight| u64::from(*weight)).sum();
+        if total_weight != BPS_DENOMINATOR {
+            return Err(error!(ErrorCode::DepositWeightsDoNotSumToWhole));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    constants::{GRID_BASE_SEED, GRID_QUOTE_SEED, GRID_STRATEGY_SEED},
+    errors::ErrorCode,
+    events::InitGridStrategyEvent,
+    state::{GridStrategy, MAX_GRID_LEVELS},
+};
+
+/// The funding and shape of a new grid, see `GridStrategy` for the meaning of each field
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct InitGridStrategyParams {
+    pub base_transfer_amount: u64,
+    pub quote_transfer_amount: u64,
+    pub lower_price: u64,
+    pub upper_price: u64,
+    pub price_denominator: u64,
+    pub grid_levels: u8,
+    pub level_size: u64,
+    pub start_date: i64,
+    pub reclaim_date: i64,
+    pub sold_ranges: u32,
+}
+
+#[derive(Accounts)]
+#[instruction(params: InitGridStrategyParams)]
+pub struct InitGridStrategy<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        seeds = [base_mint.key().as_ref(), quote_mint.key().as_ref(), &params.lower_price.to_le_bytes(), &params.upper_price.to_le_bytes(), &params.reclaim_date.to_le_bytes(), GRID_STRATEGY_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        space = GridStrategy::LEN,
+    )]
+    pub strategy: Box<Account<'info, GridStrategy>>,
+    #[account(
+        init,
+        seeds = [strategy.key().as_ref(), GRID_BASE_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        token::mint = base_mint,
+        token::authority = strategy
+    )]
+    pub base_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        seeds = [strategy.key().as_ref(), GRID_QUOTE_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        token::mint = quote_mint,
+        token::authority = strategy
+    )]
+    pub quote_account: Box<Account<'info, TokenAccount>>,
+    /// The account the base is funded from and reclaimed to
+    #[account(
+        mut,
+        constraint = base_reclaim_account.mint == base_mint.key()
+            @ ErrorCode::BadReclaimAddress
+    )]
+    pub base_reclaim_account: Box<Account<'info, TokenAccount>>,
+    /// The account the quote is funded from and reclaimed to
+    #[account(
+        mut,
+        constraint = quote_reclaim_account.mint == quote_mint.key()
+            @ ErrorCode::BadReclaimAddress,
+        constraint = quote_reclaim_account.owner == base_reclaim_account.owner
+            @ ErrorCode::BadReclaimAddress
+    )]
+    pub quote_reclaim_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitGridStrategy>, params: InitGridStrategyParams) -> Result<()> {
+    let InitGridStrategyParams {
+        base_transfer_amount,
+        quote_transfer_amount,
+        lower_price,
+        upper_price,
+        price_denominator,
+        grid_levels,
+        level_size,
+        start_date,
+        reclaim_date,
+        sold_ranges,
+    } = params;
+    let grid = &mut ctx.accounts.strategy;
+    grid.base_mint = ctx.accounts.base_mint.key();
+    grid.quote_mint = ctx.accounts.quote_mint.key();
+    grid.base_account = ctx.accounts.base_account.key();
+    grid.quote_account = ctx.accounts.quote_account.key();
+    grid.start_date = start_date;
+    grid.reclaim_date = reclaim_date;
+    grid.base_reclaim_address = ctx.accounts.base_reclaim_account.key();
+    grid.quote_reclaim_address = ctx.accounts.quote_reclaim_account.key();
+    grid.lower_price = lower_price;
+    grid.upper_price = upper_price;
+    grid.price_denominator = price_denominator;
+    grid.grid_levels = grid_levels;
+    grid.level_size = level_size;
+    grid.sold_ranges = sold_ranges;
+    grid.bump = *ctx.bumps.get("strategy").unwrap();
+
+    // Fund both sides of the grid
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.base_reclaim_account.to_account_info(),
+        to: ctx.accounts.base_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, base_transfer_amount)?;
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.quote_reclaim_account.to_account_info(),
+        to: ctx.accounts.quote_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, quote_transfer_amount)?;
+
+    emit!(InitGridStrategyEvent {
+        strategy: ctx.accounts.strategy.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        base_transfer_amount,
+        quote_transfer_amount,
+        lower_price,
+        upper_price,
+        price_denominator,
+        grid_levels,
+        level_size,
+        start_date,
+        reclaim_date,
+        sold_ranges,
+    });
+
+    Ok(())
+}
+
+impl<'info> InitGridStrategy<'info> {
+    pub fn valid_arguments(params: &InitGridStrategyParams) -> Result<()> {
+        let InitGridStrategyParams {
+            lower_price,
+            upper_price,
+            price_denominator,
+            grid_levels,
+            level_size,
+            start_date,
+            reclaim_date,
+            sold_ranges,
+            ..
+        } = *params;
+        if lower_price == 0 || lower_price >= upper_price {
+            return Err(error!(ErrorCode::InvalidGridPriceBand));
+        }
+        if price_denominator == 0 {
+            return Err(error!(ErrorCode::BoundPriceIsZero));
+        }
+        if !(2..=MAX_GRID_LEVELS).contains(&grid_levels) {
+            return Err(error!(ErrorCode::InvalidGridLevels));
+        }
+        if level_size == 0 {
+            return Err(error!(ErrorCode::GridLevelSizeIsZero));
+        }
+        if start_date >= reclaim_date {
+            return Err(error!(ErrorCode::StartDateAfterReclaimDate));
+        }
+        // Only the grid's ranges can start out sold
+        if u64::from(sold_ranges) >> (grid_levels - 1) != 0 {
+            return Err(error!(ErrorCode::InvalidGridLevel));
+        }
+        Ok(())
+    }
+}
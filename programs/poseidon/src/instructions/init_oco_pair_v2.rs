@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{
+    self, spl_token::instruction::AuthorityType, CloseAccount, SetAuthority, Token, TokenAccount,
+    Transfer,
+};
+
+use crate::{
+    constants::{BOUNDED_STRATEGY_SEED, OCO_PAIR_SEED},
+    errors::ErrorCode,
+    events::InitOcoPairV2Event,
+    state::{BoundedStrategyV2, OcoPair, StrategyExtension},
+    strategy_signer_seeds,
+};
+
+#[derive(Accounts)]
+pub struct InitOcoPairV2<'info> {
+    /// The owner of both strategies' reclaim account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The strategy that sells above the market. Its collateral account becomes the pair's.
+    #[account(
+        mut,
+        constraint = take_profit.key() != stop_loss.key() @ ErrorCode::OcoStrategiesMismatch,
+    )]
+    pub take_profit: Box<Account<'info, BoundedStrategyV2>>,
+    /// The strategy that sells below the market
+    #[account(
+        mut,
+        constraint = stop_loss.collateral_mint == take_profit.collateral_mint
+            @ ErrorCode::OcoStrategiesMismatch,
+        constraint = stop_loss.reclaim_address == take_profit.reclaim_address
+            @ ErrorCode::OcoStrategiesMismatch,
+        constraint = stop_loss.reclaim_date == take_profit.reclaim_date
+            @ ErrorCode::OcoStrategiesMismatch,
+    )]
+    pub stop_loss: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        init,
+        seeds = [take_profit.key().as_ref(), stop_loss.key().as_ref(), OCO_PAIR_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        space = OcoPair::LEN,
+    )]
+    pub oco_pair: Box<Account<'info, OcoPair>>,
+    #[account(
+        mut,
+        constraint = take_profit_collateral.key() == take_profit.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub take_profit_collateral: Box<Account<'info, TokenAccount>>,
+    /// Emptied into the take profit's collateral account and closed
+    #[account(
+        mut,
+        constraint = stop_loss_collateral.key() == stop_loss.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub stop_loss_collateral: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = reclaim_account.key() == take_profit.reclaim_address
+            @ ErrorCode::WrongReclaimAddress,
+        constraint = reclaim_account.owner == payer.key()
+            @ ErrorCode::AuthorityMisMatch,
+    )]
+    pub reclaim_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+///
+/// Pair a take profit and a stop loss strategy so that only one of them can sell the collateral.
+/// The stop loss's collateral is moved into the take profit's collateral account, which the pair
+/// owns until either strategy fills. Neither strategy may have traded yet. The stop loss may
+/// only fill at or below its trigger price.
+///
+pub fn handler(
+    ctx: Context<InitOcoPairV2>,
+    stop_loss_trigger_numerator: u64,
+    stop_loss_trigger_denominator: u64,
+) -> Result<()> {
+    for strategy in [&ctx.accounts.take_profit, &ctx.accounts.stop_loss] {
+        if strategy.has_extension() {
+            return Err(error!(ErrorCode::StrategyHasExtension));
+        }
+        // An upstream strategy keeps depositing into the collateral account the pair replaces
+        if strategy.fill_stats.trade_count > 0 || strategy.has_upstream() {
+            return Err(error!(ErrorCode::StrategyCannotJoinOcoPair));
+        }
+    }
+    // The trigger sits between the stop loss's floor and the take profit's bound, in output
+    //  per input terms
+    let trigger = (stop_loss_trigger_denominator, stop_loss_trigger_numerator);
+    let stop_loss_price = (
+        ctx.accounts.stop_loss.bounded_price_denominator,
+        ctx.accounts.stop_loss.bounded_price_numerator,
+    );
+    let take_profit_price = (
+        ctx.accounts.take_profit.bounded_price_denominator,
+        ctx.accounts.take_profit.bounded_price_numerator,
+    );
+    if price_lt(trigger, stop_loss_price) || !price_lt(trigger, take_profit_price) {
+        return Err(error!(ErrorCode::InvalidStopLossTrigger));
+    }
+
+    // Move the stop loss's collateral into the shared account and close its own
+    let stop_loss = &ctx.accounts.stop_loss;
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.token_program.to_account_info(),
+        accounts: Transfer {
+            from: ctx.accounts.stop_loss_collateral.to_account_info(),
+            to: ctx.accounts.take_profit_collateral.to_account_info(),
+            authority: stop_loss.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(stop_loss)],
+    };
+    token::transfer(cpi_ctx, ctx.accounts.stop_loss_collateral.amount)?;
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.token_program.to_account_info(),
+        accounts: CloseAccount {
+            account: ctx.accounts.stop_loss_collateral.to_account_info(),
+            destination: ctx.accounts.payer.to_account_info(),
+            authority: stop_loss.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(stop_loss)],
+    };
+    token::close_account(cpi_ctx)?;
+
+    // Hand the shared collateral to the pair until either strategy fills
+    let take_profit = &ctx.accounts.take_profit;
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.token_program.to_account_info(),
+        accounts: SetAuthority {
+            current_authority: take_profit.to_account_info(),
+            account_or_mint: ctx.accounts.take_profit_collateral.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(take_profit)],
+    };
+    token::set_authority(
+        cpi_ctx,
+        AuthorityType::AccountOwner,
+        Some(ctx.accounts.oco_pair.key()),
+    )?;
+
+    let oco_pair = &mut ctx.accounts.oco_pair;
+    oco_pair.take_profit = ctx.accounts.take_profit.key();
+    oco_pair.stop_loss = ctx.accounts.stop_loss.key();
+    oco_pair.collateral_account = ctx.accounts.take_profit_collateral.key();
+    oco_pair.bump = *ctx.bumps.get("oco_pair").unwrap();
+    oco_pair.stop_loss_trigger_numerator = stop_loss_trigger_numerator;
+    oco_pair.stop_loss_trigger_denominator = stop_loss_trigger_denominator;
+
+    let extension = StrategyExtension::OcoPair {
+        oco_pair: ctx.accounts.oco_pair.key(),
+    };
+    ctx.accounts.take_profit.extension = extension;
+    ctx.accounts.stop_loss.extension = extension;
+    ctx.accounts.stop_loss.collateral_account = ctx.accounts.take_profit_collateral.key();
+    ctx.accounts.take_profit_collateral.reload()?;
+
+    emit!(InitOcoPairV2Event {
+        oco_pair: ctx.accounts.oco_pair.key(),
+        take_profit: ctx.accounts.take_profit.key(),
+        stop_loss: ctx.accounts.stop_loss.key(),
+        collateral_account: ctx.accounts.take_profit_collateral.key(),
+        amount: ctx.accounts.take_profit_collateral.amount,
+        stop_loss_trigger_numerator,
+        stop_loss_trigger_denominator,
+    });
+
+    Ok(())
+}
+
+/// Returns true when the price `a` is below the price `b`, each given as a numerator and
+/// denominator
+fn price_lt(a: (u64, u64), b: (u64, u64)) -> bool {
+    (a.0 as u128) * (b.1 as u128) < (b.0 as u128) * (a.1 as u128)
+}
+
+impl<'info> InitOcoPairV2<'info> {
+    pub fn valid_arguments(
+        stop_loss_trigger_numerator: u64,
+        stop_loss_trigger_denominator: u64,
+    ) -> Result<()> {
+        if stop_loss_trigger_numerator == 0 || stop_loss_trigger_denominator == 0 {
+            return Err(error!(ErrorCode::InvalidStopLossTrigger));
+        }
+        Ok(())
+    }
+}
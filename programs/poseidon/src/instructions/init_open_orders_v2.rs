@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{dex::InitOpenOrders, token::TokenAccount};
+
+use crate::{
+    constants::{BOUNDED_STRATEGY_SEED, OPEN_ORDERS_SEED},
+    dexes::open_book_dex,
+    errors::ErrorCode,
+    open_orders_signer_seeds,
+    serum_utils::{self, MarketInfo},
+    state::{BoundedStrategyV2, StrategyExtension},
+    strategy_signer_seeds,
+};
+
+#[derive(Accounts)]
+pub struct InitOpenOrdersV2<'info> {
+    /// The owner of the strategy's reclaim account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The BoundedStrategy account
+    #[account(
+        mut,
+        constraint = !strategy.has_open_orders() @ ErrorCode::OpenOrdersAlreadyInitialized,
+        constraint = !strategy.has_extension() @ ErrorCode::StrategyHasExtension,
+    )]
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    /// CHECK: Constraints are handled
+    #[account(
+        owner = open_book_dex::ID
+    )]
+    pub serum_market: UncheckedAccount<'info>,
+    #[account(
+        constraint = deposit_account.key() == strategy.deposit_address
+            @ ErrorCode::DepositAddressMisMatch
+    )]
+    pub deposit_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        constraint = reclaim_account.key() == strategy.reclaim_address
+            @ ErrorCode::WrongReclaimAddress,
+        constraint = reclaim_account.owner == payer.key()
+            @ ErrorCode::AuthorityMisMatch,
+    )]
+    pub reclaim_account: Box<Account<'info, TokenAccount>>,
+    /// The OpenOrders account to initialize
+    /// CHECK: constraints handled
+    #[account(
+        mut,
+        seeds = [strategy.key().as_ref(), OPEN_ORDERS_SEED.as_bytes()],
+        bump
+    )]
+    pub open_orders: UncheckedAccount<'info>,
+    /// The Serum program
+    pub dex_program: Program<'info, open_book_dex::OpenBookDexV3>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(ctx: Context<InitOpenOrdersV2>, open_orders_space: u64) -> Result<()> {
+    // The market must trade the strategy's collateral against its deposit mint
+    let market = MarketInfo::load(&ctx.accounts.serum_market, ctx.accounts.dex_program.key)?;
+    let strategy_mints = (
+        ctx.accounts.strategy.collateral_mint,
+        ctx.accounts.deposit_account.mint,
+    );
+    if strategy_mints != (market.coin_mint, market.pc_mint)
+        && strategy_mints != (market.pc_mint, market.coin_mint)
+    {
+        return Err(error!(ErrorCode::MarketMintsDoNotMatchStrategy));
+    }
+
+    // Create the account in the instruction to avoid client bugs when
+    //  creating but not initializing.
+    let open_orders_bump = *ctx.bumps.get("open_orders").unwrap();
+    let cpi_accounts = anchor_lang::system_program::CreateAccount {
+        from: ctx.accounts.payer.to_account_info(),
+        to: ctx.accounts.open_orders.to_account_info(),
+    };
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.system_program.to_account_info(),
+        accounts: cpi_accounts,
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[open_orders_signer_seeds!(
+            ctx.accounts.strategy,
+            open_orders_bump
+        )],
+    };
+    anchor_lang::system_program::create_account(
+        cpi_ctx,
+        Rent::get()?.minimum_balance(open_orders_space as usize),
+        open_orders_space,
+        ctx.accounts.dex_program.key,
+    )?;
+
+    // The strategy owns the OpenOrders account so it can sign for its orders
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.dex_program.to_account_info(),
+        accounts: InitOpenOrders {
+            open_orders: ctx.accounts.open_orders.to_account_info(),
+            authority: ctx.accounts.strategy.to_account_info(),
+            market: ctx.accounts.serum_market.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    serum_utils::init_open_orders(cpi_ctx)?;
+
+    ctx.accounts.strategy.extension = StrategyExtension::RestingOrders {
+        open_orders: ctx.accounts.open_orders.key(),
+    };
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    constants::{
+        BPS_DENOMINATOR, REBALANCE_BASE_SEED, REBALANCE_QUOTE_SEED, REBALANCE_STRATEGY_SEED,
+    },
+    errors::ErrorCode,
+    events::InitRebalanceStrategyEvent,
+    state::RebalanceStrategy,
+};
+
+#[derive(Accounts)]
+#[instruction(base_transfer_amount: u64, quote_transfer_amount: u64, reference_price_numerator: u64, reference_price_denominator: u64, target_base_weight_bps: u16, tolerance_bps: u16, max_deviation_bps: u16, start_date: i64, reclaim_date: i64)]
+pub struct InitRebalanceStrategy<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        seeds = [base_mint.key().as_ref(), quote_mint.key().as_ref(), &reclaim_date.to_le_bytes(), REBALANCE_STRATEGY_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        space = RebalanceStrategy::LEN,
+    )]
+    pub strategy: Box<Account<'info, RebalanceStrategy>>,
+    #[account(
+        init,
+        seeds = [strategy.key().as_ref(), REBALANCE_BASE_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        token::mint = base_mint,
+        token::authority = strategy
+    )]
+    pub base_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        seeds = [strategy.key().as_ref(), REBALANCE_QUOTE_SEED.as_bytes()],
+        payer = payer,
+        bump,
+        token::mint = quote_mint,
+        token::authority = strategy
+    )]
+    pub quote_account: Box<Account<'info, TokenAccount>>,
+    /// The account the base is funded from and reclaimed to
+    #[account(
+        mut,
+        constraint = base_reclaim_account.mint == base_mint.key()
+            @ ErrorCode::BadReclaimAddress
+    )]
+    pub base_reclaim_account: Box<Account<'info, TokenAccount>>,
+    /// The account the quote is funded from and reclaimed to
+    #[account(
+        mut,
+        constraint = quote_reclaim_account.mint == quote_mint.key()
+            @ ErrorCode::BadReclaimAddress,
+        constraint = quote_reclaim_account.owner == base_reclaim_account.owner
+            @ ErrorCode::BadReclaimAddress
+    )]
+    pub quote_reclaim_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<InitRebalanceStrategy>,
+    base_transfer_amount: u64,
+    quote_transfer_amount: u64,
+    reference_price_numerator: u64,
+    reference_price_denominator: u64,
+    target_base_weight_bps: u16,
+    tolerance_bps: u16,
+    max_deviation_bps: u16,
+    start_date: i64,
+    reclaim_date: i64,
+) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.base_mint = ctx.accounts.base_mint.key();
+    strategy.quote_mint = ctx.accounts.quote_mint.key();
+    strategy.base_account = ctx.accounts.base_account.key();
+    strategy.quote_account = ctx.accounts.quote_account.key();
+    strategy.start_date = start_date;
+    strategy.reclaim_date = reclaim_date;
+    strategy.base_reclaim_address = ctx.accounts.base_reclaim_account.key();
+    strategy.quote_reclaim_address = ctx.accounts.quote_reclaim_account.key();
+    strategy.reference_price_numerator = reference_price_numerator;
+    strategy.reference_price_denominator = reference_price_denominator;
+    strategy.target_base_weight_bps = target_base_weight_bps;
+    strategy.tolerance_bps = tolerance_bps;
+    strategy.max_deviation_bps = max_deviation_bps;
+    strategy.bump = *ctx.bumps.get("strategy").unwrap();
+
+    // Fund both holdings
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.base_reclaim_account.to_account_info(),
+        to: ctx.accounts.base_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, base_transfer_amount)?;
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.quote_reclaim_account.to_account_info(),
+        to: ctx.accounts.quote_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, quote_transfer_amount)?;
+
+    emit!(InitRebalanceStrategyEvent {
+        strategy: ctx.accounts.strategy.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        base_transfer_amount,
+        quote_transfer_amount,
+        reference_price_numerator,
+        reference_price_denominator,
+        target_base_weight_bps,
+        tolerance_bps,
+        max_deviation_bps,
+        start_date,
+        reclaim_date,
+    });
+
+    Ok(())
+}
+
+impl<'info> InitRebalanceStrategy<'info> {
+    pub fn valid_arguments(
+        reference_price_numerator: u64,
+        reference_price_denominator: u64,
+        target_base_weight_bps: u16,
+        tolerance_bps: u16,
+        max_deviation_bps: u16,
+        start_date: i64,
+        reclaim_date: i64,
+    ) -> Result<()> {
+        if reference_price_numerator == 0 || reference_price_denominator == 0 {
+            return Err(error!(ErrorCode::BoundPriceIsZero));
+        }
+        if u64::from(target_base_weight_bps) > BPS_DENOMINATOR {
+            return Err(error!(ErrorCode::InvalidTargetWeight));
+        }
+        if u64::from(tolerance_bps) >= BPS_DENOMINATOR {
+            return Err(error!(ErrorCode::InvalidRebalanceTolerance));
+        }
+        if u64::from(max_deviation_bps) >= BPS_DENOMINATOR {
+            return Err(error!(ErrorCode::InvalidMaxDeviation));
+        }
+        if start_date >= reclaim_date {
+            return Err(error!(ErrorCode::StartDateAfterReclaimDate));
+        }
+        Ok(())
+    }
+}
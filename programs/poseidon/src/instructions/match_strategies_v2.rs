@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    constants::BOUNDED_STRATEGY_SEED,
+    dexes::{is_in_bounds, math::U256},
+    errors::ErrorCode,
+    events::MatchStrategiesV2Event,
+    state::BoundedStrategyV2,
+    strategy_signer_seeds,
+};
+
+#[derive(Accounts)]
+pub struct MatchStrategiesV2<'info> {
+    /// Anyone can fire this transaction
+    pub payer: Signer<'info>,
+    /// The first BoundedStrategy account
+    #[account(
+        mut,
+        constraint = !strategy_a.requires_route() @ ErrorCode::StrategyRequiresRoute,
+    )]
+    pub strategy_a: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        mut,
+        constraint = order_payer_a.key() == strategy_a.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub order_payer_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = deposit_account_a.key() == strategy_a.deposit_address
+            @ ErrorCode::DepositAddressMisMatch
+    )]
+    pub deposit_account_a: Box<Account<'info, TokenAccount>>,
+    /// The second BoundedStrategy account, which must sell what the first one buys
+    #[account(
+        mut,
+        constraint = !strategy_b.requires_route() @ ErrorCode::StrategyRequiresRoute,
+    )]
+    pub strategy_b: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        mut,
+        constraint = order_payer_b.key() == strategy_b.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+        constraint = order_payer_b.mint == deposit_account_a.mint
+            @ ErrorCode::StrategyMintsNotMirrored,
+    )]
+    pub order_payer_b: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = deposit_account_b.key() == strategy_b.deposit_address
+            @ ErrorCode::DepositAddressMisMatch,
+        constraint = deposit_account_b.mint == order_payer_a.mint
+            @ ErrorCode::StrategyMintsNotMirrored,
+    )]
+    pub deposit_account_b: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<MatchStrategiesV2>) -> Result<()> {
+    let clock = Clock::get()?;
+    let strategy_a = &ctx.accounts.strategy_a;
+    let strategy_b = &ctx.accounts.strategy_b;
+    strategy_a.validate_can_trade(&clock)?;
+    strategy_b.validate_can_trade(&clock)?;
+
+    // Each side trades its active tier's size at the active tier's price
+    let tier_a = strategy_a.trading_tier()?;
+    let tier_b = strategy_b.trading_tier()?;
+    let (bounded_price_numerator_a, bounded_price_denominator_a) = strategy_a.bounded_price(tier_a);
+    let (bounded_price_numerator_b, bounded_price_denominator_b) = strategy_b.bounded_price(tier_b);
+    let available_input_a = strategy_a.available_input(ctx.accounts.order_payer_a.amount, tier_a);
+    let available_input_b = strategy_b.available_input(ctx.accounts.order_payer_b.amount, tier_b);
+
+    let (input_a, input_b) = cross_bounds(
+        strategy_a.clamp_fill_input(available_input_a),
+        bounded_price_numerator_a,
+        bounded_price_denominator_a,
+        strategy_b.clamp_fill_input(available_input_b),
+        bounded_price_numerator_b,
+        bounded_price_denominator_b,
+    )
+    .ok_or(ErrorCode::StrategyBoundsDoNotOverlap)?;
+    if input_a == 0 || input_b == 0 {
+        return Err(error!(ErrorCode::MatchAmountIsZero));
+    }
+    // Rounding must not push either side out of its bound
+    if !is_in_bounds(
+        input_a,
+        input_b,
+        &bounded_price_numerator_a,
+        &bounded_price_denominator_a,
+    ) || !is_in_bounds(
+        input_b,
+        input_a,
+        &bounded_price_numerator_b,
+        &bounded_price_denominator_b,
+    ) {
+        return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
+    }
+    strategy_a.validate_fill_input(input_a, available_input_a)?;
+    strategy_b.validate_fill_input(input_b, available_input_b)?;
+
+    // Each strategy pays its collateral straight to the other's deposit address
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.token_program.to_account_info(),
+        accounts: Transfer {
+            from: ctx.accounts.order_payer_a.to_account_info(),
+            to: ctx.accounts.deposit_account_b.to_account_info(),
+            authority: ctx.accounts.strategy_a.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy_a)],
+    };
+    token::transfer(cpi_ctx, input_a)?;
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.token_program.to_account_info(),
+        accounts: Transfer {
+            from: ctx.accounts.order_payer_b.to_account_info(),
+            to: ctx.accounts.deposit_account_a.to_account_info(),
+            authority: ctx.accounts.strategy_b.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy_b)],
+    };
+    token::transfer(cpi_ctx, input_b)?;
+
+    ctx.accounts
+        .strategy_a
+        .record_fill(tier_a, input_a, input_b, &clock);
+    ctx.accounts
+        .strategy_b
+        .record_fill(tier_b, input_b, input_a, &clock);
+
+    emit!(MatchStrategiesV2Event {
+        strategy_a: ctx.accounts.strategy_a.key(),
+        strategy_b: ctx.accounts.strategy_b.key(),
+        executor: ctx.accounts.payer.key(),
+        price_tier_a: tier_a.map(|index| index as u8),
+        price_tier_b: tier_b.map(|index| index as u8),
+        amount_a: input_a,
+        amount_b: input_b,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+///
+/// Cross two opposing bounds at the midpoint of their prices, splitting the price improvement
+/// evenly. Returns the amount of the first strategy's collateral sold and the amount of the
+/// second strategy's collateral paid for it, or None when the bounds do not overlap.
+///
+pub fn cross_bounds(
+    max_input_a: u64,
+    bounded_price_numerator_a: u64,
+    bounded_price_denominator_a: u64,
+    max_input_b: u64,
+    bounded_price_numerator_b: u64,
+    bounded_price_denominator_b: u64,
+) -> Option<(u64, u64)> {
+    let numerator_a = U256::from(bounded_price_numerator_a);
+    let denominator_a = U256::from(bounded_price_denominator_a);
+    let numerator_b = U256::from(bounded_price_numerator_b);
+    let denominator_b = U256::from(bounded_price_denominator_b);
+    // The first strategy wants at least `denominator_a / numerator_a` of its output per input
+    //  and the second pays at most `numerator_b / denominator_b`
+    let lowest_price = denominator_a * denominator_b;
+    let highest_price = numerator_a * numerator_b;
+    if lowest_price > highest_price || numerator_a.is_zero() || denominator_b.is_zero() {
+        return None;
+    }
+    // The midpoint of the two, in the second strategy's collateral per the first's
+    let price_numerator = lowest_price + highest_price;
+    let price_denominator = U256::from(2) * numerator_a * denominator_b;
+
+    // Fill as much of the first strategy as the second can pay for
+    let max_input_a_for_b = U256::from(max_input_b) * price_denominator / price_numerator;
+    let input_a = std::cmp::min(U256::from(max_input_a), max_input_a_for_b);
+    let input_b = input_a * price_numerator / price_denominator;
+    Some((input_a.as_u64(), input_b.as_u64()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::cross_bounds;
+    use crate::dexes::is_in_bounds;
+
+    #[test]
+    /// Test that a SOL seller at >= 90 USDC and a SOL buyer at <= 95 USDC cross at 92.5
+    fn test_cross_bounds_at_midpoint() {
+        // 10 SOL for at least 90 USDC each, and 2,000 USDC for at most 95 USDC per SOL
+        let (sol, usdc) =
+            cross_bounds(10_000_000_000, 1_000, 90, 2_000_000_000, 95, 1_000).unwrap();
+        assert_eq!(sol, 10_000_000_000);
+        assert_eq!(usdc, 925_000_000);
+        assert!(is_in_bounds(sol, usdc, &1_000, &90));
+        assert!(is_in_bounds(usdc, sol, &95, &1_000));
+
+        // Only 185 USDC to spend fills 2 SOL
+        let (sol, usdc) = cross_bounds(10_000_000_000, 1_000, 90, 185_000_000, 95, 1_000).unwrap();
+        assert_eq!(sol, 2_000_000_000);
+        assert_eq!(usdc, 185_000_000);
+    }
+
+    #[test]
+    fn test_cross_bounds_without_overlap() {
+        // Selling SOL for at least 96 USDC and buying it for at most 95 USDC
+        assert_eq!(
+            cross_bounds(10_000_000_000, 1_000, 96, 2_000_000_000, 95, 1_000),
+            None
+        );
+        // Touching bounds cross at the shared price
+        assert_eq!(
+            cross_bounds(1_000_000_000, 1_000, 95, 2_000_000_000, 95, 1_000),
+            Some((1_000_000_000, 95_000_000))
+        );
+    }
+}
@@ -1,15 +1,55 @@
+pub mod additional_data;
 pub mod bounded_trade;
 pub mod bounded_trade_v2;
+pub mod cancel_resting_order_v2;
+pub mod chain_strategies_v2;
+pub mod deposit_v2;
+pub mod fill_strategy_v2;
+pub mod grid_trade;
+pub mod init_basket_v2;
 pub mod init_bounded_strategy;
 pub mod init_bounded_strategy_v2;
+pub mod init_deposit_split_v2;
+pub mod init_grid_strategy;
+pub mod init_oco_pair_v2;
+pub mod init_open_orders_v2;
+pub mod init_rebalance_strategy;
+pub mod match_strategies_v2;
+pub mod place_resting_order_v2;
+pub mod pre_trade;
+pub mod quote_route;
+pub mod rebalance_trade;
 pub mod reclaim;
+pub mod reclaim_grid_strategy;
+pub mod reclaim_rebalance_strategy;
 pub mod reclaim_v2;
 pub mod settle_funds;
+pub mod settle_resting_order_v2;
 
+pub use additional_data::*;
 pub use bounded_trade::*;
 pub use bounded_trade_v2::*;
+pub use cancel_resting_order_v2::*;
+pub use chain_strategies_v2::*;
+pub use deposit_v2::*;
+pub use fill_strategy_v2::*;
+pub use grid_trade::*;
+pub use init_basket_v2::*;
 pub use init_bounded_strategy::*;
 pub use init_bounded_strategy_v2::*;
+pub use init_deposit_split_v2::*;
+pub use init_grid_strategy::*;
+pub use init_oco_pair_v2::*;
+pub use init_open_orders_v2::*;
+pub use init_rebalance_strategy::*;
+pub use match_strategies_v2::*;
+pub use place_resting_order_v2::*;
+pub use pre_trade::*;
+pub use quote_route::*;
+pub use rebalance_trade::*;
 pub use reclaim::*;
+pub use reclaim_grid_strategy::*;
+pub use reclaim_rebalance_strategy::*;
 pub use reclaim_v2::*;
 pub use settle_funds::*;
+pub use settle_resting_order_v2::*;
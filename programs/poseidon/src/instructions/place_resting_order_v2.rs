@@ -0,0 +1,274 @@
+use std::{convert::TryFrom, num::NonZeroU64};
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    dex::{serum_dex::matching::Side, NewOrderV3},
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    constants::BOUNDED_STRATEGY_SEED,
+    dexes::open_book_dex,
+    errors::ErrorCode,
+    events::PlaceRestingOrderV2Event,
+    serum_utils::{self, MarketInfo, OpenOrdersBalances},
+    state::BoundedStrategyV2,
+    strategy_signer_seeds,
+};
+
+#[derive(Accounts)]
+pub struct PlaceRestingOrderV2<'info> {
+    /// Anyone can fire this transaction
+    pub payer: Signer<'info>,
+    /// The BoundedStrategy account
+    #[account(mut)]
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        mut,
+        constraint = order_payer.key() == strategy.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub order_payer: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Constraints are handled
+    #[account(
+        mut,
+        constraint = strategy.has_open_orders() @ ErrorCode::OpenOrdersNotInitialized,
+        constraint = strategy.open_orders_key() == Some(open_orders.key())
+            @ ErrorCode::WrongOpenOrdersKey,
+    )]
+    pub open_orders: UncheckedAccount<'info>,
+    /// CHECK: Constraints are handled
+    #[account(
+        mut,
+        owner = open_book_dex::ID
+    )]
+    pub serum_market: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub request_queue: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub event_queue: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub bids: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub asks: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub pc_vault: UncheckedAccount<'info>,
+    /// The Serum program
+    pub dex_program: Program<'info, open_book_dex::OpenBookDexV3>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(ctx: Context<PlaceRestingOrderV2>) -> Result<()> {
+    let bounded_strategy = &ctx.accounts.strategy;
+    let clock = Clock::get()?;
+    bounded_strategy.validate_can_trade(&clock)?;
+    // A resting order sits at a single price, so ladders keep trading through routes
+    if bounded_strategy.is_laddered() {
+        return Err(error!(ErrorCode::LadderedStrategyCannotRestOrders));
+    }
+    // Only one order rests at a time, and its fills must be settled before the next
+    let balances = OpenOrdersBalances::load(&ctx.accounts.open_orders.try_borrow_data()?)?;
+    if balances.has_orders() || bounded_strategy.resting_input > 0 {
+        return Err(error!(ErrorCode::RestingOrderAlreadyPlaced));
+    }
+
+    let available_input = ctx.accounts.order_payer.amount;
+    let input_amount = bounded_strategy.clamp_fill_input(available_input);
+    bounded_strategy.validate_fill_input(input_amount, available_input)?;
+
+    let market = MarketInfo::load(&ctx.accounts.serum_market, ctx.accounts.dex_program.key)?;
+    let order = resting_order(
+        market.coin_mint == bounded_strategy.collateral_mint,
+        input_amount,
+        bounded_strategy.bounded_price_numerator,
+        bounded_strategy.bounded_price_denominator,
+        market.coin_lot_size,
+        market.pc_lot_size,
+    )
+    .ok_or(ErrorCode::RestingOrderBelowLotSize)?;
+
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.dex_program.to_account_info(),
+        accounts: NewOrderV3 {
+            market: ctx.accounts.serum_market.to_account_info(),
+            open_orders: ctx.accounts.open_orders.to_account_info(),
+            request_queue: ctx.accounts.request_queue.to_account_info(),
+            event_queue: ctx.accounts.event_queue.to_account_info(),
+            market_bids: ctx.accounts.bids.to_account_info(),
+            market_asks: ctx.accounts.asks.to_account_info(),
+            order_payer_token_account: ctx.accounts.order_payer.to_account_info(),
+            open_orders_authority: ctx.accounts.strategy.to_account_info(),
+            coin_vault: ctx.accounts.coin_vault.to_account_info(),
+            pc_vault: ctx.accounts.pc_vault.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    serum_utils::place_post_only_order(
+        cpi_ctx,
+        order.side,
+        order.limit_price,
+        order.max_coin_qty,
+        order.max_native_pc_qty,
+    )?;
+
+    // Track the collateral the order locked up so the settle crank can back out its fills
+    ctx.accounts.order_payer.reload()?;
+    let input_committed = available_input.saturating_sub(ctx.accounts.order_payer.amount);
+    ctx.accounts.strategy.resting_input = input_committed;
+
+    emit!(PlaceRestingOrderV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        is_bid: order.side == Side::Bid,
+        limit_price: order.limit_price.get(),
+        input_committed,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// The terms of a post-only order in the market's lots
+#[derive(Debug, PartialEq, Eq)]
+pub struct RestingOrder {
+    pub side: Side,
+    pub limit_price: NonZeroU64,
+    pub max_coin_qty: NonZeroU64,
+    pub max_native_pc_qty: NonZeroU64,
+}
+
+///
+/// Convert a strategy's bounded price into a post-only order that sells `input_amount` of its
+/// collateral. Asks round the price up and bids round it down so a fill is never outside the
+/// bound. Returns None when the order is smaller than a lot or its price rounds to 0.
+///
+pub fn resting_order(
+    collateral_is_coin: bool,
+    input_amount: u64,
+    bounded_price_numerator: u64,
+    bounded_price_denominator: u64,
+    coin_lot_size: u64,
+    pc_lot_size: u64,
+) -> Option<RestingOrder> {
+    let numerator = u128::from(bounded_price_numerator);
+    let denominator = u128::from(bounded_price_denominator);
+    let coin_lot_size_u128 = u128::from(coin_lot_size);
+    let pc_lot_size_u128 = u128::from(pc_lot_size);
+    if collateral_is_coin {
+        // Selling the coin for at least `denominator / numerator` pc per coin
+        let lot_value = denominator.checked_mul(coin_lot_size_u128)?;
+        let lot_divisor = numerator.checked_mul(pc_lot_size_u128)?;
+        if lot_divisor == 0 {
+            return None;
+        }
+        let limit_price = lot_value.div_ceil(lot_divisor);
+        Some(RestingOrder {
+            side: Side::Ask,
+            limit_price: NonZeroU64::new(u64::try_from(limit_price).ok()?)?,
+            max_coin_qty: NonZeroU64::new(input_amount.checked_div(coin_lot_size)?)?,
+            max_native_pc_qty: NonZeroU64::new(u64::MAX)?,
+        })
+    } else {
+        // Buying the coin for at most `numerator / denominator` pc per coin
+        let lot_value = numerator.checked_mul(coin_lot_size_u128)?;
+        let lot_divisor = denominator.checked_mul(pc_lot_size_u128)?;
+        if lot_divisor == 0 {
+            return None;
+        }
+        let limit_price = lot_value / lot_divisor;
+        Some(RestingOrder {
+            side: Side::Bid,
+            limit_price: NonZeroU64::new(u64::try_from(limit_price).ok()?)?,
+            max_coin_qty: NonZeroU64::new(u64::MAX)?,
+            max_native_pc_qty: NonZeroU64::new(input_amount)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_spl::dex::serum_dex::matching::Side;
+
+    use super::resting_order;
+
+    #[test]
+    /// Test that a SOL seller at >= 92.75 USDC asks 92.75 on a SOL/USDC market
+    fn test_resting_ask() {
+        // 0.1 SOL coin lots and 0.0001 USDC pc lots
+        let order = resting_order(
+            true,
+            1_050_000_000,
+            1_000_000_000,
+            92_750_000,
+            100_000_000,
+            100,
+        )
+        .unwrap();
+        assert_eq!(order.side, Side::Ask);
+        assert_eq!(order.limit_price.get(), 92_750);
+        assert_eq!(order.max_coin_qty.get(), 10);
+
+        // Prices between ticks round up for asks
+        let order = resting_order(
+            true,
+            1_000_000_000,
+            1_000_000_000,
+            92_750_001,
+            100_000_000,
+            100,
+        )
+        .unwrap();
+        assert_eq!(order.limit_price.get(), 92_751);
+
+        // Less than a lot cannot rest
+        assert!(resting_order(
+            true,
+            99_999_999,
+            1_000_000_000,
+            92_750_000,
+            100_000_000,
+            100
+        )
+        .is_none());
+    }
+
+    #[test]
+    /// Test that a SOL buyer at <= 92.75 USDC bids 92.75 on a SOL/USDC market
+    fn test_resting_bid() {
+        let order = resting_order(
+            false,
+            500_000_000,
+            92_750_000,
+            1_000_000_000,
+            100_000_000,
+            100,
+        )
+        .unwrap();
+        assert_eq!(order.side, Side::Bid);
+        assert_eq!(order.limit_price.get(), 92_750);
+        assert_eq!(order.max_native_pc_qty.get(), 500_000_000);
+
+        // Prices between ticks round down for bids
+        let order = resting_order(
+            false,
+            500_000_000,
+            92_750_099,
+            1_000_000_000,
+            100_000_000,
+            100,
+        )
+        .unwrap();
+        assert_eq!(order.limit_price.get(), 92_750);
+    }
+}
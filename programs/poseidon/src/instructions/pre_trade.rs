@@ -0,0 +1,344 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    dexes::split_route::SplitRoute,
+    errors::ErrorCode,
+    state::{Basket, BoundedStrategyV2, DepositSplit, OcoPair},
+};
+
+/*
+ * A strategy has at most one extension, so at most one of the companion accounts below, other
+ * than the executor payment accounts, leads the remaining accounts of bounded_trade_v2 and
+ * quote_route, ahead of the route:
+ * OCO PAIR - oco_pair
+ * DOWNSTREAM - downstream strategy
+ * BASKET - basket
+ * DEPOSIT SPLIT - deposit_split, followed by each recipient's token account
+ */
+
+/**
+ * EXECUTOR PAYMENT REMAINING ACCOUNTS, required when the strategy pays an executor fee or
+ * surplus share. They follow any other companion accounts, ahead of the route.
+ * 0 - proceeds_account: a token account owned by the strategy that the route ends in. May be
+ *     the deposit account when the strategy owns it.
+ * 1 - executor_token_account: receives the executor fee and surplus share
+ *
+ * Strategies without an executor fee or surplus share take the same accounts as before the
+ * fields were added, so existing clients keep working.
+ */
+pub const EXECUTOR_PAYMENT_ACCOUNTS_LEN: usize = 2;
+
+/// The price a trade is bounded by and the collateral it may sell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TradeBound {
+    /// The price tier the trade fills, or None when the strategy is not laddered
+    pub tier: Option<usize>,
+    pub bounded_price_numerator: u64,
+    pub bounded_price_denominator: u64,
+    /// The collateral available to the trade
+    pub available_input: u64,
+}
+
+impl TradeBound {
+    ///
+    /// Resolve the bound of a trade of the strategy. Laddered strategies trade the active tier's
+    /// size at its price, and basket strategies trade the output's allocation at its price.
+    ///
+    pub fn resolve(
+        strategy: &BoundedStrategyV2,
+        basket: Option<(&Basket, usize)>,
+        collateral_balance: u64,
+    ) -> Result<Self> {
+        let tier = strategy.trading_tier()?;
+        let ((bounded_price_numerator, bounded_price_denominator), available_input) = match basket {
+            Some((basket, index)) => {
+                let output = &basket.outputs[index];
+                (
+                    (
+                        output.bounded_price_numerator,
+                        output.bounded_price_denominator,
+                    ),
+                    basket.available_input(index, collateral_balance),
+                )
+            }
+            None => (
+                strategy.bounded_price(tier),
+                strategy.available_input(collateral_balance, tier),
+            ),
+        };
+        Ok(TradeBound {
+            tier,
+            bounded_price_numerator,
+            bounded_price_denominator,
+            available_input,
+        })
+    }
+}
+
+/// The companion accounts of a strategy's trade and the bound it trades at
+pub struct PreTrade<'a, 'info> {
+    pub oco_pair: Option<Account<'info, OcoPair>>,
+    pub downstream: Option<Account<'info, BoundedStrategyV2>>,
+    /// The basket and the index of the output the trade deposits into
+    pub basket: Option<(Account<'info, Basket>, usize)>,
+    pub deposit_split: Option<(Account<'info, DepositSplit>, &'a [AccountInfo<'info>])>,
+    /// The proceeds account and the executor's token account
+    pub executor_payment: Option<(Account<'info, TokenAccount>, Account<'info, TokenAccount>)>,
+    /// The remaining accounts that follow the companion accounts
+    pub route_accounts: &'a [AccountInfo<'info>],
+    pub bound: TradeBound,
+}
+
+impl<'a, 'info> PreTrade<'a, 'info> {
+    ///
+    /// Validate that the strategy can trade, load its companion accounts from the head of the
+    /// remaining accounts and resolve the bound it trades at. The deposit account must be the
+    /// strategy's deposit address, or one of its basket's outputs.
+    ///
+    pub fn load(
+        strategy: &Account<'info, BoundedStrategyV2>,
+        order_payer: &Account<'info, TokenAccount>,
+        deposit_account: &Account<'info, TokenAccount>,
+        remaining_accounts: &'a [AccountInfo<'info>],
+        clock: &Clock,
+    ) -> Result<Self> {
+        // Validate that the strategy is within its trading window, has been funded by any
+        //  upstream strategy and is not being traded again too soon
+        strategy.validate_can_trade(clock)?;
+
+        // OCO strategies share their collateral with a sibling, and neither can trade once the
+        //  other has filled
+        let (route_accounts, oco_pair) = if strategy.oco_pair_key().is_some() {
+            let oco_pair = load_oco_pair(remaining_accounts, strategy, &order_payer.key())?;
+            (&remaining_accounts[1..], Some(oco_pair))
+        } else {
+            (remaining_accounts, None)
+        };
+
+        // Upstream strategies deposit into the downstream strategy's collateral account
+        let (route_accounts, downstream) = if let Some(downstream_key) = strategy.downstream_key() {
+            let downstream_info = route_accounts
+                .first()
+                .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+            if downstream_info.key() != downstream_key {
+                return Err(error!(ErrorCode::WrongDownstreamStrategy));
+            }
+            let downstream = Account::<BoundedStrategyV2>::try_from(downstream_info)?;
+            (&route_accounts[1..], Some(downstream))
+        } else {
+            (route_accounts, None)
+        };
+
+        // Basket strategies trade into one of their outputs
+        let (route_accounts, basket) = if let Some(basket_key) = strategy.basket_key() {
+            let basket_info = route_accounts
+                .first()
+                .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+            if basket_info.key() != basket_key {
+                return Err(error!(ErrorCode::WrongBasketKey));
+            }
+            let basket = Account::<Basket>::try_from(basket_info)?;
+            let index = basket.output_index(&deposit_account.key())?;
+            (&route_accounts[1..], Some((basket, index)))
+        } else {
+            if deposit_account.key() != strategy.deposit_address {
+                return Err(error!(ErrorCode::DepositAddressMisMatch));
+            }
+            (route_accounts, None)
+        };
+
+        // Split deposit strategies pay their proceeds out to each recipient
+        let (route_accounts, deposit_split) = if strategy.deposit_split_key().is_some() {
+            let (deposit_split, recipients) = load_deposit_split(route_accounts, strategy)?;
+            let route_start = 1 + recipients.len();
+            (
+                &route_accounts[route_start..],
+                Some((deposit_split, recipients)),
+            )
+        } else {
+            (route_accounts, None)
+        };
+
+        // Strategies that pay their executor receive the route's output in an account they own,
+        //  so the executor's share can be settled from it however the route ends
+        let (route_accounts, executor_payment) = if strategy.pays_executor() {
+            let executor_payment =
+                load_executor_payment_accounts(route_accounts, &strategy.key(), deposit_account)?;
+            (
+                &route_accounts[EXECUTOR_PAYMENT_ACCOUNTS_LEN..],
+                Some(executor_payment),
+            )
+        } else {
+            (route_accounts, None)
+        };
+
+        let bound = TradeBound::resolve(
+            strategy,
+            basket.as_ref().map(|(basket, index)| (&**basket, *index)),
+            order_payer.amount,
+        )?;
+
+        Ok(PreTrade {
+            oco_pair,
+            downstream,
+            basket,
+            deposit_split,
+            executor_payment,
+            route_accounts,
+            bound,
+        })
+    }
+}
+
+///
+/// Validate that the strategy may sell `input_amount` through the route. The stop loss of an
+/// OCO pair only sells once the market has fallen to its trigger, read from slices of the input
+/// so that the fill's own price cannot trigger it.
+///
+pub(crate) fn validate_stop_loss_trigger(
+    oco_pair: Option<&OcoPair>,
+    strategy: &Pubkey,
+    route: &SplitRoute,
+    input_amount: u64,
+) -> Result<()> {
+    match oco_pair {
+        Some(oco_pair) if !oco_pair.can_fill(strategy, &route.market_quotes(input_amount)) => {
+            Err(error!(ErrorCode::StopLossNotTriggered))
+        }
+        _ => Ok(()),
+    }
+}
+
+///
+/// Load the strategy's OcoPair from the head of the remaining accounts. Fails once the sibling
+/// has filled, as the sibling then owns the collateral.
+///
+fn load_oco_pair<'info>(
+    accounts: &[AccountInfo<'info>],
+    strategy: &Account<'info, BoundedStrategyV2>,
+    order_payer: &Pubkey,
+) -> Result<Account<'info, OcoPair>> {
+    let oco_pair_info = accounts
+        .first()
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    if strategy.oco_pair_key() != Some(oco_pair_info.key()) {
+        return Err(error!(ErrorCode::WrongOcoPairKey));
+    }
+    let oco_pair = Account::<OcoPair>::try_from(oco_pair_info)?;
+    let strategy_key = strategy.key();
+    if oco_pair.sibling_of(&strategy_key).is_none() || oco_pair.collateral_account != *order_payer {
+        return Err(error!(ErrorCode::WrongOcoPairKey));
+    }
+    if oco_pair.is_filled() && oco_pair.filled_by != strategy_key {
+        return Err(error!(ErrorCode::OcoSiblingFilled));
+    }
+    Ok(oco_pair)
+}
+
+///
+/// Load the proceeds account and the executor's token account from the head of the remaining
+/// accounts. The proceeds account must be owned by the strategy, and both accounts must share
+/// the deposit account's mint.
+///
+fn load_executor_payment_accounts<'info>(
+    accounts: &[AccountInfo<'info>],
+    strategy: &Pubkey,
+    deposit_account: &TokenAccount,
+) -> Result<(Account<'info, TokenAccount>, Account<'info, TokenAccount>)> {
+    let accounts = accounts
+        .get(..EXECUTOR_PAYMENT_ACCOUNTS_LEN)
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    let proceeds_account = Account::<TokenAccount>::try_from(&accounts[0])?;
+    if proceeds_account.owner != *strategy || proceeds_account.mint != deposit_account.mint {
+        return Err(error!(ErrorCode::WrongProceedsAccount));
+    }
+    let executor_token_account = Account::<TokenAccount>::try_from(&accounts[1])?;
+    if executor_token_account.mint != deposit_account.mint {
+        return Err(error!(ErrorCode::ExecutorTokenAccountMintMismatch));
+    }
+    Ok((proceeds_account, executor_token_account))
+}
+
+///
+/// Load the strategy's DepositSplit and its recipients' token accounts from the head of the
+/// remaining accounts. The recipients must be in the same order as in the split.
+///
+fn load_deposit_split<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+    strategy: &BoundedStrategyV2,
+) -> Result<(Account<'info, DepositSplit>, &'a [AccountInfo<'info>])> {
+    let deposit_split_info = accounts
+        .first()
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    if strategy.deposit_split_key() != Some(deposit_split_info.key()) {
+        return Err(error!(ErrorCode::WrongDepositSplitKey));
+    }
+    let deposit_split = Account::<DepositSplit>::try_from(deposit_split_info)?;
+    let recipient_count = deposit_split.recipient_count();
+    let recipients = accounts
+        .get(1..=recipient_count)
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    for (recipient, expected) in recipients.iter().zip(deposit_split.recipients.iter()) {
+        if recipient.key() != expected.address {
+            return Err(error!(ErrorCode::DepositRecipientMismatch));
+        }
+    }
+    Ok((deposit_split, recipients))
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::{AccountDeserialize, Discriminator};
+
+    use super::*;
+    use crate::state::{BasketOutput, MAX_BASKET_OUTPUTS};
+
+    #[test]
+    /// Test that a basket output is bounded by its own price and allocation
+    fn test_resolve_basket_output_bound() {
+        let mut data = BoundedStrategyV2::discriminator().to_vec();
+        data.resize(BoundedStrategyV2::LEN, 0);
+        let mut strategy = BoundedStrategyV2::try_deserialize(&mut data.as_slice()).unwrap();
+        // 20 USDC per SOL
+        strategy.bounded_price_numerator = 1_000_000_000;
+        strategy.bounded_price_denominator = 20_000_000;
+        let mut basket = Basket {
+            strategy: Pubkey::new_unique(),
+            outputs: [BasketOutput::default(); MAX_BASKET_OUTPUTS],
+            bump: 0,
+        };
+        for (output, (weight_bps, bounded_price_denominator)) in basket
+            .outputs
+            .iter_mut()
+            .zip([(6_000, 20_000_000), (4_000, 25_000_000)])
+        {
+            output.deposit_address = Pubkey::new_unique();
+            output.weight_bps = weight_bps;
+            output.bounded_price_numerator = 1_000_000_000;
+            output.bounded_price_denominator = bounded_price_denominator;
+        }
+        basket.allocate(10_000_000_000);
+
+        let bound = TradeBound::resolve(&strategy, None, 10_000_000_000).unwrap();
+        assert_eq!(
+            (
+                bound.bounded_price_numerator,
+                bound.bounded_price_denominator
+            ),
+            (1_000_000_000, 20_000_000)
+        );
+        assert_eq!(bound.available_input, 10_000_000_000);
+
+        let bound = TradeBound::resolve(&strategy, Some((&basket, 1)), 10_000_000_000).unwrap();
+        assert_eq!(bound.tier, None);
+        assert_eq!(
+            (
+                bound.bounded_price_numerator,
+                bound.bounded_price_denominator
+            ),
+            (1_000_000_000, 25_000_000)
+        );
+        assert_eq!(bound.available_input, 4_000_000_000);
+    }
+}
@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    dexes::{split_route::SplitRoute, Dex},
+    errors::ErrorCode,
+    instructions::{validate_stop_loss_trigger, AdditionalData, PreTrade, TradeBound},
+    state::BoundedStrategyV2,
+};
+
+#[derive(Accounts)]
+pub struct QuoteRoute<'info> {
+    /// The BoundedStrategy account to quote against
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        constraint = order_payer.key() == strategy.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub order_payer: Box<Account<'info, TokenAccount>>,
+    /// The strategy's deposit address, or one of its basket's outputs. Validated in the handler.
+    pub deposit_account: Box<Account<'info, TokenAccount>>,
+}
+
+/// The simulated result of a leg in the quoted route
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LegQuote {
+    /// The program ID of the DEX the leg trades on
+    pub dex_program_id: Pubkey,
+    /// The amount of tokens going into the leg
+    pub input: u64,
+    /// The amount of tokens the leg is expected to output
+    pub output: u64,
+    /// True when the leg trades the mint output by the previous leg (or the collateral mint for
+    /// the first leg, and into the deposit mint for the last leg) and produces a nonzero output
+    /// from a nonzero input. Branches allocated no input pass on their mints alone.
+    pub passed: bool,
+}
+
+/// The result of a quote, returned to callers via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteQuote {
+    /// The largest input that keeps the route within the strategy's bound
+    pub max_input: u64,
+    /// The share of the max input allocated to each branch of the route
+    pub branch_inputs: Vec<u64>,
+    /// The output the strategy is expected to keep, after the executor fee, for the max input
+    pub expected_output: u64,
+    /// True when the route at the max input passes the strategy's price check, and the stop
+    /// loss trigger of an OCO strategy
+    pub passed: bool,
+    pub legs: Vec<LegQuote>,
+}
+
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, QuoteRoute<'info>>,
+    additional_data: Vec<u8>,
+) -> Result<RouteQuote> {
+    let bounded_strategy = &ctx.accounts.strategy;
+
+    // Check and bound the quote the same way bounded_trade_v2 does. The remaining accounts are
+    //  the same as bounded_trade_v2's, companion accounts first.
+    let PreTrade {
+        oco_pair,
+        route_accounts,
+        bound:
+            TradeBound {
+                bounded_price_numerator,
+                bounded_price_denominator,
+                available_input,
+                ..
+            },
+        ..
+    } = PreTrade::load(
+        bounded_strategy,
+        &ctx.accounts.order_payer,
+        &ctx.accounts.deposit_account,
+        ctx.remaining_accounts,
+        &Clock::get()?,
+    )?;
+
+    // Build the route
+    let additional_data = AdditionalData::unpack(&additional_data)?;
+    let mut route = SplitRoute::create(
+        route_accounts,
+        &additional_data.legs,
+        &additional_data.branch_leg_counts,
+    )?;
+    route.set_executor_fee_bps(bounded_strategy.executor_fee_bps);
+
+    // Find the input the same way bounded_trade_v2 does
+    let input_tokens =
+        additional_data.input_upper_bound(bounded_strategy.clamp_fill_input(available_input));
+    let allocation = route.max_input_in_bounds(
+        input_tokens,
+        &bounded_price_numerator,
+        &bounded_price_denominator,
+        additional_data.search_iterations(),
+    );
+    let max_input: u64 = allocation.iter().sum();
+    let passed = route.simple_price_check(
+        &allocation,
+        &bounded_price_numerator,
+        &bounded_price_denominator,
+    ) && bounded_strategy
+        .validate_fill_input(max_input, available_input)
+        .is_ok()
+        && validate_stop_loss_trigger(
+            oco_pair.as_deref(),
+            &bounded_strategy.key(),
+            &route,
+            max_input,
+        )
+        .is_ok();
+
+    let legs = quote_legs(
+        &route,
+        &allocation,
+        ctx.accounts.order_payer.mint,
+        ctx.accounts.deposit_account.mint,
+    )?;
+
+    Ok(RouteQuote {
+        max_input,
+        expected_output: route.simulate_net_execution(&allocation),
+        branch_inputs: allocation,
+        passed: passed && legs.iter().all(|leg| leg.passed),
+        legs,
+    })
+}
+
+///
+/// Simulate each leg of each branch with its share of the allocation, checking that the mints
+/// line up from the collateral to the deposit
+///
+fn quote_legs(
+    route: &SplitRoute,
+    allocation: &[u64],
+    collateral_mint: Pubkey,
+    deposit_mint: Pubkey,
+) -> Result<Vec<LegQuote>> {
+    let mut legs = Vec::with_capacity(route.leg_count());
+    for (branch, branch_input) in route.branches.iter().zip(allocation.iter()) {
+        let leg_outputs = branch.simulate_legs(*branch_input);
+        let leg_count = branch.leg_count();
+        let mut expected_mint = collateral_mint;
+        let mut leg_input = *branch_input;
+        for (index, (leg, output)) in branch.legs.iter().zip(leg_outputs).enumerate() {
+            let mut leg_passed =
+                (output > 0 || leg_input == 0) && leg.start_mint()? == expected_mint;
+            expected_mint = leg.end_mint()?;
+            if index == leg_count - 1 {
+                leg_passed = leg_passed && expected_mint == deposit_mint;
+            }
+            legs.push(LegQuote {
+                dex_program_id: leg.program_id(),
+                input: leg_input,
+                output,
+                passed: leg_passed,
+            });
+            leg_input = output;
+        }
+    }
+    Ok(legs)
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::prelude::{AccountInfo, Pubkey};
+
+    use super::quote_legs;
+    use crate::dexes::split_route::{test::mock_bid_branch, SplitRoute};
+
+    #[test]
+    /// Test that a branch allocated no input still passes when its mints line up
+    fn test_quote_legs_with_unallocated_branch() {
+        // The leg reads its input mint from account 14 and its output mint from account 15
+        let (collateral_mint, deposit_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = [0; 16];
+        let mut data: Vec<Vec<u8>> = (0..16).map(|_| vec![0; 165]).collect();
+        data[14][..32].copy_from_slice(collateral_mint.as_ref());
+        data[15][..32].copy_from_slice(deposit_mint.as_ref());
+        let key = Pubkey::new_unique();
+        let accounts: Vec<AccountInfo> = lamports
+            .iter_mut()
+            .zip(data.iter_mut())
+            .map(|(lamports, data)| {
+                AccountInfo::new(&key, false, false, lamports, data, &key, false, 0)
+            })
+            .collect();
+        let split_route = SplitRoute {
+            branches: vec![
+                mock_bid_branch(&accounts, 92_000_000, 4_000_000_000),
+                mock_bid_branch(&accounts, 93_000_000, 6_000_000_000),
+            ],
+        };
+
+        let legs = quote_legs(
+            &split_route,
+            &[0, 5_000_000_000],
+            collateral_mint,
+            deposit_mint,
+        )
+        .unwrap();
+        assert_eq!(legs.len(), 2);
+        assert_eq!((legs[0].input, legs[0].output), (0, 0));
+        assert!(legs[1].output > 0);
+        assert!(legs.iter().all(|leg| leg.passed));
+
+        // Its mints are still checked
+        let legs = quote_legs(
+            &split_route,
+            &[0, 5_000_000_000],
+            deposit_mint,
+            deposit_mint,
+        )
+        .unwrap();
+        assert!(legs.iter().all(|leg| !leg.passed));
+    }
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    constants::REBALANCE_STRATEGY_SEED,
+    dexes::{settle_trade, split_route::SplitRoute},
+    errors::ErrorCode,
+    events::RebalanceTradeEvent,
+    instructions::{AdditionalData, TradeReceipt},
+    rebalance_signer_seeds,
+    state::RebalanceStrategy,
+};
+
+#[derive(Accounts)]
+pub struct RebalanceTrade<'info> {
+    /// Anyone can fire this transaction
+    pub payer: Signer<'info>,
+    /// The RebalanceStrategy account
+    pub strategy: Box<Account<'info, RebalanceStrategy>>,
+    #[account(
+        mut,
+        constraint = base_account.key() == strategy.base_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub base_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = quote_account.key() == strategy.quote_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub quote_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+///
+/// Trade the overweight holding back toward the target weight through a route. The trade is
+/// capped at the amount that reaches the target at the reference price, and must fill within
+/// the strategy's max deviation from it.
+///
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RebalanceTrade<'info>>,
+    additional_data: Vec<u8>,
+) -> Result<TradeReceipt> {
+    let strategy = &ctx.accounts.strategy;
+    let clock = Clock::get()?;
+    strategy.validate_can_trade(&clock)?;
+    let trade = strategy.rebalance_trade(
+        ctx.accounts.base_account.amount,
+        ctx.accounts.quote_account.amount,
+    )?;
+
+    let (source_account, destination_account) = if trade.sells_base {
+        (&ctx.accounts.base_account, &ctx.accounts.quote_account)
+    } else {
+        (&ctx.accounts.quote_account, &ctx.accounts.base_account)
+    };
+
+    // Build the route, which must trade from the overweight holding to the other
+    let additional_data = AdditionalData::unpack(&additional_data)?;
+    let route = SplitRoute::create(
+        ctx.remaining_accounts,
+        &additional_data.legs,
+        &additional_data.branch_leg_counts,
+    )?;
+    if source_account.mint != route.start_mint()? {
+        return Err(error!(ErrorCode::InputMintMismatch));
+    }
+    if destination_account.mint != route.end_mint()? {
+        return Err(error!(ErrorCode::OutputMintMismatch));
+    }
+
+    // Trade as much of the imbalance as the route fills within the bound
+    let allocation = route.max_input_in_bounds(
+        additional_data.input_upper_bound(trade.input_amount),
+        &trade.bounded_price_numerator,
+        &trade.bounded_price_denominator,
+        additional_data.search_iterations(),
+    );
+    if !route.simple_price_check(
+        &allocation,
+        &trade.bounded_price_numerator,
+        &trade.bounded_price_denominator,
+    ) {
+        return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
+    }
+
+    let starting_balances = (source_account.amount, destination_account.amount);
+    let source_account_info = source_account.to_account_info();
+    route.execute_delegated(
+        &allocation,
+        &ctx.accounts.token_program.to_account_info(),
+        &source_account_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.strategy.to_account_info(),
+        &[rebalance_signer_seeds!(ctx.accounts.strategy)],
+    )?;
+
+    // Check the actual deltas against the deviation bound
+    let (source_account, destination_account) = if trade.sells_base {
+        (
+            &mut ctx.accounts.base_account,
+            &mut ctx.accounts.quote_account,
+        )
+    } else {
+        (
+            &mut ctx.accounts.quote_account,
+            &mut ctx.accounts.base_account,
+        )
+    };
+    let (input_tokens_used, output_tokens_gained) = settle_trade(
+        source_account,
+        destination_account,
+        starting_balances,
+        &trade.bounded_price_numerator,
+        &trade.bounded_price_denominator,
+    )?;
+    if input_tokens_used > trade.input_amount {
+        return Err(error!(ErrorCode::MarketPriceIsOutOfBounds));
+    }
+
+    emit!(RebalanceTradeEvent {
+        strategy: ctx.accounts.strategy.key(),
+        executor: ctx.accounts.payer.key(),
+        sold_base: trade.sells_base,
+        input_used: input_tokens_used,
+        output_gained: output_tokens_gained,
+        base_weight_bps: ctx.accounts.strategy.base_weight_bps(
+            ctx.accounts.base_account.amount,
+            ctx.accounts.quote_account.amount,
+        ),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(TradeReceipt {
+        input_used: input_tokens_used,
+        output_gained: output_tokens_gained,
+        deposit_amount: output_tokens_gained,
+        legs_executed: route.leg_count() as u8,
+        effective_price_numerator: input_tokens_used,
+        effective_price_denominator: output_tokens_gained,
+    })
+}
@@ -6,7 +6,7 @@ use anchor_spl::{
 
 use crate::{
     authority_signer_seeds, constants::AUTHORITY_SEED, dexes::open_book_dex, errors::ErrorCode,
-    state::BoundedStrategy,
+    events::ReclaimEvent, state::BoundedStrategy,
 };
 
 #[derive(Accounts)]
@@ -69,6 +69,12 @@ pub fn handler(ctx: Context<Reclaim>) -> Result<()> {
     };
     token::transfer(cpi_ctx, ctx.accounts.order_payer.amount)?;
 
+    emit!(ReclaimEvent {
+        strategy: ctx.accounts.strategy.key(),
+        reclaim_address: ctx.accounts.reclaim_account.key(),
+        amount: ctx.accounts.order_payer.amount,
+    });
+
     // Close the OrderPayer account
     let cpi_accounts = CloseAccount {
         account: ctx.accounts.order_payer.to_account_info(),
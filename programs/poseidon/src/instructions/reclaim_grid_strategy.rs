@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+use crate::{
+    constants::GRID_STRATEGY_SEED, errors::ErrorCode, events::ReclaimGridStrategyEvent,
+    grid_signer_seeds, state::GridStrategy,
+};
+
+#[derive(Accounts)]
+pub struct ReclaimGridStrategy<'info> {
+    /// The account that will receive the SOL
+    /// CHECK: no need for checks
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+    /// The GridStrategy account
+    #[account(
+        mut,
+        close = receiver
+    )]
+    pub strategy: Box<Account<'info, GridStrategy>>,
+    #[account(
+        mut,
+        constraint = base_account.key() == strategy.base_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub base_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = quote_account.key() == strategy.quote_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub quote_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = base_reclaim_account.key() == strategy.base_reclaim_address
+            @ ErrorCode::WrongReclaimAddress
+    )]
+    pub base_reclaim_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = quote_reclaim_account.key() == strategy.quote_reclaim_address
+            @ ErrorCode::WrongReclaimAddress
+    )]
+    pub quote_reclaim_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<ReclaimGridStrategy>) -> Result<()> {
+    let grid = &ctx.accounts.strategy;
+    if grid.reclaim_date > Clock::get()?.unix_timestamp {
+        return Err(ErrorCode::ReclaimDateHasNotPassed.into());
+    }
+
+    // Return both sides of the grid and close their token accounts
+    for (account, reclaim_account) in [
+        (
+            &ctx.accounts.base_account,
+            &ctx.accounts.base_reclaim_account,
+        ),
+        (
+            &ctx.accounts.quote_account,
+            &ctx.accounts.quote_reclaim_account,
+        ),
+    ] {
+        let cpi_ctx = CpiContext {
+            program: ctx.accounts.token_program.to_account_info(),
+            accounts: Transfer {
+                from: account.to_account_info(),
+                to: reclaim_account.to_account_info(),
+                authority: grid.to_account_info(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[grid_signer_seeds!(grid)],
+        };
+        token::transfer(cpi_ctx, account.amount)?;
+
+        let cpi_ctx = CpiContext {
+            program: ctx.accounts.token_program.to_account_info(),
+            accounts: CloseAccount {
+                account: account.to_account_info(),
+                destination: ctx.accounts.receiver.to_account_info(),
+                authority: grid.to_account_info(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[grid_signer_seeds!(grid)],
+        };
+        token::close_account(cpi_ctx)?;
+    }
+
+    emit!(ReclaimGridStrategyEvent {
+        strategy: grid.key(),
+        base_amount: ctx.accounts.base_account.amount,
+        quote_amount: ctx.accounts.quote_account.amount,
+    });
+
+    Ok(())
+}
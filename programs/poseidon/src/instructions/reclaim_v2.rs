@@ -1,11 +1,60 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use anchor_spl::{
+    dex::{CloseOpenOrders, SettleFunds},
+    token::{
+        self, spl_token::instruction::AuthorityType, CloseAccount, SetAuthority, Token,
+        TokenAccount, Transfer,
+    },
+};
 
 use crate::{
-    constants::BOUNDED_STRATEGY_SEED, errors::ErrorCode, state::BoundedStrategyV2,
+    constants::{BOUNDED_STRATEGY_SEED, OCO_PAIR_SEED},
+    dexes::open_book_dex,
+    errors::ErrorCode,
+    events::ReclaimV2Event,
+    oco_pair_signer_seeds,
+    serum_utils::{self, MarketInfo, OpenOrdersBalances},
+    state::{Basket, BoundedStrategyV2, DepositSplit, OcoPair, StrategyExtension},
     strategy_signer_seeds,
 };
 
+/*
+ * A strategy has at most one extension, so at most one of the account lists below leads the
+ * remaining accounts.
+ */
+
+/**
+ * RESTING ORDER REMAINING ACCOUNTS, required when the strategy has an OpenOrders account
+ * 0 - open_orders
+ * 1 - serum_market
+ * 2 - coin_vault
+ * 3 - pc_vault
+ * 4 - serum_vault_signer
+ * 5 - dex_program
+ * 6 - deposit_account
+ */
+const RESTING_ORDER_ACCOUNTS_LEN: usize = 7;
+
+/*
+ * BASKET REMAINING ACCOUNTS, required when the strategy has a basket
+ * 0 - basket
+ */
+
+/**
+ * DEPOSIT SPLIT REMAINING ACCOUNTS, required when the strategy splits its deposits
+ * 0 - deposit_split
+ * 1 - proceeds_account
+ * 2 - first_recipient
+ */
+const DEPOSIT_SPLIT_ACCOUNTS_LEN: usize = 3;
+
+/**
+ * OCO PAIR REMAINING ACCOUNTS, required when the strategy is in an OCO pair
+ * 0 - oco_pair
+ * 1 - sibling strategy
+ */
+const OCO_PAIR_ACCOUNTS_LEN: usize = 2;
+
 #[derive(Accounts)]
 pub struct ReclaimV2<'info> {
     /// The account that will receive the SOL
@@ -34,11 +83,31 @@ pub struct ReclaimV2<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+///
+/// Return the strategy's collateral to its reclaim address once the reclaim date has passed and
+/// close the strategy with its extension's accounts. OpenBook only releases a cancelled order's
+/// funds once its cancel event is consumed, so a strategy with a resting order is reclaimed in
+/// three steps: `cancel_resting_order_v2`, a `consume_events` crank that includes the strategy's
+/// OpenOrders account, then `reclaim_v2`, which settles and closes the OpenOrders account.
+///
 pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ReclaimV2<'info>>) -> Result<()> {
     let bounded_strategy = &ctx.accounts.strategy;
     if bounded_strategy.reclaim_date > Clock::get()?.unix_timestamp {
         return Err(ErrorCode::ReclaimDateHasNotPassed.into());
     }
+    // The strategy's extension decides which companion accounts lead the remaining accounts
+    let accounts = ctx.remaining_accounts;
+    match bounded_strategy.extension {
+        StrategyExtension::RestingOrders { .. } => {
+            close_open_orders(&ctx, accounts)?;
+            ctx.accounts.collateral_account.reload()?;
+        }
+        StrategyExtension::Basket { .. } => close_basket(&ctx, accounts)?,
+        StrategyExtension::DepositSplit { .. } => close_deposit_split(&ctx, accounts)?,
+        StrategyExtension::OcoPair { .. } => close_oco_pair(&ctx, accounts)?,
+        StrategyExtension::None | StrategyExtension::Downstream { .. } => {}
+    }
+    let bounded_strategy = &ctx.accounts.strategy;
 
     let cpi_accounts = Transfer {
         from: ctx.accounts.collateral_account.to_account_info(),
@@ -55,6 +124,12 @@ pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ReclaimV2<'info>>) -> Resu
     };
     token::transfer(cpi_ctx, ctx.accounts.collateral_account.amount)?;
 
+    emit!(ReclaimV2Event {
+        strategy: ctx.accounts.strategy.key(),
+        reclaim_address: ctx.accounts.reclaim_account.key(),
+        amount: ctx.accounts.collateral_account.amount,
+    });
+
     let cpi_accounts = CloseAccount {
         account: ctx.accounts.collateral_account.to_account_info(),
         destination: ctx.accounts.receiver.to_account_info(),
@@ -68,3 +143,206 @@ pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ReclaimV2<'info>>) -> Resu
     };
     token::close_account(cpi_ctx)
 }
+
+///
+/// Settle the strategy's OpenOrders account, sending any fills to the deposit address and any
+/// unfilled collateral back to the collateral account, then close it. The resting order must
+/// already be cancelled and its cancel event consumed.
+///
+fn close_open_orders<'info>(
+    ctx: &Context<'_, '_, '_, 'info, ReclaimV2<'info>>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let accounts = accounts
+        .get(..RESTING_ORDER_ACCOUNTS_LEN)
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    let open_orders = &accounts[0];
+    let serum_market = &accounts[1];
+    let dex_program = &accounts[5];
+    let deposit_account = &accounts[6];
+    if ctx.accounts.strategy.open_orders_key() != Some(open_orders.key()) {
+        return Err(error!(ErrorCode::WrongOpenOrdersKey));
+    }
+    if dex_program.key() != open_book_dex::ID {
+        return Err(anchor_lang::error::ErrorCode::InvalidProgramId.into());
+    }
+    if deposit_account.key() != ctx.accounts.strategy.deposit_address {
+        return Err(error!(ErrorCode::DepositAddressMisMatch));
+    }
+    if OpenOrdersBalances::load(&open_orders.try_borrow_data()?)?.has_orders() {
+        return Err(error!(ErrorCode::RestingOrderNotCancelled));
+    }
+
+    let market = MarketInfo::load(serum_market, dex_program.key)?;
+    let (coin_wallet, pc_wallet) = if market.coin_mint == ctx.accounts.strategy.collateral_mint {
+        (
+            ctx.accounts.collateral_account.to_account_info(),
+            deposit_account.clone(),
+        )
+    } else {
+        (
+            deposit_account.clone(),
+            ctx.accounts.collateral_account.to_account_info(),
+        )
+    };
+    let cpi_ctx = CpiContext {
+        program: dex_program.clone(),
+        accounts: SettleFunds {
+            market: serum_market.clone(),
+            open_orders: open_orders.clone(),
+            open_orders_authority: ctx.accounts.strategy.to_account_info(),
+            coin_vault: accounts[2].clone(),
+            pc_vault: accounts[3].clone(),
+            coin_wallet,
+            pc_wallet,
+            vault_signer: accounts[4].clone(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    serum_utils::settle_funds(cpi_ctx)?;
+
+    let cpi_ctx = CpiContext {
+        program: dex_program.clone(),
+        accounts: CloseOpenOrders {
+            open_orders: open_orders.clone(),
+            authority: ctx.accounts.strategy.to_account_info(),
+            destination: ctx.accounts.receiver.to_account_info(),
+            market: serum_market.clone(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    serum_utils::close_open_orders(cpi_ctx)
+}
+
+/// Close the strategy's basket, returning its rent to the receiver
+fn close_basket<'info>(
+    ctx: &Context<'_, '_, '_, 'info, ReclaimV2<'info>>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let basket_info = accounts
+        .first()
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    if ctx.accounts.strategy.basket_key() != Some(basket_info.key()) {
+        return Err(error!(ErrorCode::WrongBasketKey));
+    }
+    let basket = Account::<Basket>::try_from(basket_info)?;
+    basket.close(ctx.accounts.receiver.to_account_info())
+}
+
+///
+/// Sweep any proceeds left in the strategy's proceeds account to the split's first recipient,
+/// then close the proceeds account and the DepositSplit
+///
+fn close_deposit_split<'info>(
+    ctx: &Context<'_, '_, '_, 'info, ReclaimV2<'info>>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let accounts = accounts
+        .get(..DEPOSIT_SPLIT_ACCOUNTS_LEN)
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    let deposit_split_info = &accounts[0];
+    let proceeds_info = &accounts[1];
+    let first_recipient = &accounts[2];
+    if ctx.accounts.strategy.deposit_split_key() != Some(deposit_split_info.key()) {
+        return Err(error!(ErrorCode::WrongDepositSplitKey));
+    }
+    if proceeds_info.key() != ctx.accounts.strategy.deposit_address {
+        return Err(error!(ErrorCode::DepositAddressMisMatch));
+    }
+    let deposit_split = Account::<DepositSplit>::try_from(deposit_split_info)?;
+    if first_recipient.key() != deposit_split.recipients[0].address {
+        return Err(error!(ErrorCode::DepositRecipientMismatch));
+    }
+
+    let proceeds_account = Account::<TokenAccount>::try_from(proceeds_info)?;
+    if proceeds_account.amount > 0 {
+        let cpi_ctx = CpiContext {
+            program: ctx.accounts.token_program.to_account_info(),
+            accounts: Transfer {
+                from: proceeds_info.clone(),
+                to: first_recipient.clone(),
+                authority: ctx.accounts.strategy.to_account_info(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+        };
+        token::transfer(cpi_ctx, proceeds_account.amount)?;
+    }
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.token_program.to_account_info(),
+        accounts: CloseAccount {
+            account: proceeds_info.clone(),
+            destination: ctx.accounts.receiver.to_account_info(),
+            authority: ctx.accounts.strategy.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    token::close_account(cpi_ctx)?;
+
+    deposit_split.close(ctx.accounts.receiver.to_account_info())
+}
+
+///
+/// Take ownership of the OCO pair's collateral account from the pair or the sibling that
+/// filled, then close the sibling strategy and the OcoPair
+///
+fn close_oco_pair<'info>(
+    ctx: &Context<'_, '_, '_, 'info, ReclaimV2<'info>>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let accounts = accounts
+        .get(..OCO_PAIR_ACCOUNTS_LEN)
+        .ok_or(anchor_lang::error::ErrorCode::AccountNotEnoughKeys)?;
+    let oco_pair_info = &accounts[0];
+    let sibling_info = &accounts[1];
+    if ctx.accounts.strategy.oco_pair_key() != Some(oco_pair_info.key()) {
+        return Err(error!(ErrorCode::WrongOcoPairKey));
+    }
+    let oco_pair = Account::<OcoPair>::try_from(oco_pair_info)?;
+    if oco_pair.sibling_of(&ctx.accounts.strategy.key()) != Some(sibling_info.key())
+        || oco_pair.collateral_account != ctx.accounts.collateral_account.key()
+    {
+        return Err(error!(ErrorCode::WrongOcoPairKey));
+    }
+    let sibling = Account::<BoundedStrategyV2>::try_from(sibling_info)?;
+
+    let collateral_owner = ctx.accounts.collateral_account.owner;
+    if collateral_owner == oco_pair.key() {
+        let cpi_ctx = CpiContext {
+            program: ctx.accounts.token_program.to_account_info(),
+            accounts: SetAuthority {
+                current_authority: oco_pair_info.clone(),
+                account_or_mint: ctx.accounts.collateral_account.to_account_info(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[oco_pair_signer_seeds!(oco_pair)],
+        };
+        token::set_authority(
+            cpi_ctx,
+            AuthorityType::AccountOwner,
+            Some(ctx.accounts.strategy.key()),
+        )?;
+    } else if collateral_owner == sibling.key() {
+        let cpi_ctx = CpiContext {
+            program: ctx.accounts.token_program.to_account_info(),
+            accounts: SetAuthority {
+                current_authority: sibling_info.clone(),
+                account_or_mint: ctx.accounts.collateral_account.to_account_info(),
+            },
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[strategy_signer_seeds!(sibling)],
+        };
+        token::set_authority(
+            cpi_ctx,
+            AuthorityType::AccountOwner,
+            Some(ctx.accounts.strategy.key()),
+        )?;
+    }
+
+    sibling.close(ctx.accounts.receiver.to_account_info())?;
+    oco_pair.close(ctx.accounts.receiver.to_account_info())
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    dex::SettleFunds,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    constants::BOUNDED_STRATEGY_SEED,
+    dexes::open_book_dex,
+    errors::ErrorCode,
+    events::SettleRestingOrderV2Event,
+    serum_utils::{self, MarketInfo, OpenOrdersBalances},
+    state::BoundedStrategyV2,
+    strategy_signer_seeds,
+};
+
+#[derive(Accounts)]
+pub struct SettleRestingOrderV2<'info> {
+    /// Anyone can fire this transaction
+    pub payer: Signer<'info>,
+    /// The BoundedStrategy account
+    #[account(mut)]
+    pub strategy: Box<Account<'info, BoundedStrategyV2>>,
+    #[account(
+        mut,
+        constraint = order_payer.key() == strategy.collateral_account
+            @ ErrorCode::OrderPayerMisMatch,
+    )]
+    pub order_payer: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = deposit_account.key() == strategy.deposit_address
+            @ ErrorCode::DepositAddressMisMatch
+    )]
+    pub deposit_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Constraints are handled
+    #[account(
+        mut,
+        constraint = strategy.has_open_orders() @ ErrorCode::OpenOrdersNotInitialized,
+        constraint = strategy.open_orders_key() == Some(open_orders.key())
+            @ ErrorCode::WrongOpenOrdersKey,
+    )]
+    pub open_orders: UncheckedAccount<'info>,
+    /// CHECK: Constraints are handled
+    #[account(
+        mut,
+        owner = open_book_dex::ID
+    )]
+    pub serum_market: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    #[account(mut)]
+    pub pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked by the Serum program
+    pub serum_vault_signer: UncheckedAccount<'info>,
+    /// The Serum program
+    pub dex_program: Program<'info, open_book_dex::OpenBookDexV3>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<SettleRestingOrderV2>) -> Result<()> {
+    let clock = Clock::get()?;
+    let market = MarketInfo::load(&ctx.accounts.serum_market, ctx.accounts.dex_program.key)?;
+    let collateral_is_coin = market.coin_mint == ctx.accounts.strategy.collateral_mint;
+    let (coin_wallet, pc_wallet) = if collateral_is_coin {
+        (
+            ctx.accounts.order_payer.to_account_info(),
+            ctx.accounts.deposit_account.to_account_info(),
+        )
+    } else {
+        (
+            ctx.accounts.deposit_account.to_account_info(),
+            ctx.accounts.order_payer.to_account_info(),
+        )
+    };
+
+    let collateral_before = ctx.accounts.order_payer.amount;
+    let deposit_before = ctx.accounts.deposit_account.amount;
+    let cpi_ctx = CpiContext {
+        program: ctx.accounts.dex_program.to_account_info(),
+        accounts: SettleFunds {
+            market: ctx.accounts.serum_market.to_account_info(),
+            open_orders: ctx.accounts.open_orders.to_account_info(),
+            open_orders_authority: ctx.accounts.strategy.to_account_info(),
+            coin_vault: ctx.accounts.coin_vault.to_account_info(),
+            pc_vault: ctx.accounts.pc_vault.to_account_info(),
+            coin_wallet,
+            pc_wallet,
+            vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[strategy_signer_seeds!(ctx.accounts.strategy)],
+    };
+    serum_utils::settle_funds(cpi_ctx)?;
+    ctx.accounts.order_payer.reload()?;
+    ctx.accounts.deposit_account.reload()?;
+
+    // Whatever the order committed that was neither returned nor is still locked was filled
+    let balances = OpenOrdersBalances::load(&ctx.accounts.open_orders.try_borrow_data()?)?;
+    let still_resting = if collateral_is_coin {
+        balances.native_coin_total
+    } else {
+        balances.native_pc_total
+    };
+    let collateral_returned = ctx
+        .accounts
+        .order_payer
+        .amount
+        .saturating_sub(collateral_before);
+    let input_used = ctx
+        .accounts
+        .strategy
+        .resting_input
+        .saturating_sub(collateral_returned)
+        .saturating_sub(still_resting);
+    let deposit_amount = ctx
+        .accounts
+        .deposit_account
+        .amount
+        .saturating_sub(deposit_before);
+
+    let bounded_strategy = &mut ctx.accounts.strategy;
+    bounded_strategy.resting_input = still_resting;
+    if input_used > 0 || deposit_amount > 0 {
+        bounded_strategy.record_fill(None, input_used, deposit_amount, &clock);
+    }
+
+    emit!(SettleRestingOrderV2Event {
+        strategy: bounded_strategy.key(),
+        input_used,
+        deposit_amount,
+        resting_input: still_resting,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
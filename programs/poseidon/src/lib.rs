@@ -3,6 +3,7 @@ use anchor_lang::prelude::*;
 pub mod constants;
 pub(crate) mod dexes;
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub(crate) mod macros;
 pub(crate) mod serum_utils;
@@ -10,6 +11,15 @@ pub mod state;
 pub mod utils;
 
 use crate::instructions::*;
+use crate::state::{BasketOutputParams, PriceTier};
+
+/// Decode the return data of `bounded_trade_v2`, `fill_strategy_v2` and `quote_route` when
+/// calling them via CPI
+#[cfg(feature = "cpi")]
+pub use crate::instructions::{
+    bounded_trade_v2::TradeReceipt,
+    quote_route::{LegQuote, RouteQuote},
+};
 
 declare_id!("8TJjyzq3iXc48MgV6TD5DumKKwfWKU14Jr9pwgnAbpzs");
 
@@ -64,30 +74,202 @@ pub mod poseidon {
         instructions::settle_funds::handler(ctx)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    #[access_control(InitBoundedStrategyV2::valid_arguments(
+        transfer_amount,
+        start_date,
+        reclaim_date,
+        &price_tiers,
+        executor_fee_bps,
+        surplus_share_bps,
+        min_fill_input,
+        max_fill_input
+    ))]
     pub fn init_bounded_strategy_v2<'info>(
         ctx: Context<'_, '_, '_, 'info, InitBoundedStrategyV2<'info>>,
         transfer_amount: u64,
         bounded_price_numerator: u64,
         bounded_price_denominator: u64,
+        start_date: i64,
         reclaim_date: i64,
+        price_tiers: Vec<PriceTier>,
+        executor_fee_bps: u16,
+        surplus_share_bps: u16,
+        min_fill_input: u64,
+        max_fill_input: u64,
+        min_slots_between_trades: u64,
     ) -> Result<()> {
         instructions::init_bounded_strategy_v2::handler(
             ctx,
             transfer_amount,
             bounded_price_numerator,
             bounded_price_denominator,
+            start_date,
             reclaim_date,
+            price_tiers,
+            executor_fee_bps,
+            surplus_share_bps,
+            min_fill_input,
+            max_fill_input,
+            min_slots_between_trades,
         )
     }
 
     pub fn bounded_trade_v2<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, BoundedTradeV2<'info>>,
         additional_data: Vec<u8>,
-    ) -> Result<()> {
+    ) -> Result<TradeReceipt> {
         instructions::bounded_trade_v2::handler(ctx, additional_data)
     }
 
+    pub fn quote_route<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, QuoteRoute<'info>>,
+        additional_data: Vec<u8>,
+    ) -> Result<RouteQuote> {
+        instructions::quote_route::handler(ctx, additional_data)
+    }
+
+    pub fn match_strategies_v2(ctx: Context<MatchStrategiesV2>) -> Result<()> {
+        instructions::match_strategies_v2::handler(ctx)
+    }
+
+    #[access_control(FillStrategyV2::valid_arguments(input_amount, output_amount))]
+    pub fn fill_strategy_v2(
+        ctx: Context<FillStrategyV2>,
+        input_amount: u64,
+        output_amount: u64,
+    ) -> Result<TradeReceipt> {
+        instructions::fill_strategy_v2::handler(ctx, input_amount, output_amount)
+    }
+
+    pub fn init_open_orders_v2(
+        ctx: Context<InitOpenOrdersV2>,
+        open_orders_space: u64,
+    ) -> Result<()> {
+        instructions::init_open_orders_v2::handler(ctx, open_orders_space)
+    }
+
+    pub fn place_resting_order_v2(ctx: Context<PlaceRestingOrderV2>) -> Result<()> {
+        instructions::place_resting_order_v2::handler(ctx)
+    }
+
+    pub fn settle_resting_order_v2(ctx: Context<SettleRestingOrderV2>) -> Result<()> {
+        instructions::settle_resting_order_v2::handler(ctx)
+    }
+
+    pub fn cancel_resting_order_v2(ctx: Context<CancelRestingOrderV2>) -> Result<()> {
+        instructions::cancel_resting_order_v2::handler(ctx)
+    }
+
+    #[access_control(InitBasketV2::valid_arguments(&outputs))]
+    pub fn init_basket_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitBasketV2<'info>>,
+        outputs: Vec<BasketOutputParams>,
+    ) -> Result<()> {
+        instructions::init_basket_v2::handler(ctx, outputs)
+    }
+
+    #[access_control(InitDepositSplitV2::valid_arguments(&weights_bps))]
+    pub fn init_deposit_split_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitDepositSplitV2<'info>>,
+        weights_bps: Vec<u16>,
+    ) -> Result<()> {
+        instructions::init_deposit_split_v2::handler(ctx, weights_bps)
+    }
+
+    #[access_control(InitOcoPairV2::valid_arguments(
+        stop_loss_trigger_numerator,
+        stop_loss_trigger_denominator
+    ))]
+    pub fn init_oco_pair_v2(
+        ctx: Context<InitOcoPairV2>,
+        stop_loss_trigger_numerator: u64,
+        stop_loss_trigger_denominator: u64,
+    ) -> Result<()> {
+        instructions::init_oco_pair_v2::handler(
+            ctx,
+            stop_loss_trigger_numerator,
+            stop_loss_trigger_denominator,
+        )
+    }
+
+    pub fn chain_strategies_v2(ctx: Context<ChainStrategiesV2>) -> Result<()> {
+        instructions::chain_strategies_v2::handler(ctx)
+    }
+
+    #[access_control(DepositV2::valid_arguments(amount))]
+    pub fn deposit_v2(ctx: Context<DepositV2>, amount: u64) -> Result<()> {
+        instructions::deposit_v2::handler(ctx, amount)
+    }
+
     pub fn reclaim_v2<'info>(ctx: Context<'_, '_, '_, 'info, ReclaimV2<'info>>) -> Result<()> {
         instructions::reclaim_v2::handler(ctx)
     }
+
+    #[access_control(InitGridStrategy::valid_arguments(&params))]
+    pub fn init_grid_strategy(
+        ctx: Context<InitGridStrategy>,
+        params: InitGridStrategyParams,
+    ) -> Result<()> {
+        instructions::init_grid_strategy::handler(ctx, params)
+    }
+
+    pub fn grid_trade<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, GridTrade<'info>>,
+        range: u8,
+        additional_data: Vec<u8>,
+    ) -> Result<TradeReceipt> {
+        instructions::grid_trade::handler(ctx, range, additional_data)
+    }
+
+    pub fn reclaim_grid_strategy(ctx: Context<ReclaimGridStrategy>) -> Result<()> {
+        instructions::reclaim_grid_strategy::handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[access_control(InitRebalanceStrategy::valid_arguments(
+        reference_price_numerator,
+        reference_price_denominator,
+        target_base_weight_bps,
+        tolerance_bps,
+        max_deviation_bps,
+        start_date,
+        reclaim_date
+    ))]
+    pub fn init_rebalance_strategy(
+        ctx: Context<InitRebalanceStrategy>,
+        base_transfer_amount: u64,
+        quote_transfer_amount: u64,
+        reference_price_numerator: u64,
+        reference_price_denominator: u64,
+        target_base_weight_bps: u16,
+        tolerance_bps: u16,
+        max_deviation_bps: u16,
+        start_date: i64,
+        reclaim_date: i64,
+    ) -> Result<()> {
+        instructions::init_rebalance_strategy::handler(
+            ctx,
+            base_transfer_amount,
+            quote_transfer_amount,
+            reference_price_numerator,
+            reference_price_denominator,
+            target_base_weight_bps,
+            tolerance_bps,
+            max_deviation_bps,
+            start_date,
+            reclaim_date,
+        )
+    }
+
+    pub fn rebalance_trade<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RebalanceTrade<'info>>,
+        additional_data: Vec<u8>,
+    ) -> Result<TradeReceipt> {
+        instructions::rebalance_trade::handler(ctx, additional_data)
+    }
+
+    pub fn reclaim_rebalance_strategy(ctx: Context<ReclaimRebalanceStrategy>) -> Result<()> {
+        instructions::reclaim_rebalance_strategy::handler(ctx)
+    }
 }
@@ -5,6 +5,7 @@ macro_rules! strategy_signer_seeds {
             &$strategy.collateral_mint.as_ref(),
             &$strategy.bounded_price_numerator.to_le_bytes(),
             &$strategy.bounded_price_denominator.to_le_bytes(),
+            &$crate::state::start_date_seed($strategy.start_date)[..],
             &$strategy.reclaim_date.to_le_bytes(),
             BOUNDED_STRATEGY_SEED.as_bytes(),
             &[$strategy.bump],
@@ -12,6 +13,46 @@ macro_rules! strategy_signer_seeds {
     };
 }
 
+#[macro_export]
+macro_rules! oco_pair_signer_seeds {
+    ($pair:expr) => {
+        &[
+            &$pair.take_profit.as_ref(),
+            &$pair.stop_loss.as_ref(),
+            OCO_PAIR_SEED.as_bytes(),
+            &[$pair.bump],
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! grid_signer_seeds {
+    ($grid:expr) => {
+        &[
+            &$grid.base_mint.as_ref(),
+            &$grid.quote_mint.as_ref(),
+            &$grid.lower_price.to_le_bytes(),
+            &$grid.upper_price.to_le_bytes(),
+            &$grid.reclaim_date.to_le_bytes(),
+            GRID_STRATEGY_SEED.as_bytes(),
+            &[$grid.bump],
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! rebalance_signer_seeds {
+    ($strategy:expr) => {
+        &[
+            &$strategy.base_mint.as_ref(),
+            &$strategy.quote_mint.as_ref(),
+            &$strategy.reclaim_date.to_le_bytes(),
+            REBALANCE_STRATEGY_SEED.as_bytes(),
+            &[$strategy.bump],
+        ]
+    };
+}
+
 #[macro_export]
 macro_rules! authority_signer_seeds {
     ($ctx:expr, $bump:ident) => {
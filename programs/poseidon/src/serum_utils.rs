@@ -2,13 +2,24 @@ use std::convert::{TryFrom, TryInto};
 use std::ops::DerefMut;
 
 use anchor_lang::prelude::*;
-use anchor_spl::dex::serum_dex::{
-    critbit::{LeafNode, Slab, SlabView},
-    declare_check_assert_macros,
-    error::SourceFileId,
-    matching::OrderBookState,
-    state::Market,
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::dex::{
+    serum_dex::{
+        self,
+        critbit::{LeafNode, Slab, SlabView},
+        declare_check_assert_macros,
+        error::SourceFileId,
+        instruction::SelfTradeBehavior,
+        matching::{OrderBookState, OrderType, Side},
+        state::Market,
+    },
+    CancelOrderV2, CloseOpenOrders, InitOpenOrders, NewOrderV3, SettleFunds,
 };
+use arrayref::{array_ref, array_refs};
+use safe_transmute::to_bytes::transmute_to_bytes;
+use std::{convert::identity, num::NonZeroU64};
+
+use crate::{constants::RESTING_ORDER_CLIENT_ID, dexes::open_book_dex, errors::ErrorCode};
 
 declare_check_assert_macros!(SourceFileId::State);
 
@@ -149,3 +160,224 @@ impl FeeTier {
             .unwrap()
     }
 }
+
+/// The length of an OpenOrders account's head padding and balances, up to and including the
+/// free slot bits
+const OPEN_ORDERS_BALANCES_LEN: usize = 5 + 8 + 32 + 32 + 8 + 8 + 8 + 8 + 16;
+
+/// The balances of a strategy's OpenOrders account
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpenOrdersBalances {
+    pub native_coin_free: u64,
+    pub native_coin_total: u64,
+    pub native_pc_free: u64,
+    pub native_pc_total: u64,
+    pub free_slot_bits: u128,
+}
+
+impl OpenOrdersBalances {
+    /// Read the balances from the raw OpenOrders account data
+    pub fn load(data: &[u8]) -> Result<Self> {
+        if data.len() < OPEN_ORDERS_BALANCES_LEN {
+            return Err(error!(ErrorCode::WrongOpenOrdersKey));
+        }
+        let data = array_ref![data, 0, OPEN_ORDERS_BALANCES_LEN];
+        let (
+            _head,
+            _account_flags,
+            _market,
+            _owner,
+            native_coin_free,
+            native_coin_total,
+            native_pc_free,
+            native_pc_total,
+            free_slot_bits,
+        ) = array_refs![data, 5, 8, 32, 32, 8, 8, 8, 8, 16];
+        Ok(OpenOrdersBalances {
+            native_coin_free: u64::from_le_bytes(*native_coin_free),
+            native_coin_total: u64::from_le_bytes(*native_coin_total),
+            native_pc_free: u64::from_le_bytes(*native_pc_free),
+            native_pc_total: u64::from_le_bytes(*native_pc_total),
+            free_slot_bits: u128::from_le_bytes(*free_slot_bits),
+        })
+    }
+
+    /// Returns true while any order occupies a slot, including cancelled orders whose events
+    /// have not been consumed
+    pub fn has_orders(&self) -> bool {
+        self.free_slot_bits != u128::MAX
+    }
+}
+
+/// The parts of a market a strategy needs to place orders on it
+pub struct MarketInfo {
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub coin_lot_size: u64,
+    pub pc_lot_size: u64,
+}
+
+impl MarketInfo {
+    /// Load the market's mints and lot sizes, releasing the market account before returning
+    pub fn load(serum_market: &AccountInfo, dex_program_id: &Pubkey) -> Result<Self> {
+        let market = Market::load(serum_market, dex_program_id)
+            .map_err(|_| ErrorCode::FailedToLoadOpenBookDexMarket)?;
+        Ok(MarketInfo {
+            coin_mint: Pubkey::new(transmute_to_bytes(&identity(market.coin_mint))),
+            pc_mint: Pubkey::new(transmute_to_bytes(&identity(market.pc_mint))),
+            coin_lot_size: market.coin_lot_size,
+            pc_lot_size: market.pc_lot_size,
+        })
+    }
+}
+
+/// Initialize an OpenOrders account through the OpenBook program
+pub fn init_open_orders<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, InitOpenOrders<'info>>,
+) -> Result<()> {
+    let ix = serum_dex::instruction::init_open_orders(
+        &open_book_dex::ID,
+        ctx.accounts.open_orders.key,
+        ctx.accounts.authority.key,
+        ctx.accounts.market.key,
+        None,
+    )
+    .map_err(ProgramError::from)?;
+    invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Post a post-only limit order tagged with the resting order's client id
+pub fn place_post_only_order<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, NewOrderV3<'info>>,
+    side: Side,
+    limit_price: NonZeroU64,
+    max_coin_qty: NonZeroU64,
+    max_native_pc_qty_including_fees: NonZeroU64,
+) -> Result<()> {
+    let ix = serum_dex::instruction::new_order(
+        ctx.accounts.market.key,
+        ctx.accounts.open_orders.key,
+        ctx.accounts.request_queue.key,
+        ctx.accounts.event_queue.key,
+        ctx.accounts.market_bids.key,
+        ctx.accounts.market_asks.key,
+        ctx.accounts.order_payer_token_account.key,
+        ctx.accounts.open_orders_authority.key,
+        ctx.accounts.coin_vault.key,
+        ctx.accounts.pc_vault.key,
+        ctx.accounts.token_program.key,
+        ctx.accounts.rent.key,
+        None,
+        &open_book_dex::ID,
+        side,
+        limit_price,
+        max_coin_qty,
+        OrderType::PostOnly,
+        RESTING_ORDER_CLIENT_ID,
+        SelfTradeBehavior::AbortTransaction,
+        u16::MAX,
+        max_native_pc_qty_including_fees,
+    )
+    .map_err(ProgramError::from)?;
+    invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Cancel the order tagged with the resting order's client id
+pub fn cancel_resting_order<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, CancelOrderV2<'info>>,
+) -> Result<()> {
+    let ix = serum_dex::instruction::cancel_order_by_client_order_id(
+        &open_book_dex::ID,
+        ctx.accounts.market.key,
+        ctx.accounts.market_bids.key,
+        ctx.accounts.market_asks.key,
+        ctx.accounts.open_orders.key,
+        ctx.accounts.open_orders_authority.key,
+        ctx.accounts.event_queue.key,
+        RESTING_ORDER_CLIENT_ID,
+    )
+    .map_err(ProgramError::from)?;
+    invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Settle the free balances of an OpenOrders account into the given wallets
+pub fn settle_funds<'info>(ctx: CpiContext<'_, '_, '_, 'info, SettleFunds<'info>>) -> Result<()> {
+    let ix = serum_dex::instruction::settle_funds(
+        &open_book_dex::ID,
+        ctx.accounts.market.key,
+        ctx.accounts.token_program.key,
+        ctx.accounts.open_orders.key,
+        ctx.accounts.open_orders_authority.key,
+        ctx.accounts.coin_vault.key,
+        ctx.accounts.coin_wallet.key,
+        ctx.accounts.pc_vault.key,
+        ctx.accounts.pc_wallet.key,
+        None,
+        ctx.accounts.vault_signer.key,
+    )
+    .map_err(ProgramError::from)?;
+    invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Close an empty OpenOrders account, returning its rent to the destination
+pub fn close_open_orders<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, CloseOpenOrders<'info>>,
+) -> Result<()> {
+    let ix = serum_dex::instruction::close_open_orders(
+        &open_book_dex::ID,
+        ctx.accounts.open_orders.key,
+        ctx.accounts.authority.key,
+        ctx.accounts.destination.key,
+        ctx.accounts.market.key,
+    )
+    .map_err(ProgramError::from)?;
+    invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{OpenOrdersBalances, OPEN_ORDERS_BALANCES_LEN};
+
+    #[test]
+    fn test_load_open_orders_balances() {
+        let mut data = vec![0_u8; OPEN_ORDERS_BALANCES_LEN + 16];
+        data[77..85].copy_from_slice(&1_u64.to_le_bytes());
+        data[85..93].copy_from_slice(&2_u64.to_le_bytes());
+        data[93..101].copy_from_slice(&3_u64.to_le_bytes());
+        data[101..109].copy_from_slice(&4_u64.to_le_bytes());
+        data[109..125].copy_from_slice(&(u128::MAX - 1).to_le_bytes());
+
+        let balances = OpenOrdersBalances::load(&data).unwrap();
+        assert_eq!(balances.native_coin_free, 1);
+        assert_eq!(balances.native_coin_total, 2);
+        assert_eq!(balances.native_pc_free, 3);
+        assert_eq!(balances.native_pc_total, 4);
+        assert!(balances.has_orders());
+        assert!(OpenOrdersBalances::load(&data[..100]).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert;
+
+use crate::{constants::BPS_DENOMINATOR, dexes::math::mul_div_u64, errors::ErrorCode};
+
+/// The maximum number of outputs a basket can sell its collateral into
+pub const MAX_BASKET_OUTPUTS: usize = 4;
+
+/// Splits a BoundedStrategyV2's collateral across several deposit accounts, each with its own
+/// output mint, weight and price bound
+#[account]
+pub struct Basket {
+    /// The BoundedStrategyV2 whose collateral the basket allocates
+    pub strategy: Pubkey,
+    /// The basket's outputs. Unused outputs have a default deposit address.
+    pub outputs: [BasketOutput; MAX_BASKET_OUTPUTS],
+    /// The bump for the basket's derived address
+    pub bump: u8,
+}
+
+impl Basket {
+    pub const LEN: usize = 8 + std::mem::size_of::<Basket>() + 64;
+
+    /// Returns the index of the output depositing into the given account
+    pub fn output_index(&self, deposit_address: &Pubkey) -> Result<usize> {
+        self.outputs
+            .iter()
+            .position(|output| output.is_set() && output.deposit_address == *deposit_address)
+            .ok_or_else(|| error!(ErrorCode::BasketOutputNotFound))
+    }
+
+    /// Allocate the collateral across the outputs by weight. Rounding dust goes to the first
+    /// output.
+    pub fn allocate(&mut self, collateral_amount: u64) {
+        let mut allocated: u64 = 0;
+        for output in self.outputs.iter_mut().filter(|output| output.is_set()) {
+            output.allocated_input =
+                mul_div_u64(collateral_amount, output.weight_bps.into(), BPS_DENOMINATOR).unwrap();
+            allocated += output.allocated_input;
+        }
+        self.outputs[0].allocated_input += collateral_amount - allocated;
+    }
+
+    /// Returns the collateral available to a trade into the output, limited to what is left of
+    /// its allocation
+    pub fn available_input(&self, index: usize, collateral_balance: u64) -> u64 {
+        std::cmp::min(collateral_balance, self.outputs[index].remaining_input())
+    }
+
+    /// Record a fill of `input` collateral for `output` deposited tokens against the output
+    pub fn record_fill(&mut self, index: usize, input: u64, output: u64) {
+        let basket_output = &mut self.outputs[index];
+        basket_output.filled_input = basket_output.filled_input.saturating_add(input);
+        basket_output.filled_output = basket_output.filled_output.saturating_add(output);
+    }
+}
+const_assert!(Basket::LEN == 432);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct BasketOutput {
+    /// The account the output's proceeds are deposited into. Its mint is the output mint.
+    pub deposit_address: Pubkey,
+    /// The output's share of the collateral, in basis points
+    pub weight_bps: u16,
+    /// The output's price, see `BoundedStrategyV2::bounded_price_numerator`
+    pub bounded_price_numerator: u64,
+    pub bounded_price_denominator: u64,
+    /// The amount of collateral allocated to the output
+    pub allocated_input: u64,
+    /// The amount of collateral sold into the output
+    pub filled_input: u64,
+    /// The amount of output deposited
+    pub filled_output: u64,
+}
+
+impl BasketOutput {
+    pub fn is_set(&self) -> bool {
+        self.deposit_address != Pubkey::default()
+    }
+
+    /// Returns the amount of the output's allocation that has yet to be sold
+    pub fn remaining_input(&self) -> u64 {
+        self.allocated_input.saturating_sub(self.filled_input)
+    }
+}
+
+/// The parameters of a basket output, paired with its deposit account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct BasketOutputParams {
+    pub weight_bps: u16,
+    pub bounded_price_numerator: u64,
+    pub bounded_price_denominator: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mock_output(weight_bps: u16) -> BasketOutput {
+        BasketOutput {
+            deposit_address: Pubkey::new_unique(),
+            weight_bps,
+            bounded_price_numerator: 1,
+            bounded_price_denominator: 1,
+            ..BasketOutput::default()
+        }
+    }
+
+    #[test]
+    /// Test that the collateral is allocated by weight and each output fills only its allocation
+    fn test_allocate_and_fill() {
+        let mut basket = Basket {
+            strategy: Pubkey::default(),
+            outputs: [
+                mock_output(5_000),
+                mock_output(3_000),
+                mock_output(2_000),
+                BasketOutput::default(),
+            ],
+            bump: 0,
+        };
+        basket.allocate(1_000_003);
+        assert_eq!(basket.outputs[0].allocated_input, 500_003);
+        assert_eq!(basket.outputs[1].allocated_input, 300_000);
+        assert_eq!(basket.outputs[2].allocated_input, 200_000);
+        assert_eq!(basket.outputs[3].allocated_input, 0);
+
+        let deposit_address = basket.outputs[1].deposit_address;
+        let index = basket.output_index(&deposit_address).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(basket.available_input(index, 1_000_003), 300_000);
+        basket.record_fill(index, 120_000, 60_000);
+        assert_eq!(basket.available_input(index, 1_000_003), 180_000);
+        assert_eq!(basket.available_input(index, 100_000), 100_000);
+        assert_eq!(basket.outputs[index].filled_output, 60_000);
+
+        assert!(basket.output_index(&Pubkey::default()).is_err());
+    }
+}
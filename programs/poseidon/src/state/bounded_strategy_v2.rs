@@ -1,7 +1,13 @@
 use anchor_lang::prelude::*;
 use static_assertions::const_assert;
 
-pub const MAX_ACCOUNTS: usize = 32;
+use crate::errors::ErrorCode;
+
+/// The maximum number of remaining accounts a trade route can span, matching the number of
+/// accounts a transaction can lock
+pub const MAX_ACCOUNTS: usize = 64;
+/// The maximum number of price tiers a laddered strategy can hold
+pub const MAX_PRICE_TIERS: usize = 4;
 
 #[account]
 pub struct BoundedStrategyV2 {
@@ -26,9 +32,462 @@ pub struct BoundedStrategyV2 {
     pub bounded_price_denominator: u64,
     /// The bump for the strategy's derived address
     pub bump: u8,
+    /// Optional ladder of prices, filled in order. When any tier is set the active tier's price
+    /// replaces the strategy's bounded price and only the tier's remaining amount may trade.
+    ///
+    /// ### Example:
+    /// Sell 25% at $2, 25% at $3 and 50% at $5 with three tiers of the respective sizes.
+    pub price_tiers: [PriceTier; MAX_PRICE_TIERS],
+    /// The fee, in basis points of the trade output, paid to whoever executes a trade
+    pub executor_fee_bps: u16,
+    /// The share of the output above the bound, in basis points, paid to the executor. The rest
+    /// of the price improvement goes to the deposit address.
+    pub surplus_share_bps: u16,
+    /// The smallest amount of collateral a trade may sell, unless it sells all that is left.
+    /// 0 for no minimum.
+    pub min_fill_input: u64,
+    /// The largest amount of collateral a single trade may sell. 0 for no maximum.
+    pub max_fill_input: u64,
+    /// The number of slots that must pass between trades, giving order books time to refill
+    pub min_slots_between_trades: u64,
+    /// The slot of the most recent trade
+    pub last_trade_slot: u64,
+    /// Running statistics of the strategy's fills
+    pub fill_stats: FillStats,
+    /// The strategy's one extension and the companion account holding its state
+    pub extension: StrategyExtension,
+    /// The collateral committed to the resting order that has not yet been settled as filled
+    pub resting_input: u64,
+    /// The strategy whose proceeds fund this strategy's collateral. The default key when the
+    /// strategy is not chained behind another.
+    pub upstream: Pubkey,
+    /// True until the upstream strategy's first fill, or a deposit, funds the strategy. The
+    /// strategy cannot trade before then.
+    pub awaiting_upstream: bool,
+    /// The date before which the strategy cannot trade. Strategies created before the field was
+    /// added read it from zeroed padding and can trade immediately.
+    pub start_date: i64,
+}
+
+/// The `start_date` seed of a strategy's address. It is empty for strategies that can trade
+/// immediately, so their addresses match the ones derived before `start_date` existed.
+pub fn start_date_seed(start_date: i64) -> Vec<u8> {
+    if start_date == 0 {
+        Vec::new()
+    } else {
+        start_date.to_le_bytes().to_vec()
+    }
 }
 
 impl BoundedStrategyV2 {
-    pub const LEN: usize = 8 + std::mem::size_of::<BoundedStrategyV2>() + 320;
+    pub const LEN: usize = 8 + std::mem::size_of::<BoundedStrategyV2>() + 40;
+
+    /// Returns true when the strategy has any extension. A strategy holds at most one.
+    pub fn has_extension(&self) -> bool {
+        self.extension != StrategyExtension::None
+    }
+
+    /// Returns true when the strategy has an OpenOrders account for resting orders
+    pub fn has_open_orders(&self) -> bool {
+        self.open_orders_key().is_some()
+    }
+
+    /// Returns the strategy's OpenBook OpenOrders account for resting orders
+    pub fn open_orders_key(&self) -> Option<Pubkey> {
+        match self.extension {
+            StrategyExtension::RestingOrders { open_orders } => Some(open_orders),
+            _ => None,
+        }
+    }
+
+    /// Returns the strategy's Basket account when its collateral is sold into several outputs
+    pub fn basket_key(&self) -> Option<Pubkey> {
+        match self.extension {
+            StrategyExtension::Basket { basket } => Some(basket),
+            _ => None,
+        }
+    }
+
+    /// Returns the strategy's DepositSplit account when its proceeds are split between several
+    /// deposit accounts
+    pub fn deposit_split_key(&self) -> Option<Pubkey> {
+        match self.extension {
+            StrategyExtension::DepositSplit { deposit_split } => Some(deposit_split),
+            _ => None,
+        }
+    }
+
+    /// Returns the OcoPair the strategy shares its collateral account with
+    pub fn oco_pair_key(&self) -> Option<Pubkey> {
+        match self.extension {
+            StrategyExtension::OcoPair { oco_pair } => Some(oco_pair),
+            _ => None,
+        }
+    }
+
+    /// Returns the downstream strategy whose collateral account the strategy deposits into
+    pub fn downstream_key(&self) -> Option<Pubkey> {
+        match self.extension {
+            StrategyExtension::Downstream { strategy } => Some(strategy),
+            _ => None,
+        }
+    }
+
+    /// Returns true when trades pay the executor a fee or a share of the surplus
+    pub fn pays_executor(&self) -> bool {
+        self.executor_fee_bps > 0 || self.surplus_share_bps > 0
+    }
+
+    /// Returns true when the strategy is chained behind an upstream strategy
+    pub fn has_upstream(&self) -> bool {
+        self.upstream != Pubkey::default()
+    }
+
+    /// Returns true when the strategy's proceeds must be accounted for by `bounded_trade_v2`,
+    /// rather than deposited by fills, matches or resting orders
+    pub fn requires_route(&self) -> bool {
+        self.has_extension() && !self.has_open_orders()
+    }
+
+    /// Returns true when the strategy was initialized with a ladder of price tiers
+    pub fn is_laddered(&self) -> bool {
+        self.price_tiers.iter().any(|tier| tier.is_set())
+    }
+
+    /// Returns the index of the first tier that still has size left to fill
+    pub fn active_tier(&self) -> Option<usize> {
+        self.price_tiers
+            .iter()
+            .position(|tier| tier.is_set() && tier.remaining_amount > 0)
+    }
+
+    /// Returns the tier a trade fills, or None when the strategy is not laddered. Errors when
+    /// every tier of a laddered strategy has been filled.
+    pub fn trading_tier(&self) -> Result<Option<usize>> {
+        if self.is_laddered() {
+            Ok(Some(self.active_tier().ok_or(ErrorCode::PriceTiersFilled)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Validate that the strategy is within its trading window and off cooldown
+    pub fn validate_can_trade(&self, clock: &Clock) -> Result<()> {
+        if self.start_date > clock.unix_timestamp {
+            return Err(ErrorCode::StartDateHasNotPassed.into());
+        }
+        if self.awaiting_upstream {
+            return Err(ErrorCode::UpstreamHasNotFilled.into());
+        }
+        if self.reclaim_date < clock.unix_timestamp {
+            return Err(ErrorCode::ReclaimDateHasPassed.into());
+        }
+        if !self.cooldown_has_passed(clock.slot) {
+            return Err(ErrorCode::TradeCooldownHasNotPassed.into());
+        }
+        Ok(())
+    }
+
+    /// Record a fill of `input` collateral for `output` deposited tokens against the tier it
+    /// traded, the cooldown and the fill statistics
+    pub fn record_fill(&mut self, tier: Option<usize>, input: u64, output: u64, clock: &Clock) {
+        if let Some(index) = tier {
+            let tier = &mut self.price_tiers[index];
+            tier.remaining_amount = tier.remaining_amount.saturating_sub(input);
+        }
+        self.last_trade_slot = clock.slot;
+        self.fill_stats.record(input, output, clock.unix_timestamp);
+    }
+
+    /// Returns the bounded price numerator and denominator to trade at, using the price of the
+    /// given tier for laddered strategies
+    pub fn bounded_price(&self, tier: Option<usize>) -> (u64, u64) {
+        match tier {
+            Some(index) => (
+                self.price_tiers[index].bounded_price_numerator,
+                self.price_tiers[index].bounded_price_denominator,
+            ),
+            None => (self.bounded_price_numerator, self.bounded_price_denominator),
+        }
+    }
+
+    /// Returns the collateral available to a trade, limited to the size left in the given tier
+    pub fn available_input(&self, collateral_balance: u64, tier: Option<usize>) -> u64 {
+        match tier {
+            Some(index) => {
+                std::cmp::min(collateral_balance, self.price_tiers[index].remaining_amount)
+            }
+            None => collateral_balance,
+        }
+    }
+
+    /// Returns true when enough slots have passed since the last trade
+    pub fn cooldown_has_passed(&self, slot: u64) -> bool {
+        let next_trade_slot = self
+            .last_trade_slot
+            .saturating_add(self.min_slots_between_trades);
+        self.last_trade_slot == 0 || slot >= next_trade_slot
+    }
+
+    /// Limit the input available to a trade to the strategy's maximum fill
+    pub fn clamp_fill_input(&self, input: u64) -> u64 {
+        if self.max_fill_input > 0 {
+            std::cmp::min(input, self.max_fill_input)
+        } else {
+            input
+        }
+    }
+
+    /// Validate a trade's input against the fill limits. Fills below the minimum are only
+    /// allowed when they trade all of the `available_input`.
+    pub fn validate_fill_input(&self, input: u64, available_input: u64) -> Result<()> {
+        if self.max_fill_input > 0 && input > self.max_fill_input {
+            return Err(error!(ErrorCode::FillAboveMaximum));
+        }
+        if input < self.min_fill_input && input < available_input {
+            return Err(error!(ErrorCode::FillBelowMinimum));
+        }
+        Ok(())
+    }
 }
 const_assert!(BoundedStrategyV2::LEN == 488);
+
+/// The extension a BoundedStrategyV2 carries. Each keeps its state in a companion account, which
+/// leads the remaining accounts of the instructions that need it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StrategyExtension {
+    #[default]
+    None,
+    /// Rests post-only orders from an OpenBook OpenOrders account
+    RestingOrders {
+        open_orders: Pubkey,
+    },
+    /// Sells the collateral into the outputs of a Basket
+    Basket {
+        basket: Pubkey,
+    },
+    /// Splits the proceeds between the recipients of a DepositSplit
+    DepositSplit {
+        deposit_split: Pubkey,
+    },
+    /// Shares the collateral account with the sibling strategy of an OcoPair
+    OcoPair {
+        oco_pair: Pubkey,
+    },
+    /// Deposits into the collateral account of a downstream strategy
+    Downstream {
+        strategy: Pubkey,
+    },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct PriceTier {
+    /// The tier's price, see `BoundedStrategyV2::bounded_price_numerator`
+    pub bounded_price_numerator: u64,
+    pub bounded_price_denominator: u64,
+    /// The amount of collateral that has yet to be traded at this tier's price
+    pub remaining_amount: u64,
+}
+
+impl PriceTier {
+    pub fn is_set(&self) -> bool {
+        self.bounded_price_denominator != 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct FillStats {
+    /// The total amount of collateral sold
+    pub total_input: u64,
+    /// The total amount of output deposited
+    pub total_output: u64,
+    pub trade_count: u64,
+    pub first_trade_timestamp: i64,
+    pub last_trade_timestamp: i64,
+    /// The input and output of the fill with the most output per input
+    pub best_price: ExecutionPrice,
+    /// The input and output of the fill with the least output per input
+    pub worst_price: ExecutionPrice,
+}
+
+impl FillStats {
+    /// Accumulate a fill of `input` collateral for `output` deposited tokens
+    pub fn record(&mut self, input: u64, output: u64, timestamp: i64) {
+        let price = ExecutionPrice { input, output };
+        if self.trade_count == 0 {
+            self.first_trade_timestamp = timestamp;
+            self.best_price = price;
+            self.worst_price = price;
+        } else {
+            if price.is_better_than(&self.best_price) {
+                self.best_price = price;
+            }
+            if self.worst_price.is_better_than(&price) {
+                self.worst_price = price;
+            }
+        }
+        self.total_input = self.total_input.saturating_add(input);
+        self.total_output = self.total_output.saturating_add(output);
+        self.trade_count += 1;
+        self.last_trade_timestamp = timestamp;
+    }
+}
+
+/// An execution price expressed as the output received for an input
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionPrice {
+    pub input: u64,
+    pub output: u64,
+}
+
+impl ExecutionPrice {
+    /// Returns true when this price receives more output per input than `other`
+    pub fn is_better_than(&self, other: &ExecutionPrice) -> bool {
+        u128::from(self.output) * u128::from(other.input)
+            > u128::from(other.output) * u128::from(self.input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{start_date_seed, BoundedStrategyV2, ExecutionPrice, FillStats, StrategyExtension};
+    use crate::{constants::BOUNDED_STRATEGY_SEED, strategy_signer_seeds};
+    use anchor_lang::{prelude::*, Discriminator};
+
+    /// The account layout strategies were created with before the V2 extensions
+    #[derive(AnchorSerialize)]
+    struct LegacyBoundedStrategyV2 {
+        collateral_mint: Pubkey,
+        collateral_account: Pubkey,
+        reclaim_date: i64,
+        reclaim_address: Pubkey,
+        deposit_address: Pubkey,
+        bounded_price_numerator: u64,
+        bounded_price_denominator: u64,
+        bump: u8,
+    }
+
+    #[test]
+    /// Test that a strategy stored in the original layout still deserializes and signs with its
+    /// stored bump, so it can trade and be reclaimed
+    fn test_legacy_layout_signs() {
+        let collateral_mint = Pubkey::new_unique();
+        let bounded_price_numerator: u64 = 1_000_000_000;
+        let bounded_price_denominator: u64 = 20_000_000;
+        let reclaim_date: i64 = 1_700_000_000;
+        let (address, bump) = Pubkey::find_program_address(
+            &[
+                collateral_mint.as_ref(),
+                &bounded_price_numerator.to_le_bytes(),
+                &bounded_price_denominator.to_le_bytes(),
+                &reclaim_date.to_le_bytes(),
+                BOUNDED_STRATEGY_SEED.as_bytes(),
+            ],
+            &crate::ID,
+        );
+        let legacy = LegacyBoundedStrategyV2 {
+            collateral_mint,
+            collateral_account: Pubkey::new_unique(),
+            reclaim_date,
+            reclaim_address: Pubkey::new_unique(),
+            deposit_address: Pubkey::new_unique(),
+            bounded_price_numerator,
+            bounded_price_denominator,
+            bump,
+        };
+        let mut data = BoundedStrategyV2::discriminator().to_vec();
+        data.extend(legacy.try_to_vec().unwrap());
+        data.resize(BoundedStrategyV2::LEN, 0);
+
+        let strategy = BoundedStrategyV2::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(strategy.collateral_account, legacy.collateral_account);
+        assert_eq!(strategy.reclaim_date, reclaim_date);
+        assert_eq!(strategy.reclaim_address, legacy.reclaim_address);
+        assert_eq!(strategy.deposit_address, legacy.deposit_address);
+        assert_eq!(strategy.bump, bump);
+        assert_eq!(strategy.start_date, 0);
+        assert!(!strategy.is_laddered());
+        assert!(!strategy.requires_route());
+        assert_eq!(strategy.extension, StrategyExtension::None);
+        assert!(!strategy.has_upstream());
+
+        let seeds: &[&[u8]] = strategy_signer_seeds!(strategy);
+        assert_eq!(
+            Pubkey::create_program_address(seeds, &crate::ID).unwrap(),
+            address
+        );
+    }
+
+    #[test]
+    /// Test that strategies differing only in their start date get different addresses, and
+    /// that a scheduled strategy signs with its start date
+    fn test_start_date_seed() {
+        let collateral_mint = Pubkey::new_unique();
+        let bounded_price_numerator: u64 = 1_000_000_000;
+        let bounded_price_denominator: u64 = 20_000_000;
+        let start_date: i64 = 1_690_000_000;
+        let reclaim_date: i64 = 1_700_000_000;
+        let derive = |start_date: i64| {
+            Pubkey::find_program_address(
+                &[
+                    collateral_mint.as_ref(),
+                    &bounded_price_numerator.to_le_bytes(),
+                    &bounded_price_denominator.to_le_bytes(),
+                    &start_date_seed(start_date),
+                    &reclaim_date.to_le_bytes(),
+                    BOUNDED_STRATEGY_SEED.as_bytes(),
+                ],
+                &crate::ID,
+            )
+        };
+        let (immediate_address, _) = derive(0);
+        let (address, bump) = derive(start_date);
+        assert_ne!(address, immediate_address);
+
+        let mut data = BoundedStrategyV2::discriminator().to_vec();
+        data.resize(BoundedStrategyV2::LEN, 0);
+        let mut strategy = BoundedStrategyV2::try_deserialize(&mut data.as_slice()).unwrap();
+        strategy.collateral_mint = collateral_mint;
+        strategy.bounded_price_numerator = bounded_price_numerator;
+        strategy.bounded_price_denominator = bounded_price_denominator;
+        strategy.start_date = start_date;
+        strategy.reclaim_date = reclaim_date;
+        strategy.bump = bump;
+
+        let seeds: &[&[u8]] = strategy_signer_seeds!(strategy);
+        assert_eq!(
+            Pubkey::create_program_address(seeds, &crate::ID).unwrap(),
+            address
+        );
+    }
+
+    #[test]
+    fn test_fill_stats_record() {
+        let mut stats = FillStats::default();
+        stats.record(1_000, 92_000, 10);
+        assert_eq!(stats.trade_count, 1);
+        assert_eq!(stats.best_price, stats.worst_price);
+
+        stats.record(2_000, 186_000, 20);
+        stats.record(500, 45_000, 30);
+        assert_eq!(stats.total_input, 3_500);
+        assert_eq!(stats.total_output, 323_000);
+        assert_eq!(stats.trade_count, 3);
+        assert_eq!(stats.first_trade_timestamp, 10);
+        assert_eq!(stats.last_trade_timestamp, 30);
+        assert_eq!(
+            stats.best_price,
+            ExecutionPrice {
+                input: 2_000,
+                output: 186_000
+            }
+        );
+        assert_eq!(
+            stats.worst_price,
+            ExecutionPrice {
+                input: 500,
+                output: 45_000
+            }
+        );
+    }
+}
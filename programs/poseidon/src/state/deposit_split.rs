@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert;
+
+use crate::{constants::BPS_DENOMINATOR, dexes::math::mul_div_u64};
+
+/// The maximum number of accounts a strategy's proceeds can be split between
+pub const MAX_DEPOSIT_RECIPIENTS: usize = 4;
+
+/// Splits a BoundedStrategyV2's proceeds between several deposit accounts of the same mint. The
+/// strategy deposits into its own proceeds account and pays the recipients out of it after
+/// each trade.
+#[account]
+pub struct DepositSplit {
+    /// The BoundedStrategyV2 whose proceeds are split
+    pub strategy: Pubkey,
+    /// The accounts the proceeds are paid to. Unused recipients have a default address.
+    pub recipients: [DepositRecipient; MAX_DEPOSIT_RECIPIENTS],
+    /// The bump for the split's derived address
+    pub bump: u8,
+}
+
+impl DepositSplit {
+    pub const LEN: usize = 8 + std::mem::size_of::<DepositSplit>() + 64;
+
+    /// Returns the number of recipients
+    pub fn recipient_count(&self) -> usize {
+        self.recipients
+            .iter()
+            .filter(|recipient| recipient.is_set())
+            .count()
+    }
+
+    /// Returns each recipient's share of the amount by weight. Rounding dust goes to the first
+    /// recipient.
+    pub fn payouts(&self, amount: u64) -> Vec<u64> {
+        let mut payouts: Vec<u64> = self
+            .recipients
+            .iter()
+            .filter(|recipient| recipient.is_set())
+            .map(|recipient| {
+                mul_div_u64(amount, recipient.weight_bps.into(), BPS_DENOMINATOR).unwrap()
+            })
+            .collect();
+        let paid: u64 = payouts.iter().sum();
+        if let Some(first) = payouts.first_mut() {
+            *first += amount - paid;
+        }
+        payouts
+    }
+}
+const_assert!(DepositSplit::LEN == 242);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct DepositRecipient {
+    /// The token account the recipient's share is transferred to
+    pub address: Pubkey,
+    /// The recipient's share of the proceeds, in basis points
+    pub weight_bps: u16,
+}
+
+impl DepositRecipient {
+    pub fn is_set(&self) -> bool {
+        self.address != Pubkey::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    /// Test that the proceeds are split by weight with the dust paid to the first recipient
+    fn test_payouts() {
+        let recipient = |weight_bps| DepositRecipient {
+            address: Pubkey::new_unique(),
+            weight_bps,
+        };
+        let split = DepositSplit {
+            strategy: Pubkey::default(),
+            recipients: [
+                recipient(8_000),
+                recipient(2_000),
+                DepositRecipient::default(),
+                DepositRecipient::default(),
+            ],
+            bump: 0,
+        };
+        assert_eq!(split.recipient_count(), 2);
+        assert_eq!(split.payouts(1_000), vec![800, 200]);
+        assert_eq!(split.payouts(1_003), vec![803, 200]);
+        assert_eq!(split.payouts(0), vec![0, 0]);
+    }
+}
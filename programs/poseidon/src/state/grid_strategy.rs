@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert;
+
+use crate::{dexes::math::mul_div_u64, errors::ErrorCode};
+
+/// The maximum number of price levels in a grid. Each pair of neighbouring levels forms a range
+/// whose fill state is one bit of `sold_ranges`.
+pub const MAX_GRID_LEVELS: u8 = 32;
+
+#[account]
+pub struct GridStrategy {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// The token account holding the base the grid sells
+    pub base_account: Pubkey,
+    /// The token account holding the quote the grid buys back with
+    pub quote_account: Pubkey,
+    /// The date before which the grid cannot trade
+    pub start_date: i64,
+    /// The date at which the DAO's assets can be reclaimed
+    pub reclaim_date: i64,
+    /// The addresses the base and quote are transferred to when being reclaimed
+    pub base_reclaim_address: Pubkey,
+    pub quote_reclaim_address: Pubkey,
+    /// The price band, in quote per `price_denominator` of base. Levels are spread evenly from
+    /// the lower to the upper price.
+    ///
+    /// ### Example:
+    /// A SOL/USDC band from $80 to $120 has a lower price of 80_000_000, an upper price of
+    /// 120_000_000 and a price denominator of 1_000_000_000.
+    pub lower_price: u64,
+    pub upper_price: u64,
+    pub price_denominator: u64,
+    /// The number of price levels, including both ends of the band
+    pub grid_levels: u8,
+    /// The amount of base each range sells and buys back
+    pub level_size: u64,
+    /// One bit per range, set when the range's base has been sold at its upper level and is
+    /// waiting to be bought back at its lower level
+    pub sold_ranges: u32,
+    /// The bump for the grid's derived address
+    pub bump: u8,
+}
+
+impl GridStrategy {
+    pub const LEN: usize = 8 + std::mem::size_of::<GridStrategy>() + 64;
+
+    /// Returns the number of ranges between the grid's levels
+    pub fn range_count(&self) -> u8 {
+        self.grid_levels.saturating_sub(1)
+    }
+
+    /// Returns the price of the level, in quote per `price_denominator` of base
+    pub fn level_price(&self, level: u8) -> u64 {
+        let step = mul_div_u64(
+            self.upper_price - self.lower_price,
+            level.into(),
+            u64::from(self.range_count()),
+        )
+        .unwrap();
+        self.lower_price + step
+    }
+
+    /// Returns true when the range's base has been sold
+    pub fn is_sold(&self, range: u8) -> bool {
+        self.sold_ranges & (1 << range) != 0
+    }
+
+    /// Returns the trade that fills the range. An unsold range sells `level_size` base for quote
+    /// at its upper level, a sold range buys the base back with quote at its lower level. The
+    /// result is the input amount and the bounded price numerator and denominator.
+    pub fn range_trade(&self, range: u8) -> Result<GridRangeTrade> {
+        if range >= self.range_count() {
+            return Err(error!(ErrorCode::InvalidGridLevel));
+        }
+        if self.is_sold(range) {
+            let price = self.level_price(range);
+            Ok(GridRangeTrade {
+                sells_base: false,
+                input_amount: mul_div_u64(self.level_size, price, self.price_denominator)
+                    .ok_or(ErrorCode::InvalidGridLevel)?,
+                bounded_price_numerator: price,
+                bounded_price_denominator: self.price_denominator,
+            })
+        } else {
+            Ok(GridRangeTrade {
+                sells_base: true,
+                input_amount: self.level_size,
+                bounded_price_numerator: self.price_denominator,
+                bounded_price_denominator: self.level_price(range + 1),
+            })
+        }
+    }
+
+    /// Flip the range between sold and bought back once its trade has used its full input
+    pub fn record_range_fill(&mut self, range: u8, input_used: u64) -> Result<()> {
+        if input_used != self.range_trade(range)?.input_amount {
+            return Err(error!(ErrorCode::GridRangePartiallyFilled));
+        }
+        self.sold_ranges ^= 1 << range;
+        Ok(())
+    }
+
+    /// Validate that the grid is within its trading window
+    pub fn validate_can_trade(&self, clock: &Clock) -> Result<()> {
+        if self.start_date > clock.unix_timestamp {
+            return Err(ErrorCode::StartDateHasNotPassed.into());
+        }
+        if self.reclaim_date < clock.unix_timestamp {
+            return Err(ErrorCode::ReclaimDateHasPassed.into());
+        }
+        Ok(())
+    }
+}
+const_assert!(GridStrategy::LEN == 320);
+
+/// The input and bound of a single grid trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridRangeTrade {
+    /// True when the trade sells base for quote
+    pub sells_base: bool,
+    pub input_amount: u64,
+    pub bounded_price_numerator: u64,
+    pub bounded_price_denominator: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mock_grid(sold_ranges: u32) -> GridStrategy {
+        GridStrategy {
+            base_mint: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            base_account: Pubkey::default(),
+            quote_account: Pubkey::default(),
+            start_date: 0,
+            reclaim_date: 0,
+            base_reclaim_address: Pubkey::default(),
+            quote_reclaim_address: Pubkey::default(),
+            // $80 to $120 per SOL in 5 levels, $10 apart
+            lower_price: 80_000_000,
+            upper_price: 120_000_000,
+            price_denominator: 1_000_000_000,
+            grid_levels: 5,
+            level_size: 2_000_000_000,
+            sold_ranges,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_level_prices() {
+        let grid = mock_grid(0);
+        assert_eq!(grid.range_count(), 4);
+        assert_eq!(grid.level_price(0), 80_000_000);
+        assert_eq!(grid.level_price(2), 100_000_000);
+        assert_eq!(grid.level_price(4), 120_000_000);
+    }
+
+    #[test]
+    /// Test that a range sells at its upper level and buys back at its lower level
+    fn test_range_trade() {
+        let mut grid = mock_grid(0);
+        // Sell 2 SOL for at least $100 each
+        let trade = grid.range_trade(1).unwrap();
+        assert!(trade.sells_base);
+        assert_eq!(trade.input_amount, 2_000_000_000);
+        assert_eq!(trade.bounded_price_numerator, 1_000_000_000);
+        assert_eq!(trade.bounded_price_denominator, 100_000_000);
+
+        // A partial sale leaves the range unsold
+        assert!(grid.record_range_fill(1, 1_900_000_000).is_err());
+        assert!(!grid.is_sold(1));
+
+        // Spend $180 buying the 2 SOL back at no more than $90 each
+        grid.record_range_fill(1, 2_000_000_000).unwrap();
+        assert!(grid.is_sold(1));
+        let trade = grid.range_trade(1).unwrap();
+        assert!(!trade.sells_base);
+        assert_eq!(trade.input_amount, 180_000_000);
+        assert_eq!(trade.bounded_price_numerator, 90_000_000);
+        assert_eq!(trade.bounded_price_denominator, 1_000_000_000);
+
+        assert!(grid.record_range_fill(1, 170_000_000).is_err());
+        assert!(grid.is_sold(1));
+        grid.record_range_fill(1, 180_000_000).unwrap();
+        assert!(!grid.is_sold(1));
+        assert!(grid.range_trade(4).is_err());
+    }
+}
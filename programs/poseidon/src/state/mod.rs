@@ -1,5 +1,15 @@
+pub mod basket;
 pub mod bounded_strategy;
 pub mod bounded_strategy_v2;
+pub mod deposit_split;
+pub mod grid_strategy;
+pub mod oco_pair;
+pub mod rebalance_strategy;
 
+pub use basket::*;
 pub use bounded_strategy::*;
 pub use bounded_strategy_v2::*;
+pub use deposit_split::*;
+pub use grid_strategy::*;
+pub use oco_pair::*;
+pub use rebalance_strategy::*;
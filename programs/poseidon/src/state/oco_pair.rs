@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert;
+
+/// Links a take profit and a stop loss BoundedStrategyV2 that sell the same collateral. The pair
+/// owns the shared collateral account until either strategy fills, at which point ownership
+/// passes to the filling strategy and its sibling can no longer trade.
+#[account]
+pub struct OcoPair {
+    pub take_profit: Pubkey,
+    pub stop_loss: Pubkey,
+    /// The collateral account both strategies sell from
+    pub collateral_account: Pubkey,
+    /// The strategy that filled first. The default key until either strategy fills.
+    pub filled_by: Pubkey,
+    /// The bump for the pair's derived address
+    pub bump: u8,
+    /// The price, in the same terms as the strategies' bounded prices, at or below which the
+    /// stop loss may fill. Without it the stop loss would fill as soon as the market is above
+    /// its bounded price, which is most of the time.
+    pub stop_loss_trigger_numerator: u64,
+    pub stop_loss_trigger_denominator: u64,
+}
+
+impl OcoPair {
+    pub const LEN: usize = 8 + std::mem::size_of::<OcoPair>() + 64;
+
+    /// Returns the other strategy of the pair, or None when the strategy is not in the pair
+    pub fn sibling_of(&self, strategy: &Pubkey) -> Option<Pubkey> {
+        if *strategy == self.take_profit {
+            Some(self.stop_loss)
+        } else if *strategy == self.stop_loss {
+            Some(self.take_profit)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true once either strategy has filled
+    pub fn is_filled(&self) -> bool {
+        self.filled_by != Pubkey::default()
+    }
+
+    /// Returns true when the strategy may fill at the market's price, read from a route's
+    /// `market_quotes`. The take profit always may, while the stop loss only may once every quote
+    /// is at or below its trigger price, so slippage on the fill itself cannot trigger it.
+    pub fn can_fill(&self, strategy: &Pubkey, market_quotes: &[(u64, u64)]) -> bool {
+        if *strategy != self.stop_loss {
+            return true;
+        }
+        !market_quotes.is_empty()
+            && market_quotes.iter().all(|(input, output)| {
+                (*output as u128) * (self.stop_loss_trigger_numerator as u128)
+                    <= (*input as u128) * (self.stop_loss_trigger_denominator as u128)
+            })
+    }
+}
+const_assert!(OcoPair::LEN == 224);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dexes::split_route::{test::mock_bid_branch, SplitRoute};
+
+    #[test]
+    fn test_sibling_of() {
+        let pair = OcoPair {
+            take_profit: Pubkey::new_unique(),
+            stop_loss: Pubkey::new_unique(),
+            collateral_account: Pubkey::new_unique(),
+            filled_by: Pubkey::default(),
+            bump: 0,
+            stop_loss_trigger_numerator: 1_000_000_000,
+            stop_loss_trigger_denominator: 20_000_000,
+        };
+        assert_eq!(pair.sibling_of(&pair.take_profit), Some(pair.stop_loss));
+        assert_eq!(pair.sibling_of(&pair.stop_loss), Some(pair.take_profit));
+        assert_eq!(pair.sibling_of(&pair.collateral_account), None);
+        assert!(!pair.is_filled());
+    }
+
+    fn mock_pair() -> OcoPair {
+        OcoPair {
+            take_profit: Pubkey::new_unique(),
+            stop_loss: Pubkey::new_unique(),
+            collateral_account: Pubkey::new_unique(),
+            filled_by: Pubkey::default(),
+            bump: 0,
+            // 80 USDC per SOL
+            stop_loss_trigger_numerator: 1_000_000_000,
+            stop_loss_trigger_denominator: 80_000_000,
+        }
+    }
+
+    #[test]
+    fn test_can_fill() {
+        let pair = mock_pair();
+        // The market at 92 USDC per SOL is above the trigger
+        let quotes = [(1_000_000_000, 92_000_000)];
+        assert!(!pair.can_fill(&pair.stop_loss, &quotes));
+        assert!(pair.can_fill(&pair.take_profit, &quotes));
+        // At or below the trigger the stop loss may fill
+        assert!(pair.can_fill(&pair.stop_loss, &[(1_000_000_000, 80_000_000)]));
+        assert!(pair.can_fill(&pair.stop_loss, &[(1_000_000_000, 64_000_000)]));
+        assert!(pair.can_fill(&pair.stop_loss, &[(u64::MAX, u64::MAX / 20)]));
+        assert!(!pair.can_fill(&pair.stop_loss, &[(u64::MAX / 20, u64::MAX)]));
+        // Every quote must be at or below the trigger, and there must be one
+        assert!(!pair.can_fill(
+            &pair.stop_loss,
+            &[(1_000_000_000, 64_000_000), (500_000_000, 46_000_000)]
+        ));
+        assert!(!pair.can_fill(&pair.stop_loss, &[]));
+    }
+
+    #[test]
+    /// Test that a fill whose slippage drags its price below the trigger cannot trigger the
+    /// stop loss while the top of the market is above it
+    fn test_bad_route_cannot_trigger_stop_loss() {
+        let pair = mock_pair();
+        let mock_accounts: Vec<AccountInfo> = vec![];
+        // 4 SOL are bid at 92 USDC and the rest of the book at 46 USDC
+        let route = SplitRoute {
+            branches: vec![mock_bid_branch(&mock_accounts, 92_000_000, 4_000_000_000)],
+        };
+        let input = 10_000_000_000;
+        let output = route.branches[0].simulate_execution(input);
+        // Selling it all averages below the trigger
+        assert!(pair.can_fill(&pair.stop_loss, &[(input, output)]));
+        assert!(!pair.can_fill(&pair.stop_loss, &route.market_quotes(input)));
+
+        // Once the top of the market falls to 75 USDC the stop loss fills
+        let route = SplitRoute {
+            branches: vec![mock_bid_branch(&mock_accounts, 75_000_000, 4_000_000_000)],
+        };
+        assert!(pair.can_fill(&pair.stop_loss, &route.market_quotes(input)));
+    }
+}
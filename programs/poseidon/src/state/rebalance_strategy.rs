@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert;
+use std::convert::TryFrom;
+
+use crate::{constants::BPS_DENOMINATOR, dexes::math::mul_div_u64, errors::ErrorCode};
+
+#[account]
+pub struct RebalanceStrategy {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// The token account holding the strategy's base
+    pub base_account: Pubkey,
+    /// The token account holding the strategy's quote
+    pub quote_account: Pubkey,
+    /// The date before which the strategy cannot trade
+    pub start_date: i64,
+    /// The date at which the DAO's assets can be reclaimed
+    pub reclaim_date: i64,
+    /// The addresses the base and quote are transferred to when being reclaimed
+    pub base_reclaim_address: Pubkey,
+    pub quote_reclaim_address: Pubkey,
+    /// The fixed reference price both holdings are valued at, in quote per
+    /// `reference_price_denominator` of base.
+    ///
+    /// ### Example:
+    /// Valuing SOL at $100 gives a numerator of 100_000_000 and a denominator of 1_000_000_000.
+    pub reference_price_numerator: u64,
+    pub reference_price_denominator: u64,
+    /// The share of the portfolio's value the base should make up, in basis points
+    pub target_base_weight_bps: u16,
+    /// How far the base weight can drift from its target before executors can rebalance it
+    pub tolerance_bps: u16,
+    /// How far below the reference price base can be sold, and above it bought, in basis points
+    pub max_deviation_bps: u16,
+    /// The bump for the strategy's derived address
+    pub bump: u8,
+}
+
+impl RebalanceStrategy {
+    pub const LEN: usize = 8 + std::mem::size_of::<RebalanceStrategy>() + 64;
+
+    /// Returns the value of the base in quote at the reference price
+    pub fn base_value(&self, base_amount: u64) -> u128 {
+        u128::from(base_amount) * u128::from(self.reference_price_numerator)
+            / u128::from(self.reference_price_denominator)
+    }
+
+    /// Returns the share of the portfolio's value held in base, in basis points. An empty
+    /// portfolio is treated as on target.
+    pub fn base_weight_bps(&self, base_amount: u64, quote_amount: u64) -> u64 {
+        let base_value = self.base_value(base_amount);
+        let total_value = base_value + u128::from(quote_amount);
+        if total_value == 0 {
+            return self.target_base_weight_bps.into();
+        }
+        u64::try_from(base_value * u128::from(BPS_DENOMINATOR) / total_value).unwrap()
+    }
+
+    /// Returns the trade that moves the overweight side back to the target weight. The input is
+    /// the most that can be traded without crossing the target at the reference price, and the
+    /// bound allows at most `max_deviation_bps` of slippage from the reference price.
+    pub fn rebalance_trade(&self, base_amount: u64, quote_amount: u64) -> Result<RebalanceSwap> {
+        let weight = self.base_weight_bps(base_amount, quote_amount);
+        let target = u64::from(self.target_base_weight_bps);
+        let tolerance = u64::from(self.tolerance_bps);
+        let base_value = self.base_value(base_amount);
+        let target_value = (base_value + u128::from(quote_amount))
+            * u128::from(self.target_base_weight_bps)
+            / u128::from(BPS_DENOMINATOR);
+        let deviation = u64::from(self.max_deviation_bps);
+
+        if weight > target + tolerance {
+            // Sell the excess base for at least the discounted reference price
+            let input_amount = (base_value - target_value)
+                * u128::from(self.reference_price_denominator)
+                / u128::from(self.reference_price_numerator);
+            Ok(RebalanceSwap {
+                sells_base: true,
+                input_amount: u64::try_from(input_amount).unwrap(),
+                bounded_price_numerator: self.reference_price_denominator,
+                bounded_price_denominator: mul_div_u64_ceil(
+                    self.reference_price_numerator,
+                    BPS_DENOMINATOR - deviation,
+                    BPS_DENOMINATOR,
+                )
+                .ok_or(ErrorCode::InvalidMaxDeviation)?,
+            })
+        } else if weight + tolerance < target {
+            // Buy the missing base for at most the marked up reference price
+            Ok(RebalanceSwap {
+                sells_base: false,
+                input_amount: u64::try_from(target_value - base_value).unwrap(),
+                bounded_price_numerator: mul_div_u64(
+                    self.reference_price_numerator,
+                    BPS_DENOMINATOR + deviation,
+                    BPS_DENOMINATOR,
+                )
+                .ok_or(ErrorCode::InvalidMaxDeviation)?,
+                bounded_price_denominator: self.reference_price_denominator,
+            })
+        } else {
+            Err(error!(ErrorCode::PortfolioWithinTolerance))
+        }
+    }
+
+    /// Validate that the strategy is within its trading window
+    pub fn validate_can_trade(&self, clock: &Clock) -> Result<()> {
+        if self.start_date > clock.unix_timestamp {
+            return Err(ErrorCode::StartDateHasNotPassed.into());
+        }
+        if self.reclaim_date < clock.unix_timestamp {
+            return Err(ErrorCode::ReclaimDateHasPassed.into());
+        }
+        Ok(())
+    }
+}
+const_assert!(RebalanceStrategy::LEN == 304);
+
+/// Multiplies two u64's then divides by a u64, rounding up
+fn mul_div_u64_ceil(a: u64, b: u64, divisor: u64) -> Option<u64> {
+    let product = u128::from(a).checked_mul(b.into())?;
+    let result = product.checked_add(u128::from(divisor).checked_sub(1)?)? / u128::from(divisor);
+    u64::try_from(result).ok()
+}
+
+/// The input and bound of a single rebalancing trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebalanceSwap {
+    /// True when the trade sells base for quote
+    pub sells_base: bool,
+    pub input_amount: u64,
+    pub bounded_price_numerator: u64,
+    pub bounded_price_denominator: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mock_strategy() -> RebalanceStrategy {
+        RebalanceStrategy {
+            base_mint: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            base_account: Pubkey::default(),
+            quote_account: Pubkey::default(),
+            start_date: 0,
+            reclaim_date: 0,
+            base_reclaim_address: Pubkey::default(),
+            quote_reclaim_address: Pubkey::default(),
+            // 50/50 SOL/USDC with SOL at $100, a 2% band and 1% slippage
+            reference_price_numerator: 100_000_000,
+            reference_price_denominator: 1_000_000_000,
+            target_base_weight_bps: 5_000,
+            tolerance_bps: 200,
+            max_deviation_bps: 100,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_base_weight() {
+        let strategy = mock_strategy();
+        // 1 SOL and $100
+        assert_eq!(strategy.base_weight_bps(1_000_000_000, 100_000_000), 5_000);
+        // 3 SOL and $100
+        assert_eq!(strategy.base_weight_bps(3_000_000_000, 100_000_000), 7_500);
+        assert_eq!(strategy.base_weight_bps(0, 0), 5_000);
+        // Within the band there is nothing to trade
+        assert!(strategy
+            .rebalance_trade(1_030_000_000, 100_000_000)
+            .is_err());
+    }
+
+    #[test]
+    /// Test that the overweight side is traded back to target within the deviation
+    fn test_rebalance_trade() {
+        let strategy = mock_strategy();
+        // 3 SOL and $100 sells 1 SOL for at least $99
+        let trade = strategy
+            .rebalance_trade(3_000_000_000, 100_000_000)
+            .unwrap();
+        assert!(trade.sells_base);
+        assert_eq!(trade.input_amount, 1_000_000_000);
+        assert_eq!(trade.bounded_price_numerator, 1_000_000_000);
+        assert_eq!(trade.bounded_price_denominator, 99_000_000);
+
+        // 1 SOL and $300 spends $100 on SOL at no more than $101
+        let trade = strategy
+            .rebalance_trade(1_000_000_000, 300_000_000)
+            .unwrap();
+        assert!(!trade.sells_base);
+        assert_eq!(trade.input_amount, 100_000_000);
+        assert_eq!(trade.bounded_price_numerator, 101_000_000);
+        assert_eq!(trade.bounded_price_denominator, 1_000_000_000);
+    }
+}
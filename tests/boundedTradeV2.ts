@@ -5,9 +5,18 @@ import { Market, OpenOrders } from "@project-serum/serum";
 import { assert } from "chai";
 import {
   BoundedStrategyV2,
+  encodeAdditionalData,
+  LegParams,
   parseTranactionError,
 } from "../packages/poseidon/src";
-import { deriveAllBoundedStrategyKeysV2 } from "../packages/poseidon/src/pdas";
+import {
+  deriveAllBoundedStrategyKeysV2,
+  deriveBasket,
+  deriveDepositSplit,
+  deriveOcoPair,
+  deriveOpenOrders,
+  deriveProceedsAccount,
+} from "../packages/poseidon/src/pdas";
 import { Poseidon } from "../target/types/poseidon";
 import {
   compileAndSendV0Tx,
@@ -18,7 +27,10 @@ import {
   SOL_USDC_SERUM_MARKET,
   USDC_MINT,
 } from "./utils";
-import { openBookTradeAccounts } from "../packages/poseidon/src/dexes";
+import {
+  deriveVaultSigner,
+  openBookTradeAccounts,
+} from "../packages/poseidon/src/dexes";
 import { WRAPPED_SOL_MINT } from "@project-serum/serum/lib/token-instructions";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
@@ -55,10 +67,15 @@ describe("BoundedTradeV2", () => {
     reclaimAddress: web3.PublicKey,
     collateralMint: web3.PublicKey,
     destinationMint: web3.PublicKey,
-    transferAmount: BN
+    transferAmount: BN,
+    maxFillInput?: BN,
+    startDate?: BN,
+    executorFeeBps?: number,
+    surplusShareBps?: number
   ) => Promise<{
     boundedStrategyKey: web3.PublicKey;
   }>;
+  let legParams: LegParams[];
   let additionalData: Buffer;
   let openOrdersKeypair: web3.Keypair;
 
@@ -70,10 +87,16 @@ describe("BoundedTradeV2", () => {
       {},
       DEX_ID
     );
-    additionalData = new BN(
+    legParams = [
       // @ts-ignore
-      serumMarket._baseSplTokenDecimals
-    ).toArrayLike(Buffer, "le", 1);
+      { openBookV3: { baseDecimals: serumMarket._baseSplTokenDecimals } },
+    ];
+    additionalData = encodeAdditionalData({
+      inputHint: null,
+      searchIterations: 0,
+      legs: legParams,
+      branchLegCounts: [],
+    });
     const [bids, asks] = await Promise.all([
       serumMarket.loadBids(program.provider.connection),
       serumMarket.loadAsks(program.provider.connection),
@@ -157,7 +180,11 @@ describe("BoundedTradeV2", () => {
       reclaimAddress: web3.PublicKey,
       collateralMint: web3.PublicKey,
       destinationMint: web3.PublicKey,
-      transferAmount: BN
+      transferAmount: BN,
+      maxFillInput = new BN(0),
+      startDate = new BN(0),
+      executorFeeBps = 0,
+      surplusShareBps = 0
     ) => {
       const reclaimDate = new anchor.BN(
         new Date().getTime() / 1_000 + 3600 + nonce
@@ -166,6 +193,7 @@ describe("BoundedTradeV2", () => {
         await deriveAllBoundedStrategyKeysV2(program, collateralMint, {
           boundPriceNumerator: boundedPriceNumerator,
           boundPriceDenominator: boundedPriceDenominator,
+          startDate,
           reclaimDate,
         });
 
@@ -187,7 +215,14 @@ describe("BoundedTradeV2", () => {
           transferAmount,
           boundedPriceNumerator,
           boundedPriceDenominator,
-          reclaimDate
+          startDate,
+          reclaimDate,
+          [],
+          executorFeeBps,
+          surplusShareBps,
+          new BN(0),
+          maxFillInput,
+          new BN(0)
         )
         .accounts({
           payer: program.provider.publicKey,
@@ -219,6 +254,31 @@ describe("BoundedTradeV2", () => {
     nonce += 1;
   });
 
+  /** Create a USDC account that is not the owner's associated account */
+  const createUsdcAccount = async (owner = payerKey) => {
+    const accountKeypair = new web3.Keypair();
+    const lamports =
+      await program.provider.connection.getMinimumBalanceForRentExemption(165);
+    const transaction = new web3.Transaction()
+      .add(
+        web3.SystemProgram.createAccount({
+          fromPubkey: payerKey,
+          newAccountPubkey: accountKeypair.publicKey,
+          space: 165,
+          lamports,
+          programId: SPL_TOKEN_PROGRAM_ID,
+        })
+      )
+      .add(
+        await tokenProgram.methods
+          .initializeAccount3(owner)
+          .accounts({ account: accountKeypair.publicKey, mint: USDC_MINT })
+          .instruction()
+      );
+    await program.provider.sendAndConfirm(transaction, [accountKeypair]);
+    return accountKeypair.publicKey;
+  };
+
   describe("Order side is Bid", () => {
     describe("UpperBound", () => {
       describe("Bounded price is higher than lowest ask", () => {
@@ -432,6 +492,42 @@ describe("BoundedTradeV2", () => {
             boundedStrategyKey
           );
         });
+        it("should quote the route without moving funds", async () => {
+          const collateralBefore = await tokenProgram.account.account.fetch(
+            boundedStrategy.collateralAccount
+          );
+          const remainingAccounts = await openBookTradeAccounts(
+            serumMarket,
+            boundedStrategy.collateralAccount,
+            boundedStrategy.depositAddress,
+            openOrdersKeypair.publicKey,
+            payerKey
+          );
+
+          const quote = await program.methods
+            .quoteRoute(additionalData)
+            .accounts({
+              strategy: boundedStrategyKey,
+              orderPayer: boundedStrategy.collateralAccount,
+              depositAccount: boundedStrategy.depositAddress,
+            })
+            .remainingAccounts(remainingAccounts)
+            .view();
+
+          assert.ok(quote.passed);
+          assert.ok(quote.maxInput.gt(new BN(0)));
+          assert.ok(quote.expectedOutput.gt(new BN(0)));
+          assert.equal(quote.legs.length, 1);
+          assert.ok(quote.legs[0].dexProgramId.equals(DEX_ID));
+          assert.ok(quote.legs[0].passed);
+          const collateralAfter = await tokenProgram.account.account.fetch(
+            boundedStrategy.collateralAccount
+          );
+          assert.equal(
+            collateralAfter.amount.toString(),
+            collateralBefore.amount.toString()
+          );
+        });
         it("should execute the trade and settle the assets", async () => {
           const depositTokenAccountBefore =
             await tokenProgram.account.account.fetch(quoteAddress);
@@ -499,6 +595,1283 @@ describe("BoundedTradeV2", () => {
           assert.equal(depositTokenDiff.toString(), usdcReceived.toString());
         });
       });
-    });
-  }); // End of LowerBound
+
+      describe("Strategy pays an executor fee", () => {
+        const executorFeeBps = 100;
+        let proceedsAddress: web3.PublicKey;
+        let executorAddress: web3.PublicKey;
+        beforeEach(async () => {
+          ({ boundedStrategyKey } = await initBoundedStrategy(
+            nonce,
+            new anchor.BN(1_000_000_000),
+            new anchor.BN(20_000_000),
+            quoteAddress,
+            baseAddress,
+            serumMarket.baseMintAddress,
+            USDC_MINT,
+            baseTransferAmount,
+            new BN(0),
+            new BN(0),
+            executorFeeBps
+          ));
+          boundedStrategy = await program.account.boundedStrategyV2.fetch(
+            boundedStrategyKey
+          );
+          proceedsAddress = await createUsdcAccount(boundedStrategyKey);
+          executorAddress = await createUsdcAccount();
+        });
+        const feeTradeIx = async (routeDestination: web3.PublicKey) => {
+          const remainingAccounts = await openBookTradeAccounts(
+            serumMarket,
+            boundedStrategy.collateralAccount,
+            routeDestination,
+            openOrdersKeypair.publicKey,
+            payerKey
+          );
+          return program.methods
+            .boundedTradeV2(additionalData)
+            .accounts({
+              payer: program.provider.publicKey,
+              strategy: boundedStrategyKey,
+              orderPayer: boundedStrategy.collateralAccount,
+              depositAccount: boundedStrategy.depositAddress,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts([
+              { pubkey: proceedsAddress, isWritable: true, isSigner: false },
+              { pubkey: executorAddress, isWritable: true, isSigner: false },
+              ...remainingAccounts,
+            ])
+            .instruction();
+        };
+        it("should pay the fee out of the strategy's proceeds", async () => {
+          const depositBefore = await tokenProgram.account.account.fetch(
+            quoteAddress
+          );
+          await compileAndSendV0Tx(
+            program.provider,
+            payerKeypair,
+            lookupTableAddress,
+            [await feeTradeIx(proceedsAddress)],
+            (err) => {
+              console.error(err);
+              assert.ok(false);
+            }
+          );
+
+          const [depositAfter, proceeds, executor] = await Promise.all([
+            tokenProgram.account.account.fetch(quoteAddress),
+            tokenProgram.account.account.fetch(proceedsAddress),
+            tokenProgram.account.account.fetch(executorAddress),
+          ]);
+          const depositGained = depositAfter.amount.sub(depositBefore.amount);
+          const output = depositGained.add(executor.amount);
+          assert.ok(executor.amount.gtn(0));
+          assert.equal(
+            executor.amount.toString(),
+            output.muln(executorFeeBps).divn(10_000).toString()
+          );
+          assert.equal(proceeds.amount.toString(), "0");
+        });
+        it("should error when the route skips the proceeds", async () => {
+          // Ending the route in the deposit account would leave the fee unpaid
+          await compileAndSendV0Tx(
+            program.provider,
+            payerKeypair,
+            lookupTableAddress,
+            [await feeTradeIx(boundedStrategy.depositAddress)],
+            (err) => {
+              const parsedError = parseTranactionError(err);
+              assert.equal(parsedError.msg, "Market price is out of bounds");
+            }
+          );
+          const executor = await tokenProgram.account.account.fetch(
+            executorAddress
+          );
+          assert.equal(executor.amount.toString(), "0");
+        });
+      });
+
+      describe("Strategy shares its surplus", () => {
+        const surplusShareBps = 5_000;
+        let proceedsAddress: web3.PublicKey;
+        let executorAddress: web3.PublicKey;
+        beforeEach(async () => {
+          ({ boundedStrategyKey } = await initBoundedStrategy(
+            nonce,
+            new anchor.BN(1_000_000_000),
+            new anchor.BN(20_000_000),
+            quoteAddress,
+            baseAddress,
+            serumMarket.baseMintAddress,
+            USDC_MINT,
+            baseTransferAmount,
+            new BN(0),
+            new BN(0),
+            0,
+            surplusShareBps
+          ));
+          boundedStrategy = await program.account.boundedStrategyV2.fetch(
+            boundedStrategyKey
+          );
+          proceedsAddress = await createUsdcAccount(boundedStrategyKey);
+          executorAddress = await createUsdcAccount();
+        });
+        it("should pay the surplus share out of the proceeds", async () => {
+          const remainingAccounts = await openBookTradeAccounts(
+            serumMarket,
+            boundedStrategy.collateralAccount,
+            proceedsAddress,
+            openOrdersKeypair.publicKey,
+            payerKey
+          );
+          const ix = await program.methods
+            .boundedTradeV2(additionalData)
+            .accounts({
+              payer: program.provider.publicKey,
+              strategy: boundedStrategyKey,
+              orderPayer: boundedStrategy.collateralAccount,
+              depositAccount: boundedStrategy.depositAddress,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts([
+              { pubkey: proceedsAddress, isWritable: true, isSigner: false },
+              { pubkey: executorAddress, isWritable: true, isSigner: false },
+              ...remainingAccounts,
+            ])
+            .instruction();
+          const depositBefore = await tokenProgram.account.account.fetch(
+            quoteAddress
+          );
+          await compileAndSendV0Tx(
+            program.provider,
+            payerKeypair,
+            lookupTableAddress,
+            [ix],
+            (err) => {
+              console.error(err);
+              assert.ok(false);
+            }
+          );
+
+          const [depositAfter, collateral, executor] = await Promise.all([
+            tokenProgram.account.account.fetch(quoteAddress),
+            tokenProgram.account.account.fetch(
+              boundedStrategy.collateralAccount
+            ),
+            tokenProgram.account.account.fetch(executorAddress),
+          ]);
+          const input = baseTransferAmount.sub(collateral.amount);
+          const output = depositAfter.amount
+            .sub(depositBefore.amount)
+            .add(executor.amount);
+          // The least output the bound allows, rounded up
+          const boundedOutput = input
+            .mul(new BN(20_000_000))
+            .add(new BN(999_999_999))
+            .div(new BN(1_000_000_000));
+          const surplus = output.sub(boundedOutput);
+          assert.ok(surplus.gtn(0));
+          assert.equal(
+            executor.amount.toString(),
+            surplus.muln(surplusShareBps).divn(10_000).toString()
+          );
+        });
+      });
+
+      describe("Strategy has a maximum fill input", () => {
+        const maxFillInput = new BN(1_000_000_000);
+        beforeEach(async () => {
+          // Input 1 SOL and get at least 20 USDC for it
+          const boundPriceNumerator = new anchor.BN(1_000_000_000);
+          const boundPriceDenominator = new anchor.BN(20_000_000);
+
+          ({ boundedStrategyKey } = await initBoundedStrategy(
+            nonce,
+            boundPriceNumerator,
+            boundPriceDenominator,
+            quoteAddress,
+            baseAddress,
+            serumMarket.baseMintAddress,
+            USDC_MINT,
+            baseTransferAmount,
+            maxFillInput
+          ));
+          boundedStrategy = await program.account.boundedStrategyV2.fetch(
+            boundedStrategyKey
+          );
+        });
+        it("should only sell up to the maximum fill input", async () => {
+          const collateralAccountBefore =
+            await tokenProgram.account.account.fetch(
+              boundedStrategy.collateralAccount
+            );
+          const remainingAccounts = await openBookTradeAccounts(
+            serumMarket,
+            boundedStrategy.collateralAccount,
+            boundedStrategy.depositAddress,
+            openOrdersKeypair.publicKey,
+            payerKey
+          );
+
+          const ix = await program.methods
+            .boundedTradeV2(additionalData)
+            .accounts({
+              payer: program.provider.publicKey,
+              strategy: boundedStrategyKey,
+              orderPayer: boundedStrategy.collateralAccount,
+              depositAccount: boundedStrategy.depositAddress,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(remainingAccounts)
+            .instruction();
+          await compileAndSendV0Tx(
+            program.provider,
+            payerKeypair,
+            lookupTableAddress,
+            [ix],
+            (err) => {
+              console.error(err);
+              assert.ok(false);
+            }
+          );
+
+          const collateralAccountAfter =
+            await tokenProgram.account.account.fetch(
+              boundedStrategy.collateralAccount
+            );
+          const collateralDiff = collateralAccountBefore.amount.sub(
+            collateralAccountAfter.amount
+          );
+          assert.ok(collateralDiff.gtn(0));
+          assert.ok(collateralDiff.lte(maxFillInput));
+        });
+      });
+
+      describe("Executor supplies an input hint", () => {
+        const inputHint = new BN(500_000_000);
+        beforeEach(async () => {
+          // Input 1 SOL and get at least 20 USDC for it
+          const boundPriceNumerator = new anchor.BN(1_000_000_000);
+          const boundPriceDenominator = new anchor.BN(20_000_000);
+
+          ({ boundedStrategyKey } = await initBoundedStrategy(
+            nonce,
+            boundPriceNumerator,
+            boundPriceDenominator,
+            quoteAddress,
+            baseAddress,
+            serumMarket.baseMintAddress,
+            USDC_MINT,
+            baseTransferAmount
+          ));
+          boundedStrategy = await program.account.boundedStrategyV2.fetch(
+            boundedStrategyKey
+          );
+        });
+        it("should sell the hinted input when it is in bounds", async () => {
+          const collateralAccountBefore =
+            await tokenProgram.account.account.fetch(
+              boundedStrategy.collateralAccount
+            );
+          const remainingAccounts = await openBookTradeAccounts(
+            serumMarket,
+            boundedStrategy.collateralAccount,
+            boundedStrategy.depositAddress,
+            openOrdersKeypair.publicKey,
+            payerKey
+          );
+
+          const ix = await program.methods
+            .boundedTradeV2(
+              encodeAdditionalData({
+                inputHint,
+                searchIterations: 4,
+                legs: legParams,
+                branchLegCounts: [],
+              })
+            )
+            .accounts({
+              payer: program.provider.publicKey,
+              strategy: boundedStrategyKey,
+              orderPayer: boundedStrategy.collateralAccount,
+              depositAccount: boundedStrategy.depositAddress,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(remainingAccounts)
+            .instruction();
+          await compileAndSendV0Tx(
+            program.provider,
+            payerKeypair,
+            lookupTableAddress,
+            [ix],
+            (err) => {
+              console.error(err);
+              assert.ok(false);
+            }
+          );
+
+          const collateralAccountAfter =
+            await tokenProgram.account.account.fetch(
+              boundedStrategy.collateralAccount
+            );
+          const collateralDiff = collateralAccountBefore.amount.sub(
+            collateralAccountAfter.amount
+          );
+          assert.equal(collateralDiff.toString(), inputHint.toString());
+        });
+        it("should reject an unsupported additional data version", async () => {
+          const remainingAccounts = await openBookTradeAccounts(
+            serumMarket,
+            boundedStrategy.collateralAccount,
+            boundedStrategy.depositAddress,
+            openOrdersKeypair.publicKey,
+            payerKey
+          );
+          const unsupportedData = Buffer.from(additionalData);
+          unsupportedData[0] = 0xff;
+
+          const ix = await program.methods
+            .boundedTradeV2(unsupportedData)
+            .accounts({
+              payer: program.provider.publicKey,
+              strategy: boundedStrategyKey,
+              orderPayer: boundedStrategy.collateralAccount,
+              depositAccount: boundedStrategy.depositAddress,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(remainingAccounts)
+            .instruction();
+          await compileAndSendV0Tx(
+            program.provider,
+            payerKeypair,
+            lookupTableAddress,
+            [ix],
+            (err) => {
+              const parsedError = parseTranactionError(err);
+              assert.equal(
+                parsedError.msg,
+                "Unsupported additional data version"
+              );
+            }
+          );
+        });
+      });
+
+      describe("Strategy has not reached its start date", () => {
+        beforeEach(async () => {
+          // Input 1 SOL and get at least 20 USDC for it, starting in 30 minutes
+          const boundPriceNumerator = new anchor.BN(1_000_000_000);
+          const boundPriceDenominator = new anchor.BN(20_000_000);
+          const startDate = new anchor.BN(new Date().getTime() / 1_000 + 1800);
+
+          ({ boundedStrategyKey } = await initBoundedStrategy(
+            nonce,
+            boundPriceNumerator,
+            boundPriceDenominator,
+            quoteAddress,
+            baseAddress,
+            serumMarket.baseMintAddress,
+            USDC_MINT,
+            baseTransferAmount,
+            new BN(0),
+            startDate
+          ));
+          boundedStrategy = await program.account.boundedStrategyV2.fetch(
+            boundedStrategyKey
+          );
+        });
+        it("should error", async () => {
+          const remainingAccounts = await openBookTradeAccounts(
+            serumMarket,
+            boundedStrategy.collateralAccount,
+            boundedStrategy.depositAddress,
+            openOrdersKeypair.publicKey,
+            payerKey
+          );
+          const ix = await program.methods
+            .boundedTradeV2(additionalData)
+            .accounts({
+              payer: program.provider.publicKey,
+              strategy: boundedStrategyKey,
+              orderPayer: boundedStrategy.collateralAccount,
+              depositAccount: boundedStrategy.depositAddress,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(remainingAccounts)
+            .instruction();
+          await compileAndSendV0Tx(
+            program.provider,
+            payerKeypair,
+            lookupTableAddress,
+            [ix],
+            (err) => {
+              const parsedError = parseTranactionError(err);
+              assert.equal(
+                parsedError.msg,
+                "Strategy cannot trade before the start date"
+              );
+            }
+          );
+          assert.ok(true);
+        });
+      });
+    });
+  }); // End of LowerBound
+  describe("Opposing strategies", () => {
+    let sellerKey: web3.PublicKey;
+    let buyerKey: web3.PublicKey;
+    let seller: BoundedStrategyV2;
+    let buyer: BoundedStrategyV2;
+    const matchStrategies = async (onError: (err) => void) => {
+      const ix = await program.methods
+        .matchStrategiesV2()
+        .accounts({
+          payer: program.provider.publicKey,
+          strategyA: sellerKey,
+          orderPayerA: seller.collateralAccount,
+          depositAccountA: seller.depositAddress,
+          strategyB: buyerKey,
+          orderPayerB: buyer.collateralAccount,
+          depositAccountB: buyer.depositAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .instruction();
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [ix],
+        onError
+      );
+    };
+    describe("Bounds overlap", () => {
+      beforeEach(async () => {
+        // Sell SOL for at least 90 USDC each
+        ({ boundedStrategyKey: sellerKey } = await initBoundedStrategy(
+          nonce,
+          new anchor.BN(1_000_000_000),
+          new anchor.BN(90_000_000),
+          quoteAddress,
+          baseAddress,
+          serumMarket.baseMintAddress,
+          USDC_MINT,
+          baseTransferAmount
+        ));
+        // Buy SOL for at most 95 USDC each
+        ({ boundedStrategyKey: buyerKey } = await initBoundedStrategy(
+          nonce,
+          new anchor.BN(95_000_000),
+          new anchor.BN(1_000_000_000),
+          baseAddress,
+          quoteAddress,
+          USDC_MINT,
+          serumMarket.baseMintAddress,
+          quoteTransferAmount
+        ));
+        seller = await program.account.boundedStrategyV2.fetch(sellerKey);
+        buyer = await program.account.boundedStrategyV2.fetch(buyerKey);
+      });
+      it("should cross the strategies at the midpoint", async () => {
+        await matchStrategies((err) => {
+          console.error(err);
+          assert.ok(false);
+        });
+
+        const [sellerCollateral, buyerCollateral] = await Promise.all([
+          tokenProgram.account.account.fetch(seller.collateralAccount),
+          tokenProgram.account.account.fetch(buyer.collateralAccount),
+        ]);
+        const solSold = baseTransferAmount.sub(sellerCollateral.amount);
+        const usdcSold = quoteTransferAmount.sub(buyerCollateral.amount);
+        // All of the buyer's USDC fills at 92.5 USDC per SOL
+        assert.equal(solSold.toString(), "108108108");
+        assert.equal(usdcSold.toString(), "9999999");
+
+        const sellerAfter = await program.account.boundedStrategyV2.fetch(
+          sellerKey
+        );
+        assert.equal(sellerAfter.fillStats.tradeCount.toNumber(), 1);
+        assert.equal(sellerAfter.fillStats.totalOutput.toString(), "9999999");
+      });
+    });
+    describe("Bounds do not overlap", () => {
+      beforeEach(async () => {
+        // Sell SOL for at least 96 USDC each
+        ({ boundedStrategyKey: sellerKey } = await initBoundedStrategy(
+          nonce,
+          new anchor.BN(1_000_000_000),
+          new anchor.BN(96_000_000),
+          quoteAddress,
+          baseAddress,
+          serumMarket.baseMintAddress,
+          USDC_MINT,
+          baseTransferAmount
+        ));
+        // Buy SOL for at most 95 USDC each
+        ({ boundedStrategyKey: buyerKey } = await initBoundedStrategy(
+          nonce,
+          new anchor.BN(95_000_000),
+          new anchor.BN(1_000_000_000),
+          baseAddress,
+          quoteAddress,
+          USDC_MINT,
+          serumMarket.baseMintAddress,
+          quoteTransferAmount
+        ));
+        seller = await program.account.boundedStrategyV2.fetch(sellerKey);
+        buyer = await program.account.boundedStrategyV2.fetch(buyerKey);
+      });
+      it("should error", async () => {
+        await matchStrategies((err) => {
+          const parsedError = parseTranactionError(err);
+          assert.equal(parsedError.msg, "Strategy bounds do not overlap");
+        });
+      });
+    });
+  });
+  describe("Market maker fills the strategy", () => {
+    beforeEach(async () => {
+      // Sell SOL for at least 90 USDC each
+      ({ boundedStrategyKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(90_000_000),
+        quoteAddress,
+        baseAddress,
+        serumMarket.baseMintAddress,
+        USDC_MINT,
+        baseTransferAmount
+      ));
+      boundedStrategy = await program.account.boundedStrategyV2.fetch(
+        boundedStrategyKey
+      );
+    });
+    const fillStrategy = async (
+      inputAmount: BN,
+      outputAmount: BN,
+      onError: (err) => void
+    ) => {
+      const ix = await program.methods
+        .fillStrategyV2(inputAmount, outputAmount)
+        .accounts({
+          maker: program.provider.publicKey,
+          strategy: boundedStrategyKey,
+          orderPayer: boundedStrategy.collateralAccount,
+          depositAccount: boundedStrategy.depositAddress,
+          makerSourceAccount: quoteAddress,
+          makerDestinationAccount: baseAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .instruction();
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [ix],
+        onError
+      );
+    };
+    it("should fill at the maker's price when it is in bounds", async () => {
+      // 0.1 SOL for 9.1 USDC
+      await fillStrategy(new BN(100_000_000), new BN(9_100_000), (err) => {
+        console.error(err);
+        assert.ok(false);
+      });
+      const collateralAccount = await tokenProgram.account.account.fetch(
+        boundedStrategy.collateralAccount
+      );
+      assert.equal(
+        baseTransferAmount.sub(collateralAccount.amount).toString(),
+        "100000000"
+      );
+      const strategyAfter = await program.account.boundedStrategyV2.fetch(
+        boundedStrategyKey
+      );
+      assert.equal(strategyAfter.fillStats.totalOutput.toString(), "9100000");
+    });
+    it("should error when the maker's price is out of bounds", async () => {
+      // 0.1 SOL for 8.9 USDC
+      await fillStrategy(new BN(100_000_000), new BN(8_900_000), (err) => {
+        const parsedError = parseTranactionError(err);
+        assert.equal(parsedError.msg, "Market price is out of bounds");
+      });
+    });
+  });
+  describe("Resting maker order", () => {
+    let openOrdersKey: web3.PublicKey;
+    beforeEach(async () => {
+      // Sell SOL for at least 10 USDC above the lowest ask, so the order rests
+      const askPrice = Math.ceil(lowestAsk[0] + 10);
+      ({ boundedStrategyKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(askPrice * 1_000_000),
+        quoteAddress,
+        baseAddress,
+        serumMarket.baseMintAddress,
+        USDC_MINT,
+        baseTransferAmount
+      ));
+      boundedStrategy = await program.account.boundedStrategyV2.fetch(
+        boundedStrategyKey
+      );
+      [openOrdersKey] = deriveOpenOrders(program, boundedStrategyKey);
+    });
+    const initOpenOrders = (payer = payerKeypair) =>
+      program.methods
+        .initOpenOrdersV2(new BN(OpenOrders.getLayout(DEX_ID).span))
+        .accounts({
+          payer: payer.publicKey,
+          strategy: boundedStrategyKey,
+          serumMarket: serumMarket.address,
+          depositAccount: boundedStrategy.depositAddress,
+          reclaimAccount: boundedStrategy.reclaimAddress,
+          openOrders: openOrdersKey,
+          dexProgram: DEX_ID,
+          systemProgram: web3.SystemProgram.programId,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([payer])
+        .rpc();
+    it("should reject an OpenOrders account from another payer", async () => {
+      try {
+        await initOpenOrders(new web3.Keypair());
+        assert.ok(false);
+      } catch (err) {
+        const parsedError = parseTranactionError(err);
+        assert.equal(parsedError.msg, "Authority does not match the strategy");
+      }
+    });
+    it("should post the collateral at the bound and settle it", async () => {
+      await initOpenOrders();
+      await program.methods
+        .placeRestingOrderV2()
+        .accounts({
+          payer: payerKey,
+          strategy: boundedStrategyKey,
+          orderPayer: boundedStrategy.collateralAccount,
+          openOrders: openOrdersKey,
+          serumMarket: serumMarket.address,
+          requestQueue: serumMarket._decoded.requestQueue,
+          eventQueue: serumMarket._decoded.eventQueue,
+          bids: serumMarket.bidsAddress,
+          asks: serumMarket.asksAddress,
+          coinVault: serumMarket.decoded.baseVault,
+          pcVault: serumMarket.decoded.quoteVault,
+          dexProgram: DEX_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      let strategyAfter = await program.account.boundedStrategyV2.fetch(
+        boundedStrategyKey
+      );
+      assert.equal(
+        strategyAfter.openOrders.toString(),
+        openOrdersKey.toString()
+      );
+      assert.equal(
+        strategyAfter.restingInput.toString(),
+        baseTransferAmount.toString()
+      );
+      const orders = await serumMarket.loadOrdersForOwner(
+        program.provider.connection,
+        boundedStrategyKey
+      );
+      assert.equal(orders.length, 1);
+      assert.equal(orders[0].side, "sell");
+
+      // Nothing has filled, so settling leaves the order resting
+      await program.methods
+        .settleRestingOrderV2()
+        .accounts({
+          payer: payerKey,
+          strategy: boundedStrategyKey,
+          orderPayer: boundedStrategy.collateralAccount,
+          depositAccount: boundedStrategy.depositAddress,
+          openOrders: openOrdersKey,
+          serumMarket: serumMarket.address,
+          coinVault: serumMarket.decoded.baseVault,
+          pcVault: serumMarket.decoded.quoteVault,
+          serumVaultSigner: await deriveVaultSigner(serumMarket),
+          dexProgram: DEX_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      strategyAfter = await program.account.boundedStrategyV2.fetch(
+        boundedStrategyKey
+      );
+      assert.equal(
+        strategyAfter.restingInput.toString(),
+        baseTransferAmount.toString()
+      );
+      assert.equal(strategyAfter.fillStats.tradeCount.toString(), "0");
+    });
+  });
+  describe("Basket of outputs", () => {
+    let basketKey: web3.PublicKey;
+    let grantsAddress: web3.PublicKey;
+    beforeEach(async () => {
+      // Sell SOL for at least 20 USDC each
+      ({ boundedStrategyKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(20_000_000),
+        quoteAddress,
+        baseAddress,
+        serumMarket.baseMintAddress,
+        USDC_MINT,
+        baseTransferAmount
+      ));
+      boundedStrategy = await program.account.boundedStrategyV2.fetch(
+        boundedStrategyKey
+      );
+      // A second USDC account receives 40% of the collateral's proceeds
+      grantsAddress = await createUsdcAccount();
+
+      [basketKey] = deriveBasket(program, boundedStrategyKey);
+      await program.methods
+        .initBasketV2([
+          {
+            weightBps: 6_000,
+            boundedPriceNumerator: new BN(1_000_000_000),
+            boundedPriceDenominator: new BN(20_000_000),
+          },
+          {
+            weightBps: 4_000,
+            boundedPriceNumerator: new BN(1_000_000_000),
+            boundedPriceDenominator: new BN(20_000_000),
+          },
+        ])
+        .accounts({
+          payer: payerKey,
+          strategy: boundedStrategyKey,
+          basket: basketKey,
+          collateralAccount: boundedStrategy.collateralAccount,
+          reclaimAccount: boundedStrategy.reclaimAddress,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: quoteAddress, isWritable: false, isSigner: false },
+          { pubkey: grantsAddress, isWritable: false, isSigner: false },
+        ])
+        .rpc();
+    });
+    it("should quote an output up to its allocation", async () => {
+      const remainingAccounts = await openBookTradeAccounts(
+        serumMarket,
+        boundedStrategy.collateralAccount,
+        grantsAddress,
+        openOrdersKeypair.publicKey,
+        payerKey
+      );
+      const quote = await program.methods
+        .quoteRoute(additionalData)
+        .accounts({
+          strategy: boundedStrategyKey,
+          orderPayer: boundedStrategy.collateralAccount,
+          depositAccount: grantsAddress,
+        })
+        .remainingAccounts([
+          { pubkey: basketKey, isWritable: false, isSigner: false },
+          ...remainingAccounts,
+        ])
+        .view();
+
+      const basket = await program.account.basket.fetch(basketKey);
+      assert.ok(quote.passed);
+      assert.ok(quote.maxInput.gtn(0));
+      assert.ok(quote.maxInput.lte(basket.outputs[1].allocatedInput));
+      assert.ok(quote.legs[0].passed);
+    });
+    it("should only sell the output's allocation into it", async () => {
+      const basket = await program.account.basket.fetch(basketKey);
+      assert.equal(
+        basket.outputs[0].allocatedInput.toString(),
+        baseTransferAmount.muln(6).divn(10).toString()
+      );
+      const remainingAccounts = await openBookTradeAccounts(
+        serumMarket,
+        boundedStrategy.collateralAccount,
+        quoteAddress,
+        openOrdersKeypair.publicKey,
+        payerKey
+      );
+      const ix = await program.methods
+        .boundedTradeV2(additionalData)
+        .accounts({
+          payer: program.provider.publicKey,
+          strategy: boundedStrategyKey,
+          orderPayer: boundedStrategy.collateralAccount,
+          depositAccount: quoteAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: basketKey, isWritable: true, isSigner: false },
+          ...remainingAccounts,
+        ])
+        .instruction();
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [ix],
+        (err) => {
+          console.error(err);
+          assert.ok(false);
+        }
+      );
+
+      const basketAfter = await program.account.basket.fetch(basketKey);
+      const collateralAccount = await tokenProgram.account.account.fetch(
+        boundedStrategy.collateralAccount
+      );
+      const inputUsed = baseTransferAmount.sub(collateralAccount.amount);
+      assert.ok(inputUsed.gtn(0));
+      assert.ok(inputUsed.lte(basketAfter.outputs[0].allocatedInput));
+      assert.equal(
+        basketAfter.outputs[0].filledInput.toString(),
+        inputUsed.toString()
+      );
+      assert.equal(basketAfter.outputs[1].filledInput.toString(), "0");
+    });
+  });
+  describe("Split deposits", () => {
+    let depositSplitKey: web3.PublicKey;
+    let proceedsAddress: web3.PublicKey;
+    let grantsAddress: web3.PublicKey;
+    beforeEach(async () => {
+      // Sell SOL for at least 20 USDC each
+      ({ boundedStrategyKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(20_000_000),
+        quoteAddress,
+        baseAddress,
+        serumMarket.baseMintAddress,
+        USDC_MINT,
+        baseTransferAmount,
+        new BN(1_000_000_000)
+      ));
+      boundedStrategy = await program.account.boundedStrategyV2.fetch(
+        boundedStrategyKey
+      );
+      // 80% of the proceeds go to the treasury and 20% to a grants account
+      grantsAddress = await createUsdcAccount();
+      [depositSplitKey] = deriveDepositSplit(program, boundedStrategyKey);
+      [proceedsAddress] = deriveProceedsAccount(program, boundedStrategyKey);
+      await program.methods
+        .initDepositSplitV2([8_000, 2_000])
+        .accounts({
+          payer: payerKey,
+          strategy: boundedStrategyKey,
+          depositSplit: depositSplitKey,
+          depositAccount: boundedStrategy.depositAddress,
+          depositMint: USDC_MINT,
+          proceedsAccount: proceedsAddress,
+          reclaimAccount: boundedStrategy.reclaimAddress,
+          tokenProgram: SPL_TOKEN_PROGRAM_ID,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: quoteAddress, isWritable: false, isSigner: false },
+          { pubkey: grantsAddress, isWritable: false, isSigner: false },
+        ])
+        .rpc();
+    });
+    it("should pay the proceeds out by weight", async () => {
+      const [treasuryBefore, grantsBefore] = await Promise.all([
+        tokenProgram.account.account.fetch(quoteAddress),
+        tokenProgram.account.account.fetch(grantsAddress),
+      ]);
+      const remainingAccounts = await openBookTradeAccounts(
+        serumMarket,
+        boundedStrategy.collateralAccount,
+        proceedsAddress,
+        openOrdersKeypair.publicKey,
+        payerKey
+      );
+      const ix = await program.methods
+        .boundedTradeV2(additionalData)
+        .accounts({
+          payer: program.provider.publicKey,
+          strategy: boundedStrategyKey,
+          orderPayer: boundedStrategy.collateralAccount,
+          depositAccount: proceedsAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: depositSplitKey, isWritable: false, isSigner: false },
+          { pubkey: quoteAddress, isWritable: true, isSigner: false },
+          { pubkey: grantsAddress, isWritable: true, isSigner: false },
+          ...remainingAccounts,
+        ])
+        .instruction();
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [ix],
+        (err) => {
+          console.error(err);
+          assert.ok(false);
+        }
+      );
+
+      const [treasuryAfter, grantsAfter, proceedsAfter] = await Promise.all([
+        tokenProgram.account.account.fetch(quoteAddress),
+        tokenProgram.account.account.fetch(grantsAddress),
+        tokenProgram.account.account.fetch(proceedsAddress),
+      ]);
+      const treasuryGained = treasuryAfter.amount.sub(treasuryBefore.amount);
+      const grantsGained = grantsAfter.amount.sub(grantsBefore.amount);
+      assert.ok(grantsGained.gtn(0));
+      // The treasury gets 4 times the grants, plus the rounding dust
+      const dust = treasuryGained.sub(grantsGained.muln(4));
+      assert.ok(dust.gten(0));
+      assert.ok(dust.ltn(5));
+      assert.equal(proceedsAfter.amount.toString(), "0");
+    });
+  });
+  describe("OCO pairs", () => {
+    let takeProfitKey: web3.PublicKey;
+    let stopLossKey: web3.PublicKey;
+    let ocoPairKey: web3.PublicKey;
+    let collateralAddress: web3.PublicKey;
+    beforeEach(async () => {
+      // Take profit at 20 USDC per SOL and stop loss at 15 USDC per SOL once
+      //  the market falls to 18 USDC per SOL, both selling half of the
+      //  collateral
+      ({ boundedStrategyKey: takeProfitKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(20_000_000),
+        quoteAddress,
+        baseAddress,
+        serumMarket.baseMintAddress,
+        USDC_MINT,
+        baseTransferAmount.divn(2)
+      ));
+      ({ boundedStrategyKey: stopLossKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(15_000_000),
+        quoteAddress,
+        baseAddress,
+        serumMarket.baseMintAddress,
+        USDC_MINT,
+        baseTransferAmount.divn(2)
+      ));
+      const [takeProfit, stopLoss] = await Promise.all([
+        program.account.boundedStrategyV2.fetch(takeProfitKey),
+        program.account.boundedStrategyV2.fetch(stopLossKey),
+      ]);
+      collateralAddress = takeProfit.collateralAccount;
+      [ocoPairKey] = deriveOcoPair(program, takeProfitKey, stopLossKey);
+      await program.methods
+        .initOcoPairV2(new BN(1_000_000_000), new BN(18_000_000))
+        .accounts({
+          payer: payerKey,
+          takeProfit: takeProfitKey,
+          stopLoss: stopLossKey,
+          ocoPair: ocoPairKey,
+          takeProfitCollateral: takeProfit.collateralAccount,
+          stopLossCollateral: stopLoss.collateralAccount,
+          reclaimAccount: takeProfit.reclaimAddress,
+          tokenProgram: SPL_TOKEN_PROGRAM_ID,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+    });
+    const tradeIx = async (strategyKey: web3.PublicKey) => {
+      const remainingAccounts = await openBookTradeAccounts(
+        serumMarket,
+        collateralAddress,
+        quoteAddress,
+        openOrdersKeypair.publicKey,
+        payerKey
+      );
+      return program.methods
+        .boundedTradeV2(additionalData)
+        .accounts({
+          payer: program.provider.publicKey,
+          strategy: strategyKey,
+          orderPayer: collateralAddress,
+          depositAccount: quoteAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: ocoPairKey, isWritable: true, isSigner: false },
+          ...remainingAccounts,
+        ])
+        .instruction();
+    };
+    it("should share the collateral between both strategies", async () => {
+      const [stopLoss, ocoPair, collateralAccount] = await Promise.all([
+        program.account.boundedStrategyV2.fetch(stopLossKey),
+        program.account.ocoPair.fetch(ocoPairKey),
+        tokenProgram.account.account.fetch(collateralAddress),
+      ]);
+      assert.ok(stopLoss.extension.ocoPair.ocoPair.equals(ocoPairKey));
+      assert.ok(stopLoss.collateralAccount.equals(collateralAddress));
+      assert.ok(collateralAccount.owner.equals(ocoPairKey));
+      assert.equal(
+        collateralAccount.amount.toString(),
+        baseTransferAmount.toString()
+      );
+      assert.ok(ocoPair.filledBy.equals(web3.PublicKey.default));
+      assert.equal(ocoPair.stopLossTriggerDenominator.toString(), "18000000");
+    });
+    it("should not fill the stop loss above its trigger", async () => {
+      // The market is above 18 USDC per SOL
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [await tradeIx(stopLossKey)],
+        (err) => {
+          const parsedError = parseTranactionError(err);
+          assert.equal(
+            parsedError.msg,
+            "The market has not fallen to the stop loss trigger"
+          );
+        }
+      );
+      const [ocoPair, collateralAccount] = await Promise.all([
+        program.account.ocoPair.fetch(ocoPairKey),
+        tokenProgram.account.account.fetch(collateralAddress),
+      ]);
+      assert.ok(ocoPair.filledBy.equals(web3.PublicKey.default));
+      assert.ok(collateralAccount.owner.equals(ocoPairKey));
+    });
+    it("should disable the sibling once a strategy fills", async () => {
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [await tradeIx(takeProfitKey)],
+        (err) => {
+          console.error(err);
+          assert.ok(false);
+        }
+      );
+      const [ocoPair, collateralAccount] = await Promise.all([
+        program.account.ocoPair.fetch(ocoPairKey),
+        tokenProgram.account.account.fetch(collateralAddress),
+      ]);
+      assert.ok(ocoPair.filledBy.equals(takeProfitKey));
+      assert.ok(collateralAccount.owner.equals(takeProfitKey));
+
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [await tradeIx(stopLossKey)],
+        (err) => {
+          const parsedError = parseTranactionError(err);
+          assert.equal(
+            parsedError.msg,
+            "The strategy's OCO sibling has already filled"
+          );
+        }
+      );
+    });
+  });
+  describe("Chained strategies", () => {
+    let upstreamKey: web3.PublicKey;
+    let downstreamKey: web3.PublicKey;
+    let downstreamCollateral: web3.PublicKey;
+    beforeEach(async () => {
+      // Sell SOL for at least 20 USDC each, then buy SOL with that USDC below
+      //  80 USDC each
+      ({ boundedStrategyKey: upstreamKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(20_000_000),
+        quoteAddress,
+        baseAddress,
+        serumMarket.baseMintAddress,
+        USDC_MINT,
+        baseTransferAmount
+      ));
+      ({ boundedStrategyKey: downstreamKey } = await initBoundedStrategy(
+        nonce,
+        new anchor.BN(80_000_000),
+        new anchor.BN(1_000_000_000),
+        baseAddress,
+        quoteAddress,
+        USDC_MINT,
+        serumMarket.baseMintAddress,
+        new BN(0)
+      ));
+      const [upstream, downstream] = await Promise.all([
+        program.account.boundedStrategyV2.fetch(upstreamKey),
+        program.account.boundedStrategyV2.fetch(downstreamKey),
+      ]);
+      boundedStrategy = upstream;
+      downstreamCollateral = downstream.collateralAccount;
+      await program.methods
+        .chainStrategiesV2()
+        .accounts({
+          payer: payerKey,
+          upstream: upstreamKey,
+          downstream: downstreamKey,
+          depositAccount: upstream.depositAddress,
+          downstreamCollateral,
+          reclaimAccount: upstream.reclaimAddress,
+          downstreamReclaimAccount: downstream.reclaimAddress,
+        })
+        .rpc();
+    });
+    it("should link the strategies", async () => {
+      const [upstream, downstream] = await Promise.all([
+        program.account.boundedStrategyV2.fetch(upstreamKey),
+        program.account.boundedStrategyV2.fetch(downstreamKey),
+      ]);
+      assert.ok(upstream.extension.downstream.strategy.equals(downstreamKey));
+      assert.ok(upstream.depositAddress.equals(downstreamCollateral));
+      assert.ok(downstream.upstream.equals(upstreamKey));
+      assert.ok(downstream.awaitingUpstream);
+    });
+    it("should reject chaining the strategies into a cycle", async () => {
+      const [upstream, downstream] = await Promise.all([
+        program.account.boundedStrategyV2.fetch(upstreamKey),
+        program.account.boundedStrategyV2.fetch(downstreamKey),
+      ]);
+      const ix = await program.methods
+        .chainStrategiesV2()
+        .accounts({
+          payer: payerKey,
+          upstream: downstreamKey,
+          downstream: upstreamKey,
+          depositAccount: downstream.depositAddress,
+          downstreamCollateral: upstream.collateralAccount,
+          reclaimAccount: downstream.reclaimAddress,
+          downstreamReclaimAccount: upstream.reclaimAddress,
+        })
+        .instruction();
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [ix],
+        (err) => {
+          const parsedError = parseTranactionError(err);
+          assert.equal(
+            parsedError.msg,
+            "Upstream strategy cannot itself be chained behind an upstream strategy"
+          );
+        }
+      );
+    });
+    it("should let a deposit fund the downstream strategy", async () => {
+      const downstream = await program.account.boundedStrategyV2.fetch(
+        downstreamKey
+      );
+      const ix = await program.methods
+        .depositV2(new BN(1_000_000))
+        .accounts({
+          payer: payerKey,
+          strategy: downstreamKey,
+          collateralAccount: downstreamCollateral,
+          sourceAccount: quoteAddress,
+          reclaimAccount: downstream.reclaimAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .instruction();
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [ix],
+        (err) => {
+          console.error(err);
+          assert.ok(false);
+        }
+      );
+
+      const [downstreamAfter, collateralAccount] = await Promise.all([
+        program.account.boundedStrategyV2.fetch(downstreamKey),
+        tokenProgram.account.account.fetch(downstreamCollateral),
+      ]);
+      assert.ok(!downstreamAfter.awaitingUpstream);
+      assert.equal(collateralAccount.amount.toString(), "1000000");
+    });
+    it("should fund the downstream strategy with the proceeds", async () => {
+      const downstreamTradeIx = async () => {
+        const remainingAccounts = await openBookTradeAccounts(
+          serumMarket,
+          downstreamCollateral,
+          baseAddress,
+          openOrdersKeypair.publicKey,
+          payerKey
+        );
+        return program.methods
+          .boundedTradeV2(additionalData)
+          .accounts({
+            payer: program.provider.publicKey,
+            strategy: downstreamKey,
+            orderPayer: downstreamCollateral,
+            depositAccount: baseAddress,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .remainingAccounts(remainingAccounts)
+          .instruction();
+      };
+      // The downstream strategy cannot trade before the upstream fills
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [await downstreamTradeIx()],
+        (err) => {
+          const parsedError = parseTranactionError(err);
+          assert.equal(
+            parsedError.msg,
+            "The strategy's upstream strategy has not filled yet"
+          );
+        }
+      );
+
+      const remainingAccounts = await openBookTradeAccounts(
+        serumMarket,
+        boundedStrategy.collateralAccount,
+        downstreamCollateral,
+        openOrdersKeypair.publicKey,
+        payerKey
+      );
+      const ix = await program.methods
+        .boundedTradeV2(additionalData)
+        .accounts({
+          payer: program.provider.publicKey,
+          strategy: upstreamKey,
+          orderPayer: boundedStrategy.collateralAccount,
+          depositAccount: downstreamCollateral,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: downstreamKey, isWritable: true, isSigner: false },
+          ...remainingAccounts,
+        ])
+        .instruction();
+      await compileAndSendV0Tx(
+        program.provider,
+        payerKeypair,
+        lookupTableAddress,
+        [ix],
+        (err) => {
+          console.error(err);
+          assert.ok(false);
+        }
+      );
+
+      const [downstream, collateralAccount] = await Promise.all([
+        program.account.boundedStrategyV2.fetch(downstreamKey),
+        tokenProgram.account.account.fetch(downstreamCollateral),
+      ]);
+      assert.ok(!downstream.awaitingUpstream);
+      assert.ok(collateralAccount.amount.gtn(0));
+    });
+  });
 });
@@ -0,0 +1,223 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program, web3 } from "@coral-xyz/anchor";
+import { splTokenProgram, SPL_TOKEN_PROGRAM_ID } from "@coral-xyz/spl-token";
+import { Market, OpenOrders } from "@project-serum/serum";
+import { WRAPPED_SOL_MINT } from "@project-serum/serum/lib/token-instructions";
+import { assert } from "chai";
+import {
+  encodeAdditionalData,
+  GridStrategy,
+  parseTranactionError,
+} from "../packages/poseidon/src";
+import { openBookTradeAccounts } from "../packages/poseidon/src/dexes";
+import { deriveAllGridStrategyKeys } from "../packages/poseidon/src/pdas";
+import { Poseidon } from "../target/types/poseidon";
+import {
+  createAssociatedTokenInstruction,
+  DEX_ID,
+  SOL_USDC_SERUM_MARKET,
+  USDC_MINT,
+} from "./utils";
+
+/**
+ * SerumMarket is in the current state Bids and Asks
+ * [23.709,29.329,"5c9d","7291"] [23.727,204.945,"5caf","032091"]
+ */
+
+describe("GridStrategy", () => {
+  const program = anchor.workspace.Poseidon as Program<Poseidon>;
+  const payerKey = program.provider.publicKey;
+  const tokenProgram = splTokenProgram({ programId: SPL_TOKEN_PROGRAM_ID });
+
+  // A $20 to $25 SOL/USDC band with levels $1 apart, each range trading 0.1 SOL
+  const lowerPrice = new BN(20_000_000);
+  const upperPrice = new BN(25_000_000);
+  const priceDenominator = new BN(1_000_000_000);
+  const gridLevels = 6;
+  const levelSize = new BN(100_000_000);
+  // The market trades around $23.7, so the ranges below $22 start out sold
+  const soldRanges = 0b11;
+  const baseTransferAmount = new BN(1_000_000_000);
+  const quoteTransferAmount = new BN(10_000_000);
+
+  let serumMarket: Market;
+  let quoteAddress: web3.PublicKey;
+  let baseAddress: web3.PublicKey;
+  let openOrdersKeypair: web3.Keypair;
+  let gridStrategyKey: web3.PublicKey;
+  let gridStrategy: GridStrategy;
+  let nonce = 0;
+
+  before(async () => {
+    serumMarket = await Market.load(
+      program.provider.connection,
+      SOL_USDC_SERUM_MARKET,
+      {},
+      DEX_ID
+    );
+    const [
+      { instruction, associatedAddress },
+      { instruction: baseMintAtaIx, associatedAddress: baseAta },
+    ] = await Promise.all([
+      createAssociatedTokenInstruction(program.provider, USDC_MINT),
+      createAssociatedTokenInstruction(program.provider, WRAPPED_SOL_MINT),
+    ]);
+    quoteAddress = associatedAddress;
+    baseAddress = baseAta;
+    try {
+      await program.provider.sendAndConfirm(
+        new web3.Transaction().add(instruction).add(baseMintAtaIx)
+      );
+    } catch (err) {}
+
+    await program.provider.connection.requestAirdrop(
+      payerKey,
+      baseTransferAmount.muln(10).toNumber()
+    );
+    const transaction = new web3.Transaction();
+    // Create an OpenOrders account for the executor
+    openOrdersKeypair = new web3.Keypair();
+    transaction.add(
+      await OpenOrders.makeCreateAccountTransaction(
+        program.provider.connection,
+        serumMarket.address,
+        payerKey,
+        openOrdersKeypair.publicKey,
+        serumMarket.programId
+      )
+    );
+    transaction.add(
+      await tokenProgram.methods
+        .mintTo(quoteTransferAmount.muln(10))
+        .accounts({ mint: USDC_MINT, account: quoteAddress, owner: payerKey })
+        .instruction()
+    );
+    // Move SOL to wrapped SOL
+    transaction.add(
+      web3.SystemProgram.transfer({
+        fromPubkey: payerKey,
+        toPubkey: baseAddress,
+        lamports: baseTransferAmount.muln(5).toNumber(),
+      })
+    );
+    transaction.add(
+      await tokenProgram.methods
+        .syncNative()
+        .accounts({ account: baseAddress })
+        .instruction()
+    );
+    await program.provider.sendAndConfirm(transaction, [openOrdersKeypair]);
+  });
+
+  beforeEach(async () => {
+    nonce += 1;
+    const reclaimDate = new BN(new Date().getTime() / 1_000 + 3600 + nonce);
+    const {
+      gridStrategy: _gridStrategyKey,
+      baseAccount,
+      quoteAccount,
+    } = deriveAllGridStrategyKeys(program, WRAPPED_SOL_MINT, USDC_MINT, {
+      lowerPrice,
+      upperPrice,
+      reclaimDate,
+    });
+    gridStrategyKey = _gridStrategyKey;
+    await program.methods
+      .initGridStrategy({
+        baseTransferAmount,
+        quoteTransferAmount,
+        lowerPrice,
+        upperPrice,
+        priceDenominator,
+        gridLevels,
+        levelSize,
+        startDate: new BN(0),
+        reclaimDate,
+        soldRanges,
+      })
+      .accounts({
+        payer: payerKey,
+        baseMint: WRAPPED_SOL_MINT,
+        quoteMint: USDC_MINT,
+        strategy: gridStrategyKey,
+        baseAccount,
+        quoteAccount,
+        baseReclaimAccount: baseAddress,
+        quoteReclaimAccount: quoteAddress,
+        tokenProgram: SPL_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    gridStrategy = await program.account.gridStrategy.fetch(gridStrategyKey);
+  });
+
+  const gridTrade = async (range: number, sellsBase: boolean) => {
+    const remainingAccounts = await openBookTradeAccounts(
+      serumMarket,
+      sellsBase ? gridStrategy.baseAccount : gridStrategy.quoteAccount,
+      sellsBase ? gridStrategy.quoteAccount : gridStrategy.baseAccount,
+      openOrdersKeypair.publicKey,
+      payerKey
+    );
+    const additionalData = encodeAdditionalData({
+      inputHint: null,
+      searchIterations: 0,
+      legs: [
+        // @ts-ignore
+        { openBookV3: { baseDecimals: serumMarket._baseSplTokenDecimals } },
+      ],
+      branchLegCounts: [],
+    });
+    await program.methods
+      .gridTrade(range, additionalData)
+      .accounts({
+        payer: payerKey,
+        strategy: gridStrategyKey,
+        baseAccount: gridStrategy.baseAccount,
+        quoteAccount: gridStrategy.quoteAccount,
+        tokenProgram: SPL_TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+  };
+
+  it("should store the grid and fund both sides", async () => {
+    assert.equal(gridStrategy.gridLevels, gridLevels);
+    assert.equal(gridStrategy.soldRanges, soldRanges);
+    assert.equal(gridStrategy.levelSize.toString(), levelSize.toString());
+    const [baseAccount, quoteAccount] = await Promise.all([
+      tokenProgram.account.account.fetch(gridStrategy.baseAccount),
+      tokenProgram.account.account.fetch(gridStrategy.quoteAccount),
+    ]);
+    assert.equal(baseAccount.amount.toString(), baseTransferAmount.toString());
+    assert.equal(
+      quoteAccount.amount.toString(),
+      quoteTransferAmount.toString()
+    );
+  });
+
+  it("should sell a range when the bid is above its upper level", async () => {
+    // The $22 to $23 range sells into the $23.709 bid
+    await gridTrade(2, true);
+    const gridAfter = await program.account.gridStrategy.fetch(
+      gridStrategyKey
+    );
+    assert.equal(gridAfter.soldRanges, soldRanges | 0b100);
+    const quoteAccount = await tokenProgram.account.account.fetch(
+      gridStrategy.quoteAccount
+    );
+    // At least $2.30 for 0.1 SOL
+    assert.ok(quoteAccount.amount.sub(quoteTransferAmount).gten(2_300_000));
+  });
+
+  it("should not buy back a range above its lower level", async () => {
+    // The $21 to $22 range would buy 0.1 SOL at the $23.727 ask
+    try {
+      await gridTrade(1, false);
+      assert.ok(false);
+    } catch (err) {
+      const parsedError = parseTranactionError(err);
+      assert.equal(parsedError.msg, "Market price is out of bounds");
+    }
+  });
+});
@@ -28,6 +28,7 @@ describe("InitBoundedStrategyV2", () => {
 
   let boundPriceNumerator = new anchor.BN(95_700_000);
   let boundPriceDenominator = new anchor.BN(1_000_000_000);
+  let startDate = new anchor.BN(0);
   let reclaimDate = new anchor.BN(new Date().getTime() / 1_000 + 3600);
   let reclaimAddress: web3.PublicKey;
   let depositAddress: web3.PublicKey;
@@ -77,6 +78,7 @@ describe("InitBoundedStrategyV2", () => {
     timesRun += 1;
     boundPriceNumerator = new anchor.BN(95_700_000);
     boundPriceDenominator = new anchor.BN(1_000_000_000);
+    startDate = new anchor.BN(0);
     reclaimDate = new anchor.BN(new Date().getTime() / 1_000 + 3600 + timesRun);
     transferAmount = new BN(10_000_000);
   });
@@ -87,6 +89,7 @@ describe("InitBoundedStrategyV2", () => {
       await deriveAllBoundedStrategyKeysV2(program, USDC_MINT, {
         boundPriceNumerator,
         boundPriceDenominator,
+        startDate,
         reclaimDate,
       });
     const reclaimTokenAccountBefore = await tokenProgram.account.account.fetch(
@@ -98,7 +101,14 @@ describe("InitBoundedStrategyV2", () => {
         transferAmount,
         boundPriceNumerator,
         boundPriceDenominator,
-        reclaimDate
+        startDate,
+        reclaimDate,
+        [],
+        0,
+        0,
+        new BN(0),
+        new BN(0),
+        new BN(0)
       )
       .accounts({
         payer: program.provider.publicKey,
@@ -140,6 +150,7 @@ describe("InitBoundedStrategyV2", () => {
       boundedStrategy.boundedPriceDenominator.toString(),
       boundPriceDenominator.toString()
     );
+    assert.equal(boundedStrategy.startDate.toString(), startDate.toString());
     assert.equal(
       boundedStrategy.reclaimDate.toString(),
       reclaimDate.toString()
@@ -169,4 +180,238 @@ describe("InitBoundedStrategyV2", () => {
       transferAmount.toString()
     );
   });
+
+  it("Should store the price tiers of a laddered BoundedStrategyV2", async () => {
+    const { boundedStrategy: boundedStrategyKey, collateralAccount } =
+      await deriveAllBoundedStrategyKeysV2(program, USDC_MINT, {
+        boundPriceNumerator,
+        boundPriceDenominator,
+        startDate,
+        reclaimDate,
+      });
+    // Buy 25% at 95.7, 25% at 94.7 and 50% at 93.7
+    const priceTiers = [
+      {
+        boundedPriceNumerator: new BN(95_700_000),
+        boundedPriceDenominator: new BN(1_000_000_000),
+        remainingAmount: transferAmount.divn(4),
+      },
+      {
+        boundedPriceNumerator: new BN(94_700_000),
+        boundedPriceDenominator: new BN(1_000_000_000),
+        remainingAmount: transferAmount.divn(4),
+      },
+      {
+        boundedPriceNumerator: new BN(93_700_000),
+        boundedPriceDenominator: new BN(1_000_000_000),
+        remainingAmount: transferAmount.divn(2),
+      },
+    ];
+
+    const instruction = await program.methods
+      .initBoundedStrategyV2(
+        transferAmount,
+        boundPriceNumerator,
+        boundPriceDenominator,
+        startDate,
+        reclaimDate,
+        priceTiers,
+        0,
+        0,
+        new BN(0),
+        new BN(0),
+        new BN(0)
+      )
+      .accounts({
+        payer: program.provider.publicKey,
+        collateralAccount,
+        mint: USDC_MINT,
+        strategy: boundedStrategyKey,
+        reclaimAccount: reclaimAddress,
+        depositAccount: depositAddress,
+        tokenProgram: SPL_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .instruction();
+    try {
+      const tx = new web3.Transaction().add(instruction);
+      await program.provider.sendAndConfirm(tx);
+    } catch (err) {
+      console.error(err);
+      assert.ok(false);
+    }
+
+    const boundedStrategy = await program.account.boundedStrategyV2.fetch(
+      boundedStrategyKey
+    );
+    priceTiers.forEach((tier, index) => {
+      const storedTier = boundedStrategy.priceTiers[index];
+      assert.equal(
+        storedTier.boundedPriceNumerator.toString(),
+        tier.boundedPriceNumerator.toString()
+      );
+      assert.equal(
+        storedTier.boundedPriceDenominator.toString(),
+        tier.boundedPriceDenominator.toString()
+      );
+      assert.equal(
+        storedTier.remainingAmount.toString(),
+        tier.remainingAmount.toString()
+      );
+    });
+    // Unused tiers are left empty
+    const unusedTier = boundedStrategy.priceTiers[priceTiers.length];
+    assert.equal(unusedTier.boundedPriceDenominator.toString(), "0");
+  });
+
+  it("Should error when the price tiers exceed the transfer amount", async () => {
+    const { boundedStrategy: boundedStrategyKey, collateralAccount } =
+      await deriveAllBoundedStrategyKeysV2(program, USDC_MINT, {
+        boundPriceNumerator,
+        boundPriceDenominator,
+        startDate,
+        reclaimDate,
+      });
+    const priceTiers = [
+      {
+        boundedPriceNumerator: boundPriceNumerator,
+        boundedPriceDenominator: boundPriceDenominator,
+        remainingAmount: transferAmount.addn(1),
+      },
+    ];
+
+    const instruction = await program.methods
+      .initBoundedStrategyV2(
+        transferAmount,
+        boundPriceNumerator,
+        boundPriceDenominator,
+        startDate,
+        reclaimDate,
+        priceTiers,
+        0,
+        0,
+        new BN(0),
+        new BN(0),
+        new BN(0)
+      )
+      .accounts({
+        payer: program.provider.publicKey,
+        collateralAccount,
+        mint: USDC_MINT,
+        strategy: boundedStrategyKey,
+        reclaimAccount: reclaimAddress,
+        depositAccount: depositAddress,
+        tokenProgram: SPL_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .instruction();
+    try {
+      const tx = new web3.Transaction().add(instruction);
+      await program.provider.sendAndConfirm(tx);
+      assert.ok(false);
+    } catch (err) {
+      const parsedError = parseTranactionError(err);
+      assert.equal(
+        parsedError.msg,
+        "Price tier amounts cannot exceed the transfer amount"
+      );
+    }
+  });
+
+  it("Should error when the start date is not before the reclaim date", async () => {
+    startDate = reclaimDate;
+    const { boundedStrategy: boundedStrategyKey, collateralAccount } =
+      await deriveAllBoundedStrategyKeysV2(program, USDC_MINT, {
+        boundPriceNumerator,
+        boundPriceDenominator,
+        startDate,
+        reclaimDate,
+      });
+
+    const instruction = await program.methods
+      .initBoundedStrategyV2(
+        transferAmount,
+        boundPriceNumerator,
+        boundPriceDenominator,
+        startDate,
+        reclaimDate,
+        [],
+        0,
+        0,
+        new BN(0),
+        new BN(0),
+        new BN(0)
+      )
+      .accounts({
+        payer: program.provider.publicKey,
+        collateralAccount,
+        mint: USDC_MINT,
+        strategy: boundedStrategyKey,
+        reclaimAccount: reclaimAddress,
+        depositAccount: depositAddress,
+        tokenProgram: SPL_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .instruction();
+    try {
+      const tx = new web3.Transaction().add(instruction);
+      await program.provider.sendAndConfirm(tx);
+      assert.ok(false);
+    } catch (err) {
+      const parsedError = parseTranactionError(err);
+      assert.equal(
+        parsedError.msg,
+        "Start date must be before the reclaim date"
+      );
+    }
+  });
+
+  it("Should create strategies that differ only in their start date", async () => {
+    const startDates = [
+      new anchor.BN(0),
+      new anchor.BN(new Date().getTime() / 1_000 + 1800),
+    ];
+    const strategyKeys: web3.PublicKey[] = [];
+    for (const startDate of startDates) {
+      const { boundedStrategy: boundedStrategyKey, collateralAccount } =
+        await deriveAllBoundedStrategyKeysV2(program, USDC_MINT, {
+          boundPriceNumerator,
+          boundPriceDenominator,
+          startDate,
+          reclaimDate,
+        });
+      await program.methods
+        .initBoundedStrategyV2(
+          transferAmount,
+          boundPriceNumerator,
+          boundPriceDenominator,
+          startDate,
+          reclaimDate,
+          [],
+          0,
+          0,
+          new BN(0),
+          new BN(0),
+          new BN(0)
+        )
+        .accounts({
+          payer: program.provider.publicKey,
+          collateralAccount,
+          mint: USDC_MINT,
+          strategy: boundedStrategyKey,
+          reclaimAccount: reclaimAddress,
+          depositAccount: depositAddress,
+          tokenProgram: SPL_TOKEN_PROGRAM_ID,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+      strategyKeys.push(boundedStrategyKey);
+    }
+
+    assert.notEqual(strategyKeys[0].toString(), strategyKeys[1].toString());
+    const scheduled = await program.account.boundedStrategyV2.fetch(
+      strategyKeys[1]
+    );
+    assert.equal(scheduled.startDate.toString(), startDates[1].toString());
+  });
 });
@@ -4,7 +4,10 @@ import { splTokenProgram, SPL_TOKEN_PROGRAM_ID } from "@coral-xyz/spl-token";
 import { Program, web3 } from "@coral-xyz/anchor";
 import { Market, OpenOrders } from "@project-serum/serum";
 import { assert } from "chai";
-import { parseTranactionError } from "../packages/poseidon/src";
+import {
+  encodeAdditionalData,
+  parseTranactionError,
+} from "../packages/poseidon/src";
 import { openBookTradeAccounts } from "../packages/poseidon/src/dexes";
 import { deriveAllBoundedStrategyKeysV2 } from "../packages/poseidon/src/pdas";
 import { IDL, Poseidon } from "../target/types/poseidon";
@@ -36,6 +39,7 @@ describe("OpenBook + Raydium Trade", () => {
 
   let boundPriceNumerator = new anchor.BN(95_700_000);
   let boundPriceDenominator = new anchor.BN(1_000_000_000);
+  let startDate = new anchor.BN(0);
   let reclaimDate = new anchor.BN(new Date().getTime() / 1_000 + 3600);
   let reclaimAddress: web3.PublicKey;
   let depositAddress: web3.PublicKey;
@@ -54,10 +58,16 @@ describe("OpenBook + Raydium Trade", () => {
       {},
       OPEN_BOOK_DEX_ID
     );
-    additionalData = new BN(
-      // @ts-ignore
-      serumMarket._baseSplTokenDecimals
-    ).toArrayLike(Buffer, "le", 1);
+    additionalData = encodeAdditionalData({
+      inputHint: null,
+      searchIterations: 0,
+      legs: [
+        // @ts-ignore
+        { openBookV3: { baseDecimals: serumMarket._baseSplTokenDecimals } },
+        { raydium: {} },
+      ],
+      branchLegCounts: [],
+    });
     await program.provider.connection.requestAirdrop(
       payerKey,
       10_000_000_000_000
@@ -183,6 +193,7 @@ describe("OpenBook + Raydium Trade", () => {
         {
           boundPriceNumerator,
           boundPriceDenominator,
+          startDate,
           reclaimDate,
         }
       ));
@@ -195,7 +206,14 @@ describe("OpenBook + Raydium Trade", () => {
         transferAmount,
         boundPriceNumerator,
         boundPriceDenominator,
-        reclaimDate
+        startDate,
+        reclaimDate,
+        [],
+        0,
+        0,
+        new BN(0),
+        new BN(0),
+        new BN(0)
       )
       .accounts({
         payer: program.provider.publicKey,
@@ -277,10 +295,16 @@ describe("OpenBook + Raydium Trade", () => {
     let traderUsdcKey: web3.PublicKey;
     let traderProgram: Program<Poseidon>;
     before(async () => {
-      additionalData = new BN(
-        // @ts-ignore
-        serumMarket._baseSplTokenDecimals
-      ).toArrayLike(Buffer, "le", 1);
+      additionalData = encodeAdditionalData({
+        inputHint: null,
+        searchIterations: 0,
+        legs: [
+          // @ts-ignore
+          { openBookV3: { baseDecimals: serumMarket._baseSplTokenDecimals } },
+          { raydium: {} },
+        ],
+        branchLegCounts: [],
+      });
       // Create a new payer for trading
       const signature = await program.provider.connection.requestAirdrop(
         traderKeypair.publicKey,
@@ -0,0 +1,231 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program, web3 } from "@coral-xyz/anchor";
+import { splTokenProgram, SPL_TOKEN_PROGRAM_ID } from "@coral-xyz/spl-token";
+import { Market, OpenOrders } from "@project-serum/serum";
+import { WRAPPED_SOL_MINT } from "@project-serum/serum/lib/token-instructions";
+import { assert } from "chai";
+import {
+  encodeAdditionalData,
+  RebalanceStrategy,
+  parseTranactionError,
+} from "../packages/poseidon/src";
+import { openBookTradeAccounts } from "../packages/poseidon/src/dexes";
+import { deriveAllRebalanceStrategyKeys } from "../packages/poseidon/src/pdas";
+import { Poseidon } from "../target/types/poseidon";
+import {
+  createAssociatedTokenInstruction,
+  DEX_ID,
+  SOL_USDC_SERUM_MARKET,
+  USDC_MINT,
+} from "./utils";
+
+/**
+ * SerumMarket is in the current state Bids and Asks
+ * [23.709,29.329,"5c9d","7291"] [23.727,204.945,"5caf","032091"]
+ */
+
+describe("RebalanceStrategy", () => {
+  const program = anchor.workspace.Poseidon as Program<Poseidon>;
+  const payerKey = program.provider.publicKey;
+  const tokenProgram = splTokenProgram({ programId: SPL_TOKEN_PROGRAM_ID });
+
+  // Hold SOL and USDC 50/50 by value, valuing SOL at $23.70
+  const referencePriceNumerator = new BN(23_700_000);
+  const referencePriceDenominator = new BN(1_000_000_000);
+  const targetBaseWeightBps = 5_000;
+  const toleranceBps = 200;
+  const maxDeviationBps = 100;
+  const baseTransferAmount = new BN(1_000_000_000);
+
+  let serumMarket: Market;
+  let quoteAddress: web3.PublicKey;
+  let baseAddress: web3.PublicKey;
+  let openOrdersKeypair: web3.Keypair;
+  let rebalanceStrategyKey: web3.PublicKey;
+  let rebalanceStrategy: RebalanceStrategy;
+  let nonce = 0;
+
+  before(async () => {
+    serumMarket = await Market.load(
+      program.provider.connection,
+      SOL_USDC_SERUM_MARKET,
+      {},
+      DEX_ID
+    );
+    const [
+      { instruction, associatedAddress },
+      { instruction: baseMintAtaIx, associatedAddress: baseAta },
+    ] = await Promise.all([
+      createAssociatedTokenInstruction(program.provider, USDC_MINT),
+      createAssociatedTokenInstruction(program.provider, WRAPPED_SOL_MINT),
+    ]);
+    quoteAddress = associatedAddress;
+    baseAddress = baseAta;
+    try {
+      await program.provider.sendAndConfirm(
+        new web3.Transaction().add(instruction).add(baseMintAtaIx)
+      );
+    } catch (err) {}
+
+    await program.provider.connection.requestAirdrop(
+      payerKey,
+      baseTransferAmount.muln(10).toNumber()
+    );
+    const transaction = new web3.Transaction();
+    // Create an OpenOrders account for the executor
+    openOrdersKeypair = new web3.Keypair();
+    transaction.add(
+      await OpenOrders.makeCreateAccountTransaction(
+        program.provider.connection,
+        serumMarket.address,
+        payerKey,
+        openOrdersKeypair.publicKey,
+        serumMarket.programId
+      )
+    );
+    transaction.add(
+      await tokenProgram.methods
+        .mintTo(new BN(1_000_000_000))
+        .accounts({ mint: USDC_MINT, account: quoteAddress, owner: payerKey })
+        .instruction()
+    );
+    // Move SOL to wrapped SOL
+    transaction.add(
+      web3.SystemProgram.transfer({
+        fromPubkey: payerKey,
+        toPubkey: baseAddress,
+        lamports: baseTransferAmount.muln(5).toNumber(),
+      })
+    );
+    transaction.add(
+      await tokenProgram.methods
+        .syncNative()
+        .accounts({ account: baseAddress })
+        .instruction()
+    );
+    await program.provider.sendAndConfirm(transaction, [openOrdersKeypair]);
+  });
+
+  const initRebalanceStrategy = async (
+    quoteTransferAmount: BN,
+    priceNumerator = referencePriceNumerator
+  ) => {
+    nonce += 1;
+    const reclaimDate = new BN(new Date().getTime() / 1_000 + 3600 + nonce);
+    const {
+      rebalanceStrategy: _rebalanceStrategyKey,
+      baseAccount,
+      quoteAccount,
+    } = deriveAllRebalanceStrategyKeys(program, WRAPPED_SOL_MINT, USDC_MINT, {
+      reclaimDate,
+    });
+    rebalanceStrategyKey = _rebalanceStrategyKey;
+    await program.methods
+      .initRebalanceStrategy(
+        baseTransferAmount,
+        quoteTransferAmount,
+        priceNumerator,
+        referencePriceDenominator,
+        targetBaseWeightBps,
+        toleranceBps,
+        maxDeviationBps,
+        new BN(0),
+        reclaimDate
+      )
+      .accounts({
+        payer: payerKey,
+        baseMint: WRAPPED_SOL_MINT,
+        quoteMint: USDC_MINT,
+        strategy: rebalanceStrategyKey,
+        baseAccount,
+        quoteAccount,
+        baseReclaimAccount: baseAddress,
+        quoteReclaimAccount: quoteAddress,
+        tokenProgram: SPL_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    rebalanceStrategy = await program.account.rebalanceStrategy.fetch(
+      rebalanceStrategyKey
+    );
+  };
+
+  const rebalanceTrade = async (sellsBase: boolean) => {
+    const { baseAccount, quoteAccount } = rebalanceStrategy;
+    const remainingAccounts = await openBookTradeAccounts(
+      serumMarket,
+      sellsBase ? baseAccount : quoteAccount,
+      sellsBase ? quoteAccount : baseAccount,
+      openOrdersKeypair.publicKey,
+      payerKey
+    );
+    const additionalData = encodeAdditionalData({
+      inputHint: null,
+      searchIterations: 0,
+      legs: [
+        // @ts-ignore
+        { openBookV3: { baseDecimals: serumMarket._baseSplTokenDecimals } },
+      ],
+      branchLegCounts: [],
+    });
+    await program.methods
+      .rebalanceTrade(additionalData)
+      .accounts({
+        payer: payerKey,
+        strategy: rebalanceStrategyKey,
+        baseAccount,
+        quoteAccount,
+        tokenProgram: SPL_TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+  };
+
+  it("should sell overweight base back toward the target", async () => {
+    // 1 SOL and $10 is about 70% SOL by value
+    const quoteTransferAmount = new BN(10_000_000);
+    await initRebalanceStrategy(quoteTransferAmount);
+    await rebalanceTrade(true);
+    const [baseAccount, quoteAccount] = await Promise.all([
+      tokenProgram.account.account.fetch(rebalanceStrategy.baseAccount),
+      tokenProgram.account.account.fetch(rebalanceStrategy.quoteAccount),
+    ]);
+    // Never sells past the target, and gets at least $23.463 per SOL sold
+    const baseSold = baseTransferAmount.sub(baseAccount.amount);
+    assert.ok(baseSold.gtn(0));
+    assert.ok(baseSold.lten(289_029_536));
+    const quoteGained = quoteAccount.amount.sub(quoteTransferAmount);
+    assert.ok(
+      quoteGained
+        .mul(referencePriceDenominator)
+        .gte(baseSold.muln(23_463_000))
+    );
+  });
+
+  it("should not trade within the tolerance band", async () => {
+    // 1 SOL and $23.70 is on target
+    await initRebalanceStrategy(new BN(23_700_000));
+    try {
+      await rebalanceTrade(true);
+      assert.ok(false);
+    } catch (err) {
+      const parsedError = parseTranactionError(err);
+      assert.equal(
+        parsedError.msg,
+        "Portfolio is within its tolerance of the target weight"
+      );
+    }
+  });
+
+  it("should not sell below the max deviation from reference", async () => {
+    // Valuing SOL at $25 only sells it for $24.75 or more
+    await initRebalanceStrategy(new BN(10_000_000), new BN(25_000_000));
+    try {
+      await rebalanceTrade(true);
+      assert.ok(false);
+    } catch (err) {
+      const parsedError = parseTranactionError(err);
+      assert.equal(parsedError.msg, "Market price is out of bounds");
+    }
+  });
+});
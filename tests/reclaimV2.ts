@@ -3,8 +3,15 @@ import { splTokenProgram, SPL_TOKEN_PROGRAM_ID } from "@coral-xyz/spl-token";
 import { WRAPPED_SOL_MINT } from "@project-serum/serum/lib/token-instructions";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { assert } from "chai";
-import { BoundedStrategyV2 } from "../packages/poseidon/src";
-import { deriveAllBoundedStrategyKeysV2 } from "../packages/poseidon/src/pdas";
+import {
+  BoundedStrategyV2,
+  parseTranactionError,
+} from "../packages/poseidon/src";
+import { deriveVaultSigner } from "../packages/poseidon/src/dexes";
+import {
+  deriveAllBoundedStrategyKeysV2,
+  deriveOpenOrders,
+} from "../packages/poseidon/src/pdas";
 import { Poseidon } from "../target/types/poseidon";
 import {
   createAssociatedTokenInstruction,
@@ -14,7 +21,7 @@ import {
   USDC_MINT,
   wait,
 } from "./utils";
-import { Market } from "@project-serum/serum";
+import { DexInstructions, Market, OpenOrders } from "@project-serum/serum";
 import { Transaction } from "@solana/web3.js";
 
 describe("ReclaimV2", () => {
@@ -26,6 +33,7 @@ describe("ReclaimV2", () => {
 
   let boundPriceNumerator = new BN(95_700_000);
   let boundPriceDenominator = new BN(1_000_000_000);
+  let startDate = new BN(0);
   let reclaimAddress: web3.PublicKey;
   let depositAddress: web3.PublicKey;
   let transferAmount = new BN(10_000_000);
@@ -36,13 +44,17 @@ describe("ReclaimV2", () => {
   let boundedStrategy: BoundedStrategyV2;
   let boundedStrategyKey: web3.PublicKey, collateralAddress: web3.PublicKey;
 
-  const initBoundStrat = async (_reclaimDate: BN) => {
+  const initBoundStrat = async (
+    _reclaimDate: BN,
+    _boundPriceNumerator = boundPriceNumerator
+  ) => {
     const {
       boundedStrategy: _boundedStrategyKey,
       collateralAccount: _collateralAccount,
     } = await deriveAllBoundedStrategyKeysV2(program, USDC_MINT, {
-      boundPriceNumerator,
+      boundPriceNumerator: _boundPriceNumerator,
       boundPriceDenominator,
+      startDate,
       reclaimDate: _reclaimDate,
     });
     boundedStrategyKey = _boundedStrategyKey;
@@ -50,9 +62,16 @@ describe("ReclaimV2", () => {
     const ix = await program.methods
       .initBoundedStrategyV2(
         transferAmount,
-        boundPriceNumerator,
+        _boundPriceNumerator,
         boundPriceDenominator,
-        _reclaimDate
+        startDate,
+        _reclaimDate,
+        [],
+        0,
+        0,
+        new BN(0),
+        new BN(0),
+        new BN(0)
       )
       .accounts({
         payer: program.provider.publicKey,
@@ -237,4 +256,142 @@ describe("ReclaimV2", () => {
       assert.ok(true);
     });
   });
+
+  describe("Strategy has a resting order", () => {
+    let openOrdersKey: web3.PublicKey;
+    beforeEach(async () => {
+      // Bid 10 USDC per SOL, below the market so the order rests
+      await initBoundStrat(
+        new BN(new Date().getTime() / 1_000 + 5),
+        new BN(10_000_000)
+      );
+      [openOrdersKey] = deriveOpenOrders(program, boundedStrategyKey);
+      await program.methods
+        .initOpenOrdersV2(new BN(OpenOrders.getLayout(DEX_ID).span))
+        .accounts({
+          payer: payerKey,
+          strategy: boundedStrategyKey,
+          serumMarket: serumMarket.address,
+          depositAccount: depositAddress,
+          reclaimAccount: reclaimAddress,
+          openOrders: openOrdersKey,
+          dexProgram: DEX_ID,
+          systemProgram: web3.SystemProgram.programId,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      await program.methods
+        .placeRestingOrderV2()
+        .accounts({
+          payer: payerKey,
+          strategy: boundedStrategyKey,
+          orderPayer: collateralAddress,
+          openOrders: openOrdersKey,
+          serumMarket: serumMarket.address,
+          requestQueue: serumMarket.decoded.requestQueue,
+          eventQueue: serumMarket.decoded.eventQueue,
+          bids: serumMarket.bidsAddress,
+          asks: serumMarket.asksAddress,
+          coinVault: serumMarket.decoded.baseVault,
+          pcVault: serumMarket.decoded.quoteVault,
+          dexProgram: DEX_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      await wait(6000);
+    });
+
+    /**
+     * OpenBook only releases a cancelled order's funds once its cancel event
+     * is consumed, so the order is cancelled and the event queue cranked
+     * before reclaiming
+     */
+    it("should reclaim after the order is cancelled and cranked", async () => {
+      const reclaimIx = program.methods
+        .reclaimV2()
+        .accounts({
+          receiver: payerKey,
+          strategy: boundedStrategyKey,
+          collateralAccount: collateralAddress,
+          reclaimAccount: reclaimAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: openOrdersKey, isWritable: true, isSigner: false },
+          { pubkey: serumMarket.address, isWritable: true, isSigner: false },
+          {
+            pubkey: serumMarket.decoded.baseVault,
+            isWritable: true,
+            isSigner: false,
+          },
+          {
+            pubkey: serumMarket.decoded.quoteVault,
+            isWritable: true,
+            isSigner: false,
+          },
+          {
+            pubkey: await deriveVaultSigner(serumMarket),
+            isWritable: false,
+            isSigner: false,
+          },
+          { pubkey: DEX_ID, isWritable: false, isSigner: false },
+          { pubkey: depositAddress, isWritable: true, isSigner: false },
+        ]);
+      try {
+        await reclaimIx.rpc();
+        assert.ok(false);
+      } catch (err) {
+        const parsedError = parseTranactionError(err);
+        assert.equal(
+          parsedError.msg,
+          "Resting order must be cancelled and its events consumed before reclaiming"
+        );
+      }
+
+      await program.methods
+        .cancelRestingOrderV2()
+        .accounts({
+          payer: payerKey,
+          strategy: boundedStrategyKey,
+          openOrders: openOrdersKey,
+          serumMarket: serumMarket.address,
+          bids: serumMarket.bidsAddress,
+          asks: serumMarket.asksAddress,
+          eventQueue: serumMarket.decoded.eventQueue,
+          dexProgram: DEX_ID,
+        })
+        .rpc();
+      await program.provider.sendAndConfirm(
+        new Transaction().add(
+          DexInstructions.consumeEvents({
+            market: serumMarket.address,
+            eventQueue: serumMarket.decoded.eventQueue,
+            coinFee: depositAddress,
+            pcFee: reclaimAddress,
+            openOrdersAccounts: [openOrdersKey],
+            limit: 10,
+            programId: DEX_ID,
+          })
+        )
+      );
+
+      const reclaimAccountBefore = await tokenProgram.account.account.fetch(
+        reclaimAddress
+      );
+      await reclaimIx.rpc();
+      const [reclaimAccountAfter, openOrdersInfo, boundedStrategyInfo] =
+        await Promise.all([
+          tokenProgram.account.account.fetch(reclaimAddress),
+          program.provider.connection.getAccountInfo(openOrdersKey),
+          program.provider.connection.getAccountInfo(boundedStrategyKey),
+        ]);
+      assert.equal(
+        reclaimAccountAfter.amount.sub(reclaimAccountBefore.amount).toString(),
+        transferAmount.toString()
+      );
+      assert.ok(!openOrdersInfo);
+      assert.ok(!boundedStrategyInfo);
+    });
+  });
 });